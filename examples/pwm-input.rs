@@ -38,6 +38,7 @@ fn main() -> ! {
 
         // NOTE: this value may only be accurately observed at the CC2 interrupt.
         let _duty = monitor.get_duty_cycle();
+        let _frequency = monitor.get_frequency();
     }
 
     loop {
@@ -12,9 +12,8 @@ use stm32f4xx_hal::{
 };
 type Mono = stm32f4xx_hal::timer::MonoTimerUs<pac::TIM2>;
 
-// Uncomment if use SysTick as monotonic timer
-//use rtic_monotonics::systick::prelude::*;
-//systick_monotonic!(Mono, 1000);
+// Uncomment if use SysTick as monotonic timer (`rtic-systick` feature)
+//type Mono = stm32f4xx_hal::timer::MonoTimer<pac::SYST, 1000>;
 
 use rtic::app;
 
@@ -39,7 +38,7 @@ mod app {
         ctx.device.TIM2.monotonic_us(&mut ctx.core.NVIC, &clocks);
 
         // Uncomment if use SysTick as monotonic timer
-        //Mono::start(ctx.core.SYST, 48_000_000);
+        //ctx.core.SYST.monotonic::<1000>(&mut ctx.core.NVIC, &clocks);
 
         let gpioc = ctx.device.GPIOC.split();
         let led = gpioc.pc13.into_push_pull_output();
@@ -59,8 +58,3 @@ mod app {
         }
     }
 }
-
-//Add this to Cargo.toml if use SysTick as monotonic timer
-//[dependencies.rtic-monotonics]
-//version = "2.0"
-//features = ["cortex-m-systick"]
@@ -27,7 +27,7 @@ fn main() -> ! {
         .pclk2(84.MHz())
         .freeze();
 
-    assert!(clocks.is_pll48clk_valid());
+    assert!(clocks.is_48mhz_accurate());
 
     let mut delay = core.SYST.delay(&clocks);
 
@@ -9,10 +9,17 @@
 //! devices and some pins may be missing on some variants. The implementation has been split up and commented
 //! to show which pins are available on certain device variants but currently the library doesn't enforce this.
 //! To fully support the right pins would require 10+ more features for the various variants.
-//! ## Todo
-//! * Injected conversions
-//! * Analog watchdog config
-//! * Discontinuous mode
+//!
+//! Injected conversions are configured with [`config::InjectedSequence`] and [`config::InjectedTrigger`].
+//!
+//! Analog watchdog compares converted values with thresholds and raises [`Event::AnalogWatchdog`]
+//! when they are out of range, see `set_watchdog_thresholds` and `watch_channel`.
+//!
+//! In discontinuous mode ([`config::Discontinuous`]) each trigger converts only the next few
+//! channels of a long sequence, so channel groups can be synchronized to PWM phases.
+//!
+//! Resolution of 6, 8, 10 or 12 bits and data alignment are set in [`config::AdcConfig`], lower
+//! resolution allows higher sample rate. `sample_to_millivolts` and `max_sample` take both into account.
 //! # Examples
 //! ## One-shot conversion
 //! ```
@@ -31,6 +38,19 @@
 //! info!("pa3: {}mV", millivolts);
 //! ```
 //!
+//! `convert` waits for the result. `start_single_conversion` returns immediately, the result
+//! is taken with `read_ready` when it's done, for example in ADC interrupt after
+//! `adc.listen(Event::EndOfConversion)`:
+//! ```
+//! adc.start_single_conversion(&pa3, SampleTime::Cycles_480);
+//! // ...
+//! if let Ok(sample) = adc.read_ready() {
+//!     // ...
+//! }
+//! ```
+//!
+//! With `async` feature `read_async` awaits the result, see [`asynch`].
+//!
 //! ## Sequence conversion
 //! ```
 //! use stm32f4xx_hal::{
@@ -62,6 +82,16 @@
 //! adc.start_conversion();
 //! ```
 //!
+//! The same sequence can be built at once with [`config::ScanSequence`], which also enables scan mode:
+//! ```
+//! adc.set_sequence(
+//!     &ScanSequence::new()
+//!         .channel(&pa0, SampleTime::Cycles_112)
+//!         .channel(&pa3, SampleTime::Cycles_480)
+//!         .channel(&pa0, SampleTime::Cycles_112),
+//! );
+//! ```
+//!
 //! ## External trigger
 //!
 //! A common mistake on STM forums is enabling continuous mode but that causes it to start
@@ -69,78 +99,134 @@
 //! future triggers. Continuous mode is disabled by default but I thought it was worth
 //! highlighting.
 //!
+//! Timers can return trigger matching their event with `adc_trigger_update` and `adc_trigger_compare`,
+//! see [`crate::timer::adc_trigger`].
+//!
 //! Getting the timer config right to make sure it's sending the event the ADC is listening
-//! to can be a bit of a pain but the key fields are highlighted below. Try hooking a timer
-//! channel up to an external pin with an LED or oscilloscope attached to check it's really
-//! generating pulses if the ADC doesn't seem to be triggering.
+//! to can be a bit of a pain. Try hooking a timer channel up to an external pin with an LED
+//! or oscilloscope attached to check it's really generating pulses if the ADC doesn't seem
+//! to be triggering.
 //! ```
 //! use stm32f4xx_hal::{
 //!   gpio::gpioa,
 //!   adc::{
 //!     Adc,
-//!     config::{AdcConfig, SampleTime, Sequence, Eoc, Scan, Clock},
+//!     config::{AdcConfig, SampleTime, Sequence, TriggerMode},
 //!   },
 //! };
 //!
-//!  let config = AdcConfig::default()
-//!      //Set the trigger you want
-//!      .external_trigger(TriggerMode::RisingEdge, ExternalTrigger::Tim_1_cc_1);
-//!  let mut adc = Adc::adc1(device.ADC1, true, config);
-//!  let pa0 = gpioa.pa0.into_analog();
-//!  adc.configure_channel(&pa0, Sequence::One, SampleTime::Cycles_112);
-//!  //Make sure it's enabled but don't start the conversion
-//!  adc.enable();
-//!
-//! //Configure the timer
-//! let mut tim = Timer::tim1(device.TIM1, 1.hz(), clocks);
-//! unsafe {
-//!     let tim = &(*TIM1::ptr());
-//!
-//!     //Channel 1
-//!     //Disable the channel before configuring it
-//!     tim.ccer().modify(|_, w| w.cc1e().clear_bit());
-//!
-//!     tim.ccmr1_output().modify(|_, w| w
-//!       //Preload enable for channel
-//!       .oc1pe().set_bit()
-//!
-//!       //Set mode for channel, the default mode is "frozen" which won't work
-//!       .oc1m().pwm_mode1()
-//!     );
+//! //Configure the timer, compare event of channel 1 happens in the middle of each 1 ms period
+//! let mut timer = device.TIM1.counter_us(&clocks);
+//! let trigger = timer.adc_trigger_compare::<0>(500);
 //!
-//!     //Set the duty cycle, 0 won't work in pwm mode but might be ok in
-//!     //toggle mode or match mode
-//!     let max_duty = tim.arr.read().arr().bits() as u16;
-//!     tim.ccr1.modify(|_, w| w.ccr().bits(max_duty / 2));
-//!
-//!     //Enable the channel
-//!     tim.ccer.modify(|_, w| w.cc1e().set_bit());
+//! let config = AdcConfig::default()
+//!     //Set the trigger you want
+//!     .external_trigger(TriggerMode::RisingEdge, trigger);
+//! let mut adc = Adc::adc1(device.ADC1, true, config);
+//! let pa0 = gpioa.pa0.into_analog();
+//! adc.configure_channel(&pa0, Sequence::One, SampleTime::Cycles_112);
+//! //Make sure it's enabled but don't start the conversion
+//! adc.enable();
 //!
-//!     //Enable the TIM main Output
-//!     tim.bdtr.modify(|_, w| w.moe().set_bit());
-//! }
+//! timer.start(1.millis()).unwrap();
 //! ```
 
 #![deny(missing_docs)]
 
-/*
-    Currently unused but this is the formula for using temperature calibration:
-    Temperature in °C = (110-30) * (adc_sample - VtempCal30::get().read()) / (VtempCal110::get().read()-VtempCal30::get().read()) + 30
-*/
-
 use crate::dma::traits::{DMASet, PeriAddress, SafePeripheralRead};
 use crate::dma::PeripheralToMemory;
 use crate::rcc::{Enable, Reset};
 use crate::{
     gpio::{self, Analog},
     pac,
-    signature::VrefCal,
-    signature::VDDA_CALIB,
+    signature::{VrefCal, VtempCal110, VtempCal30, VDDA_CALIB},
 };
 use core::fmt;
+use core::ops::Deref;
+use enumflags2::BitFlags;
 
+#[cfg(feature = "async")]
+pub mod asynch;
 pub mod config;
+pub mod dma;
 mod f4;
+mod injected;
+mod watchdog;
+
+pub use dma::AdcDma;
+
+/// ADC peripheral
+pub trait Instance: crate::Sealed + Deref<Target = pac::adc1::RegisterBlock> {
+    #[doc(hidden)]
+    fn ptr() -> *const pac::adc1::RegisterBlock;
+    #[cfg(feature = "async")]
+    #[doc(hidden)]
+    fn waker() -> &'static crate::waker::WakerRegistration;
+}
+
+macro_rules! instance {
+    ($($(#[$attr:meta])* $ADC:ty,)+) => {
+        $(
+            $(#[$attr])*
+            impl Instance for $ADC {
+                fn ptr() -> *const pac::adc1::RegisterBlock {
+                    <$ADC>::PTR
+                }
+
+                #[cfg(feature = "async")]
+                fn waker() -> &'static crate::waker::WakerRegistration {
+                    static WAKER: crate::waker::WakerRegistration =
+                        crate::waker::WakerRegistration::new();
+                    &WAKER
+                }
+            }
+        )+
+    };
+}
+
+instance! {
+    pac::ADC1,
+    #[cfg(feature = "adc2")]
+    pac::ADC2,
+    #[cfg(feature = "adc3")]
+    pac::ADC3,
+}
+
+/// ADC interrupt events
+#[enumflags2::bitflags]
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// End of regular conversion
+    EndOfConversion = 1 << 5,
+    /// Analog watchdog
+    AnalogWatchdog = 1 << 6,
+    /// End of injected sequence
+    InjectedEndOfConversion = 1 << 7,
+    /// Overrun
+    Overrun = 1 << 26,
+}
+
+/// ADC status flags
+#[enumflags2::bitflags]
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Flag {
+    /// Converted value is out of analog watchdog thresholds
+    AnalogWatchdog = 1 << 0,
+    /// End of regular conversion
+    EndOfConversion = 1 << 1,
+    /// End of injected sequence
+    InjectedEndOfConversion = 1 << 2,
+    /// Injected conversion started
+    InjectedStart = 1 << 3,
+    /// Regular conversion started
+    RegularStart = 1 << 4,
+    /// Overrun
+    Overrun = 1 << 5,
+}
 
 /// Vref internal signal, used for calibration
 pub struct Vref;
@@ -160,7 +246,7 @@ pub struct Adc<ADC> {
     adc_reg: ADC,
     /// VDDA in millivolts calculated from the factory calibration and vrefint
     calibrated_vdda: u32,
-    /// Exclusive limit for the sample value possible for the configured resolution.
+    /// Exclusive limit for the sample value possible for the configured resolution and alignment.
     max_sample: u32,
 }
 impl<ADC> fmt::Debug for Adc<ADC> {
@@ -187,14 +273,60 @@ macro_rules! adc {
             }
 
             let vref_cal = VrefCal::get().read();
-            let vref_samp = self.read(&mut Vref).unwrap(); //This can't actually fail, it's just in a result to satisfy hal trait
+            let vref_samp = self.read_internal(&Vref, !vref_en);
 
-            self.calibrated_vdda = (VDDA_CALIB * u32::from(vref_cal)) / u32::from(vref_samp);
+            self.calibrated_vdda = (VDDA_CALIB * u32::from(vref_cal)) / vref_samp;
             if !vref_en {
                 self.disable_temperature_and_vref();
             }
         }
 
+        /// Converts internal channel and returns 12-bit value, comparable with factory calibration
+        fn read_internal<CHANNEL>(&mut self, channel: &CHANNEL, just_enabled: bool) -> u32
+        where
+            CHANNEL: embedded_hal_02::adc::Channel<pac::ADC1, ID=u8>
+        {
+            let enabled = self.is_enabled();
+            if !enabled {
+                self.enable();
+            }
+            if just_enabled {
+                // Temperature sensor and VREFINT need 10 us to start, long sampling covers it
+                self.convert(channel, config::SampleTime::Cycles_480);
+            }
+            let sample = self.convert(channel, config::SampleTime::Cycles_480);
+            if !enabled {
+                self.disable();
+            }
+            u32::from(sample) * 4096 / self.max_sample
+        }
+
+        /// Measures VDDA using VREFINT and its factory calibration, returns millivolts
+        ///
+        /// The result is also used for following `sample_to_millivolts` conversions.
+        pub fn read_vdda(&mut self) -> u32 {
+            self.calibrate();
+            self.calibrated_vdda
+        }
+
+        /// Measures junction temperature using factory calibration, returns °C
+        ///
+        /// VDDA measured by the last calibration is taken into account.
+        pub fn read_temperature(&mut self) -> f32 {
+            let ts_en = self.temperature_and_vref_enabled();
+            if !ts_en {
+                self.enable_temperature_and_vref();
+            }
+            // Calibration values are taken at VDDA = 3.3 V
+            let sample = self.read_internal(&Temperature, !ts_en) * self.calibrated_vdda / VDDA_CALIB;
+            if !ts_en {
+                self.disable_temperature_and_vref();
+            }
+            let cal30 = VtempCal30::get().read() as f32;
+            let cal110 = VtempCal110::get().read() as f32;
+            (110.0 - 30.0) * (sample as f32 - cal30) / (cal110 - cal30) + 30.0
+        }
+
         /// Enables the vbat internal channel
         pub fn enable_vbat(&self) {
             unsafe {
@@ -211,6 +343,43 @@ macro_rules! adc {
             }
         }
 
+        /// Returns if the vbat internal channel is enabled
+        pub fn vbat_enabled(&self) -> bool {
+            unsafe {
+                let common = &(*pac::$common_type::ptr());
+                common.ccr().read().vbate().bit_is_set()
+            }
+        }
+
+        /// Measures backup battery voltage, returns millivolts
+        ///
+        /// VBAT is connected to ADC through internal divider (by 2 on STM32F40x/41x, by 4 on others),
+        /// the result is corrected for it. The divider is disconnected after measurement to
+        /// save battery if vbat channel was disabled before.
+        pub fn read_vbat(&mut self) -> u32 {
+            #[cfg(feature = "gpio-f417")]
+            const DIVIDER: u32 = 2;
+            #[cfg(not(feature = "gpio-f417"))]
+            const DIVIDER: u32 = 4;
+
+            let vbat_en = self.vbat_enabled();
+            if !vbat_en {
+                self.enable_vbat();
+            }
+            let enabled = self.is_enabled();
+            if !enabled {
+                self.enable();
+            }
+            let sample = self.convert(&Vbat, config::SampleTime::Cycles_480);
+            if !enabled {
+                self.disable();
+            }
+            if !vbat_en {
+                self.disable_vbat();
+            }
+            u32::from(sample) * self.calibrated_vdda / self.max_sample * DIVIDER
+        }
+
         /// Enables the temp and vref internal channels.
         /// They can't work while vbat is also enabled so this method also disables vbat.
         pub fn enable_temperature_and_vref(&mut self) {
@@ -298,6 +467,7 @@ macro_rules! adc {
                     self.set_scan(config.scan);
                     self.set_external_trigger(config.external_trigger);
                     self.set_continuous(config.continuous);
+                    self.set_discontinuous(config.discontinuous);
                     self.set_dma(config.dma);
                     self.set_end_of_conversion_interrupt(config.end_of_conversion_interrupt);
                     self.set_default_sample_time(config.default_sample_time);
@@ -327,6 +497,37 @@ macro_rules! adc {
                     self.adc_reg.cr2().modify(|_, w| w.adon().clear_bit());
                 }
 
+                /// Disables the adc and stops its clock to save power, configuration is kept
+                pub fn power_down(&mut self) {
+                    self.disable();
+                    unsafe { pac::$adc_type::disable_unchecked(); }
+                }
+
+                /// Restarts the clock of the adc and enables it after [power_down](#method.power_down)
+                ///
+                /// ADC needs up to 3 us to stabilize before the first conversion.
+                pub fn power_up(&mut self) {
+                    unsafe { pac::$adc_type::enable_unchecked(); }
+                    self.enable();
+                }
+
+                /// Disables the adc and its clock and returns the peripheral
+                ///
+                /// Channel pins are only borrowed by the adc, so they stay with the caller.
+                /// # Arguments
+                /// * `reset` - should a reset be performed. Note that all ADCs share the same reset,
+                ///   other ADCs are reset too
+                pub fn release(mut self, reset: bool) -> pac::$adc_type {
+                    self.disable();
+                    unsafe {
+                        if reset {
+                            pac::$adc_type::reset_unchecked();
+                        }
+                        pac::$adc_type::disable_unchecked();
+                    }
+                    self.adc_reg
+                }
+
                 /// Starts conversion sequence. Waits for the hardware to indicate it's actually started.
                 pub fn start_conversion(&mut self) {
                     self.enable();
@@ -347,20 +548,18 @@ macro_rules! adc {
                 }
 
                 /// Sets the sampling resolution
+                ///
+                /// Conversion takes 3 ADC clock cycles less for each 2 bits of resolution less.
                 pub fn set_resolution(&mut self, resolution: config::Resolution) {
-                    self.max_sample = match resolution {
-                        config::Resolution::Twelve => (1 << 12),
-                        config::Resolution::Ten => (1 << 10),
-                        config::Resolution::Eight => (1 << 8),
-                        config::Resolution::Six => (1 << 6),
-                    };
                     self.config.resolution = resolution;
+                    self.update_max_sample();
                     self.adc_reg.cr1().modify(|_, w| w.res().set(resolution as _));
                 }
 
                 /// Sets the DR register alignment to left or right
                 pub fn set_align(&mut self, align: config::Align) {
                     self.config.align = align;
+                    self.update_max_sample();
                     self.adc_reg.cr2().modify(|_, w| w.align().bit(align.into()));
                 }
 
@@ -379,12 +578,38 @@ macro_rules! adc {
                     });
                 }
 
+                /// Returns configured external trigger and its edge
+                pub fn external_trigger(&self) -> (config::TriggerMode, config::ExternalTrigger) {
+                    self.config.external_trigger
+                }
+
                 /// Enables and disables continuous mode
                 pub fn set_continuous(&mut self, continuous: config::Continuous) {
                     self.config.continuous = continuous;
                     self.adc_reg.cr2().modify(|_, w| w.cont().bit(continuous.into()));
                 }
 
+                /// Enables discontinuous mode, so each trigger converts only a few channels
+                /// of regular sequence, or disables it
+                ///
+                /// # Panics
+                ///
+                /// If number of channels is not in 1..=8.
+                pub fn set_discontinuous(&mut self, discontinuous: config::Discontinuous) {
+                    self.config.discontinuous = discontinuous;
+                    match discontinuous {
+                        config::Discontinuous::Disabled => {
+                            self.adc_reg.cr1().modify(|_, w| w.discen().clear_bit());
+                        }
+                        config::Discontinuous::Enabled(n) => {
+                            assert!((1..=8).contains(&n), "ADC discontinuous channel count must be 1..=8");
+                            self.adc_reg.cr1().modify(|_, w| unsafe {
+                                w.discnum().bits(n - 1).discen().set_bit()
+                            });
+                        }
+                    }
+                }
+
                 /// Sets DMA to disabled, single or continuous
                 pub fn set_dma(&mut self, dma: config::Dma) {
                     self.config.dma = dma;
@@ -574,9 +799,72 @@ macro_rules! adc {
 
                     result
                 }
+
+                /// Starts single conversion of `pin` and returns without waiting for the result,
+                /// which is then taken with [read_ready](#method.read_ready).
+                ///
+                /// Listen [`Event::EndOfConversion`] to get interrupt when the result is ready.
+                /// Note that it reconfigures the adc sequence and doesn't restore it.
+                pub fn start_single_conversion<PIN>(&mut self, pin: &PIN, sample_time: config::SampleTime)
+                where
+                    PIN: embedded_hal_02::adc::Channel<pac::$adc_type, ID=u8>
+                {
+                    self.adc_reg.cr2().modify(|_, w| w
+                        .dma().clear_bit() //Disable dma
+                        .cont().clear_bit() //Disable continuous mode
+                        .exten().set(config::TriggerMode::Disabled.into()) //Disable trigger
+                        .eocs().clear_bit() //EOC is set at the end of the sequence
+                    );
+                    self.adc_reg.cr1().modify(|_, w| w.scan().clear_bit());
+
+                    self.reset_sequence();
+                    self.configure_channel(pin, config::Sequence::One, sample_time);
+                    self.start_conversion();
+                }
+
+                /// Returns result of conversion started by [start_single_conversion](#method.start_single_conversion)
+                /// or `WouldBlock` while it is in progress. The config is restored when the result is taken.
+                pub fn read_ready(&mut self) -> nb::Result<u16, ()> {
+                    if self.adc_reg.sr().read().eoc().bit_is_clear() {
+                        return Err(nb::Error::WouldBlock);
+                    }
+                    // Reading data register clears EOC
+                    let result = self.current_sample();
+                    self.adc_reg.sr().modify(|_, w| w.strt().clear_bit());
+
+                    // Keep interrupt enabled by `listen`
+                    let eocie = self.adc_reg.cr1().read().eocie().bit();
+                    self.apply_config(self.config);
+                    self.adc_reg.cr1().modify(|_, w| w.eocie().bit(eocie));
+
+                    Ok(result)
+                }
             }
 
             impl Adc<pac::$adc_type> {
+                /// Converts `pin` with default sample time, waiting for the result asynchronously
+                ///
+                /// [`asynch::on_interrupt`] must be called from ADC interrupt handler.
+                /// Note that it reconfigures the adc sequence and doesn't restore it.
+                #[cfg(feature = "async")]
+                pub async fn read_async<PIN>(&mut self, pin: &PIN) -> u16
+                where
+                    PIN: embedded_hal_02::adc::Channel<pac::$adc_type, ID=u8>
+                {
+                    self.start_single_conversion(pin, self.config.default_sample_time);
+                    core::future::poll_fn(|cx| {
+                        <pac::$adc_type as Instance>::waker().register(cx.waker());
+                        match self.read_ready() {
+                            Ok(sample) => core::task::Poll::Ready(sample),
+                            Err(_) => {
+                                self.adc_reg.cr1().modify(|_, w| w.eocie().set_bit());
+                                core::task::Poll::Pending
+                            }
+                        }
+                    })
+                    .await
+                }
+
                 fn read<PIN>(&mut self, pin: &mut PIN) -> nb::Result<u16, ()>
                     where PIN: embedded_hal_02::adc::Channel<pac::$adc_type, ID=u8>,
                 {
@@ -618,6 +906,117 @@ macro_rules! adc {
     };
 }
 
+impl<ADC: Instance> Adc<ADC> {
+    fn update_max_sample(&mut self) {
+        let bits = self.config.resolution.bits();
+        self.max_sample = match self.config.align {
+            config::Align::Right => 1 << bits,
+            // 6-bit samples are left aligned in the low byte
+            config::Align::Left if bits == 6 => 1 << 8,
+            config::Align::Left => 1 << 16,
+        };
+    }
+
+    /// Returns exclusive limit of sample values for the configured resolution and alignment
+    ///
+    /// Left aligned samples of any resolution except 6 bits use full `u16` range.
+    pub fn max_sample(&self) -> u32 {
+        self.max_sample
+    }
+
+    /// Sets sample time of `channel`
+    ///
+    /// Channel numbers come from `Channel` implementations, so they are at most 18.
+    fn set_channel_sample_time(&mut self, channel: u8, sample_time: config::SampleTime) {
+        let st = u8::from(sample_time);
+        if channel < 10 {
+            self.adc_reg.smpr2().modify(|_, w| w.smp(channel).set(st));
+        } else {
+            self.adc_reg
+                .smpr1()
+                .modify(|_, w| w.smp(channel - 10).set(st));
+        }
+    }
+
+    /// Replaces regular sequence with `sequence` and sets sample times of its channels
+    ///
+    /// Scan mode is enabled if the sequence has more than one channel.
+    ///
+    /// # Panics
+    ///
+    /// If `sequence` is empty.
+    pub fn set_sequence(&mut self, sequence: &config::ScanSequence<ADC>) {
+        assert!(!sequence.is_empty(), "ADC sequence is empty");
+        let channels = &sequence.channels[..sequence.len as usize];
+        for &(channel, sample_time) in channels {
+            self.set_channel_sample_time(channel, sample_time);
+        }
+        // Ranks 1-6 are in SQR3, 7-12 in SQR2 and 13-16 in SQR1
+        self.adc_reg.sqr3().write(|w| {
+            for (n, &(channel, _)) in channels.iter().take(6).enumerate() {
+                unsafe { w.sq(n as u8).bits(channel) };
+            }
+            w
+        });
+        self.adc_reg.sqr2().write(|w| {
+            for (n, &(channel, _)) in channels.iter().skip(6).take(6).enumerate() {
+                unsafe { w.sq(n as u8).bits(channel) };
+            }
+            w
+        });
+        self.adc_reg.sqr1().write(|w| {
+            for (n, &(channel, _)) in channels.iter().skip(12).enumerate() {
+                unsafe { w.sq(n as u8).bits(channel) };
+            }
+            w.l().set(sequence.len - 1)
+        });
+
+        if sequence.len > 1 {
+            self.config.scan = config::Scan::Enabled;
+            self.adc_reg.cr1().modify(|_, w| w.scan().set_bit());
+        }
+    }
+}
+
+impl<ADC: Instance> crate::Listen for Adc<ADC> {
+    type Event = Event;
+    fn listen(&mut self, event: impl Into<BitFlags<Event>>) {
+        let bits = event.into().bits();
+        self.adc_reg
+            .cr1()
+            .modify(|r, w| unsafe { w.bits(r.bits() | bits) });
+    }
+    fn listen_only(&mut self, event: impl Into<BitFlags<Event>>) {
+        let all = BitFlags::<Event>::ALL.bits();
+        let bits = event.into().bits();
+        self.adc_reg
+            .cr1()
+            .modify(|r, w| unsafe { w.bits((r.bits() & !all) | bits) });
+    }
+    fn unlisten(&mut self, event: impl Into<BitFlags<Event>>) {
+        let bits = event.into().bits();
+        self.adc_reg
+            .cr1()
+            .modify(|r, w| unsafe { w.bits(r.bits() & !bits) });
+    }
+}
+
+impl<ADC: Instance> crate::ReadFlags for Adc<ADC> {
+    type Flag = Flag;
+    fn flags(&self) -> BitFlags<Flag> {
+        BitFlags::from_bits_truncate(self.adc_reg.sr().read().bits())
+    }
+}
+
+impl<ADC: Instance> crate::ClearFlags for Adc<ADC> {
+    type Flag = Flag;
+    fn clear_flags(&mut self, flags: impl Into<BitFlags<Flag>>) {
+        // Flags are cleared by writing 0
+        let bits = flags.into().bits();
+        self.adc_reg.sr().write(|w| unsafe { w.bits(!bits) });
+    }
+}
+
 unsafe impl<ADC, STREAM, const CHANNEL: u8> DMASet<STREAM, CHANNEL, PeripheralToMemory> for Adc<ADC> where
     ADC: DMASet<STREAM, CHANNEL, PeripheralToMemory>
 {
@@ -0,0 +1,31 @@
+//! Interrupt driven async conversions
+//!
+//! `read_async` starts single conversion and enables end of conversion interrupt while
+//! waiting, so [`on_interrupt`] must be called from the ADC interrupt handler, and the
+//! interrupt must be unmasked in the NVIC. All ADCs share one interrupt vector:
+//!
+//! ```rust,ignore
+//! #[interrupt]
+//! fn ADC() {
+//!     adc::asynch::on_interrupt::<pac::ADC1>();
+//!     adc::asynch::on_interrupt::<pac::ADC2>();
+//! }
+//!
+//! let sample = adc.read_async(&pa3).await;
+//! ```
+//!
+//! The handler disables end of conversion interrupt after waking the task, so it can't be
+//! used for other purposes at the same time.
+
+use super::Instance;
+
+/// Wakes task waiting for conversion of `ADC`, call from ADC interrupt handler
+pub fn on_interrupt<ADC: Instance>() {
+    let adc = unsafe { &*ADC::ptr() };
+    let cr1 = adc.cr1().read();
+    if cr1.eocie().bit_is_set() && adc.sr().read().eoc().bit_is_set() {
+        // Data register is read by the task, which clears EOC
+        adc.cr1().modify(|_, w| w.eocie().clear_bit());
+        ADC::waker().wake();
+    }
+}
@@ -1,5 +1,7 @@
 //! Contains types related to ADC configuration
 
+use core::marker::PhantomData;
+
 /// The place in the sequence a given channel should be captured
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -69,6 +71,142 @@ impl From<u8> for Sequence {
     }
 }
 
+/// Ordered regular conversion sequence of up to 16 channels of `ADC`,
+/// each with its own sample time
+///
+/// Only channels available on `ADC` can be added, others don't compile.
+/// ```rust,ignore
+/// let sequence = ScanSequence::new()
+///     .channel(&pa0, SampleTime::Cycles_112)
+///     .channel(&Temperature, SampleTime::Cycles_480)
+///     .channel(&pa0, SampleTime::Cycles_112);
+/// adc.set_sequence(&sequence);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ScanSequence<ADC> {
+    pub(crate) channels: [(u8, SampleTime); 16],
+    pub(crate) len: u8,
+    _adc: PhantomData<fn() -> ADC>,
+}
+
+impl<ADC> ScanSequence<ADC> {
+    /// Creates empty sequence
+    pub const fn new() -> Self {
+        Self {
+            channels: [(0, SampleTime::Cycles_3); 16],
+            len: 0,
+            _adc: PhantomData,
+        }
+    }
+
+    /// Appends `channel` sampled for `sample_time`
+    ///
+    /// Channel can appear several times, but all its entries use the sample time
+    /// given last because it is set per channel, not per rank.
+    ///
+    /// # Panics
+    ///
+    /// If sequence already has 16 channels.
+    pub fn channel<CHANNEL>(mut self, _channel: &CHANNEL, sample_time: SampleTime) -> Self
+    where
+        CHANNEL: embedded_hal_02::adc::Channel<ADC, ID = u8>,
+    {
+        assert!(self.len < 16, "ADC sequence is limited to 16 channels");
+        self.channels[self.len as usize] = (CHANNEL::channel(), sample_time);
+        self.len += 1;
+        self
+    }
+
+    /// Number of conversions in the sequence
+    pub fn len(&self) -> u8 {
+        self.len
+    }
+
+    /// Is sequence empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<ADC> Default for ScanSequence<ADC> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Injected conversion sequence of up to 4 channels of `ADC`
+///
+/// Each rank has its own data register and offset subtracted from the result.
+/// ```rust,ignore
+/// let sequence = InjectedSequence::new()
+///     .channel(&pa0, SampleTime::Cycles_15)
+///     .offset(2048)
+///     .channel(&pa1, SampleTime::Cycles_15);
+/// adc.set_injected_sequence(&sequence);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct InjectedSequence<ADC> {
+    pub(crate) channels: [(u8, SampleTime, u16); 4],
+    pub(crate) len: u8,
+    _adc: PhantomData<fn() -> ADC>,
+}
+
+impl<ADC> InjectedSequence<ADC> {
+    /// Creates empty sequence
+    pub const fn new() -> Self {
+        Self {
+            channels: [(0, SampleTime::Cycles_3, 0); 4],
+            len: 0,
+            _adc: PhantomData,
+        }
+    }
+
+    /// Appends `channel` sampled for `sample_time`
+    ///
+    /// # Panics
+    ///
+    /// If sequence already has 4 channels.
+    pub fn channel<CHANNEL>(mut self, _channel: &CHANNEL, sample_time: SampleTime) -> Self
+    where
+        CHANNEL: embedded_hal_02::adc::Channel<ADC, ID = u8>,
+    {
+        assert!(
+            self.len < 4,
+            "ADC injected sequence is limited to 4 channels"
+        );
+        self.channels[self.len as usize] = (CHANNEL::channel(), sample_time, 0);
+        self.len += 1;
+        self
+    }
+
+    /// Sets 12-bit offset subtracted from the result of the last added channel
+    ///
+    /// # Panics
+    ///
+    /// If sequence is empty.
+    pub fn offset(mut self, offset: u16) -> Self {
+        assert!(self.len > 0, "ADC injected sequence is empty");
+        self.channels[self.len as usize - 1].2 = offset & 0xfff;
+        self
+    }
+
+    /// Number of conversions in the sequence
+    pub fn len(&self) -> u8 {
+        self.len
+    }
+
+    /// Is sequence empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<ADC> Default for InjectedSequence<ADC> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// The number of cycles to sample a given channel for
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -155,6 +293,17 @@ impl From<Resolution> for u8 {
         r as _
     }
 }
+impl Resolution {
+    /// Number of significant bits in sample
+    pub const fn bits(self) -> u8 {
+        match self {
+            Self::Twelve => 12,
+            Self::Ten => 10,
+            Self::Eight => 8,
+            Self::Six => 6,
+        }
+    }
+}
 
 /// Possible external triggers the ADC can listen to
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -187,6 +336,12 @@ pub enum ExternalTrigger {
     Tim_5_cc_2 = 0b1011,
     /// TIM5 compare channel 3
     Tim_5_cc_3 = 0b1100,
+    /// TIM8 compare channel 1
+    #[cfg(feature = "tim8")]
+    Tim_8_cc_1 = 0b1101,
+    /// TIM8 trigger out
+    #[cfg(feature = "tim8")]
+    Tim_8_trgo = 0b1110,
     /// External interrupt line 11
     Exti_11 = 0b1111,
 }
@@ -196,6 +351,50 @@ impl From<ExternalTrigger> for u8 {
     }
 }
 
+/// Possible external triggers of injected conversions
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum InjectedTrigger {
+    /// TIM1 compare channel 4
+    Tim_1_cc_4 = 0b0000,
+    /// TIM1 trigger out
+    Tim_1_trgo = 0b0001,
+    /// TIM2 compare channel 1
+    Tim_2_cc_1 = 0b0010,
+    /// TIM2 trigger out
+    Tim_2_trgo = 0b0011,
+    /// TIM3 compare channel 2
+    Tim_3_cc_2 = 0b0100,
+    /// TIM3 compare channel 4
+    Tim_3_cc_4 = 0b0101,
+    /// TIM4 compare channel 1
+    Tim_4_cc_1 = 0b0110,
+    /// TIM4 compare channel 2
+    Tim_4_cc_2 = 0b0111,
+    /// TIM4 compare channel 3
+    Tim_4_cc_3 = 0b1000,
+    /// TIM4 trigger out
+    Tim_4_trgo = 0b1001,
+    /// TIM5 compare channel 4
+    Tim_5_cc_4 = 0b1010,
+    /// TIM5 trigger out
+    Tim_5_trgo = 0b1011,
+    /// TIM8 compare channel 2
+    Tim_8_cc_2 = 0b1100,
+    /// TIM8 compare channel 3
+    Tim_8_cc_3 = 0b1101,
+    /// TIM8 compare channel 4
+    Tim_8_cc_4 = 0b1110,
+    /// External interrupt line 15
+    Exti_15 = 0b1111,
+}
+impl From<InjectedTrigger> for u8 {
+    fn from(it: InjectedTrigger) -> u8 {
+        it as _
+    }
+}
+
 /// Possible trigger modes
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -270,6 +469,16 @@ impl From<Continuous> for bool {
     }
 }
 
+/// Discontinuous mode of regular conversions
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Discontinuous {
+    /// Whole sequence is converted on each trigger
+    Disabled,
+    /// Each trigger converts next 1..=8 channels of the sequence
+    Enabled(u8),
+}
+
 /// DMA mode
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -306,6 +515,7 @@ pub struct AdcConfig {
     pub(crate) scan: Scan,
     pub(crate) external_trigger: (TriggerMode, ExternalTrigger),
     pub(crate) continuous: Continuous,
+    pub(crate) discontinuous: Discontinuous,
     pub(crate) dma: Dma,
     pub(crate) end_of_conversion_interrupt: Eoc,
     pub(crate) default_sample_time: SampleTime,
@@ -343,6 +553,11 @@ impl AdcConfig {
         self.continuous = continuous;
         self
     }
+    /// change the discontinuous field
+    pub fn discontinuous(mut self, discontinuous: Discontinuous) -> Self {
+        self.discontinuous = discontinuous;
+        self
+    }
     /// change the dma field
     pub fn dma(mut self, dma: Dma) -> Self {
         self.dma = dma;
@@ -378,6 +593,7 @@ impl Default for AdcConfig {
             scan: Scan::Disabled,
             external_trigger: (TriggerMode::Disabled, ExternalTrigger::Tim_1_cc_1),
             continuous: Continuous::Single,
+            discontinuous: Discontinuous::Disabled,
             dma: Dma::Disabled,
             end_of_conversion_interrupt: Eoc::Disabled,
             default_sample_time: SampleTime::Cycles_480,
@@ -0,0 +1,271 @@
+//! Continuous ADC sampling into circular DMA buffer
+//!
+//! [`AdcDma`] keeps the regular sequence converting into a circular buffer. DMA half transfer
+//! and transfer complete interrupts tell that one half of the buffer is filled, it can be
+//! processed while DMA writes the other half.
+//!
+//! In double buffer mode ([`AdcDma::new_double`]) DMA fills two separate buffers in turn,
+//! each of them is passed to the callback when it is full, so no samples are lost
+//! between buffers, for example when logging to SD card or USB.
+//!
+//! ADC requests DMA after each conversion as long as DDS is set. If data isn't read in time
+//! ADC sets overrun flag and stops DMA requests, so `on_interrupt` must be called from both
+//! DMA stream and ADC interrupt handlers: it restarts acquisition and reports [`Error::Overrun`].
+//!
+//! ```rust,ignore
+//! let config = AdcConfig::default()
+//!     .scan(Scan::Enabled)
+//!     .external_trigger(TriggerMode::RisingEdge, ExternalTrigger::Tim_2_trgo);
+//! let mut adc = Adc::adc1(dp.ADC1, true, config);
+//! adc.configure_channel(&pa0, Sequence::One, SampleTime::Cycles_112);
+//! adc.configure_channel(&pa1, Sequence::Two, SampleTime::Cycles_112);
+//! let buffer = cortex_m::singleton!(: [u16; 256] = [0; 256]).unwrap();
+//! let mut sampler = AdcDma::new(adc, dma2.0, buffer);
+//! sampler.start();
+//!
+//! #[interrupt]
+//! fn DMA2_STREAM0() {
+//!     sampler.on_interrupt(|half, samples| process(half, samples)).ok();
+//! }
+//! ```
+
+use super::{config::Dma, Adc, Instance};
+use crate::dma::{
+    config::Priority,
+    traits::{Channel, DMASet, PeriAddress, Stream},
+    ChannelX, CurrentBuffer, DmaDataSize, DmaDirection, DmaEvent, PeripheralToMemory,
+};
+use crate::Listen;
+use core::sync::atomic::{compiler_fence, Ordering};
+use embedded_dma::WriteBuffer;
+
+/// Errors of continuous acquisition
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// ADC data was overwritten before DMA read it, acquisition was restarted
+    Overrun,
+    /// DMA transfer error, acquisition is stopped
+    TransferError,
+}
+
+/// Half of the circular buffer, or one of the buffers in double buffer mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Half {
+    /// First half, filled at half transfer, or the first buffer
+    First,
+    /// Second half, filled at transfer complete, or the second buffer
+    Second,
+}
+
+/// ADC converting continuously into circular DMA buffer or two alternating buffers
+pub struct AdcDma<ADC, STREAM, const CHANNEL: u8, BUF> {
+    adc: Adc<ADC>,
+    stream: STREAM,
+    buf: BUF,
+    double_buf: Option<BUF>,
+    len: usize,
+}
+
+impl<ADC, STREAM, const CHANNEL: u8, BUF> AdcDma<ADC, STREAM, CHANNEL, BUF>
+where
+    ADC: Instance,
+    Adc<ADC>: PeriAddress<MemSize = u16> + DMASet<STREAM, CHANNEL, PeripheralToMemory>,
+    STREAM: Stream,
+    ChannelX<CHANNEL>: Channel,
+    BUF: WriteBuffer<Word = u16> + 'static,
+{
+    /// Configures `stream` to write ADC data into `buf` circularly
+    ///
+    /// Buffer length should be an even multiple of the sequence length, so each half
+    /// contains whole sequences.
+    ///
+    /// # Panics
+    ///
+    /// If `buf` is empty or longer than 65535 samples.
+    pub fn new(adc: Adc<ADC>, mut stream: STREAM, mut buf: BUF) -> Self {
+        // NOTE(unsafe) We own the buffer and don't give `&mut` access to it until release
+        let (ptr, len) = unsafe { buf.write_buffer() };
+        assert!(len > 0 && len <= u16::MAX as usize);
+
+        Self::configure(&mut stream, &adc, ptr as u32, len as u16);
+        stream.set_double_buffer(false);
+        stream.listen_only(
+            DmaEvent::HalfTransfer | DmaEvent::TransferComplete | DmaEvent::TransferError,
+        );
+        Self::init(adc, stream, buf, None, len)
+    }
+
+    /// Configures `stream` to fill `buf` and `double_buf` in turn
+    ///
+    /// Both buffers are used to the length of the shorter one.
+    ///
+    /// # Panics
+    ///
+    /// If a buffer is empty or longer than 65535 samples.
+    pub fn new_double(
+        adc: Adc<ADC>,
+        mut stream: STREAM,
+        mut buf: BUF,
+        mut double_buf: BUF,
+    ) -> Self {
+        // NOTE(unsafe) We own the buffers and don't give `&mut` access to them until release
+        let (ptr, len) = unsafe { buf.write_buffer() };
+        let (db_ptr, db_len) = unsafe { double_buf.write_buffer() };
+        let len = len.min(db_len);
+        assert!(len > 0 && len <= u16::MAX as usize);
+
+        Self::configure(&mut stream, &adc, ptr as u32, len as u16);
+        stream.set_alternate_memory_address(db_ptr as u32);
+        stream.set_double_buffer(true);
+        stream.listen_only(DmaEvent::TransferComplete | DmaEvent::TransferError);
+        Self::init(adc, stream, buf, Some(double_buf), len)
+    }
+
+    fn configure(stream: &mut STREAM, adc: &Adc<ADC>, ptr: u32, len: u16) {
+        Self::stop_stream(stream);
+        stream.set_channel(ChannelX::<CHANNEL>::VALUE);
+        stream.set_direction(DmaDirection::PeripheralToMemory);
+        stream.set_peripheral_address(adc.address());
+        stream.set_memory_address(ptr);
+        stream.set_number_of_transfers(len);
+        stream.set_priority(Priority::High);
+        unsafe {
+            stream.set_memory_size(DmaDataSize::HalfWord);
+            stream.set_peripheral_size(DmaDataSize::HalfWord);
+        }
+        stream.set_memory_increment(true);
+        stream.set_peripheral_increment(false);
+        stream.set_circular_mode(true);
+        stream.set_fifo_enable(false);
+    }
+
+    fn init(adc: Adc<ADC>, stream: STREAM, buf: BUF, double_buf: Option<BUF>, len: usize) -> Self {
+        adc.adc_reg.cr2().modify(|_, w| w.dma().clear_bit());
+        adc.adc_reg.cr1().modify(|_, w| w.ovrie().set_bit());
+
+        Self {
+            adc,
+            stream,
+            buf,
+            double_buf,
+            len,
+        }
+    }
+
+    fn stop_stream(stream: &mut STREAM) {
+        unsafe { stream.disable() };
+        while stream.is_enabled() {}
+        stream.clear_all_flags();
+    }
+
+    /// Starts acquisition
+    ///
+    /// Conversions are started by software if external trigger is disabled,
+    /// otherwise by the trigger.
+    pub fn start(&mut self) {
+        let adc = &self.adc.adc_reg;
+        adc.sr()
+            .modify(|_, w| w.ovr().clear_bit().eoc().clear_bit());
+        self.stream.set_number_of_transfers(self.len as u16);
+        // "Preceding reads and writes cannot be moved past subsequent writes"
+        compiler_fence(Ordering::Release);
+        unsafe { self.stream.enable() };
+        // DDS keeps DMA requests after the last transfer of the sequence
+        adc.cr2()
+            .modify(|_, w| w.dds().set_bit().dma().set_bit().adon().set_bit());
+        if adc.cr2().read().exten().bits() == 0 {
+            adc.cr2().modify(|_, w| w.swstart().set_bit());
+        }
+    }
+
+    /// Stops conversions and DMA
+    pub fn stop(&mut self) {
+        self.adc
+            .adc_reg
+            .cr2()
+            .modify(|_, w| w.cont().clear_bit().dma().clear_bit().dds().clear_bit());
+        Self::stop_stream(&mut self.stream);
+        self.adc.adc_reg.sr().modify(|_, w| w.ovr().clear_bit());
+        // "No re-ordering of reads and writes across this point is allowed"
+        compiler_fence(Ordering::SeqCst);
+        let cont = self.adc.config.continuous.into();
+        self.adc.adc_reg.cr2().modify(|_, w| w.cont().bit(cont));
+    }
+
+    /// Handles DMA and ADC interrupts, calls `f` with the filled half of the buffer
+    /// or the filled buffer in double buffer mode
+    ///
+    /// The slice is valid until the DMA wraps around to it again, so `f` should
+    /// copy or process samples without delay.
+    pub fn on_interrupt<F>(&mut self, mut f: F) -> Result<(), Error>
+    where
+        F: FnMut(Half, &[u16]),
+    {
+        if self.stream.is_transfer_error() {
+            self.stop();
+            self.stream.clear_transfer_error();
+            return Err(Error::TransferError);
+        }
+        if self.adc.adc_reg.sr().read().ovr().bit_is_set() {
+            // DMA is stopped by overrun, restart both
+            self.stop();
+            self.start();
+            return Err(Error::Overrun);
+        }
+        if let Some(double_buf) = self.double_buf.as_mut() {
+            if self.stream.is_transfer_complete() {
+                self.stream.clear_transfer_complete();
+                compiler_fence(Ordering::Acquire);
+                // DMA has switched to the other buffer
+                let (half, buf) = match self.stream.current_buffer() {
+                    CurrentBuffer::SecondBuffer => (Half::First, &mut self.buf),
+                    CurrentBuffer::FirstBuffer => (Half::Second, double_buf),
+                };
+                // NOTE(unsafe) Only the buffer DMA has finished with is read
+                let ptr = unsafe { buf.write_buffer().0 } as *const u16;
+                f(half, unsafe { core::slice::from_raw_parts(ptr, self.len) });
+            }
+            return Ok(());
+        }
+        // NOTE(unsafe) Only the half DMA has finished with is read
+        let ptr = unsafe { self.buf.write_buffer().0 } as *const u16;
+        let half = self.len / 2;
+        if self.stream.is_half_transfer() {
+            self.stream.clear_half_transfer();
+            compiler_fence(Ordering::Acquire);
+            f(Half::First, unsafe {
+                core::slice::from_raw_parts(ptr, half)
+            });
+        }
+        if self.stream.is_transfer_complete() {
+            self.stream.clear_transfer_complete();
+            compiler_fence(Ordering::Acquire);
+            f(Half::Second, unsafe {
+                core::slice::from_raw_parts(ptr.add(half), self.len - half)
+            });
+        }
+        Ok(())
+    }
+
+    /// Stops acquisition and returns ADC, stream and buffers
+    pub fn release(mut self) -> (Adc<ADC>, STREAM, BUF, Option<BUF>) {
+        self.stop();
+        self.stream.unlisten(
+            DmaEvent::HalfTransfer | DmaEvent::TransferComplete | DmaEvent::TransferError,
+        );
+        self.adc.adc_reg.cr1().modify(|_, w| w.ovrie().clear_bit());
+        let (dds, en) = match self.adc.config.dma {
+            Dma::Disabled => (false, false),
+            Dma::Single => (false, true),
+            Dma::Continuous => (true, true),
+        };
+        self.adc
+            .adc_reg
+            .cr2()
+            .modify(|_, w| w.dds().bit(dds).dma().bit(en));
+        self.stream.set_double_buffer(false);
+        (self.adc, self.stream, self.buf, self.double_buf)
+    }
+}
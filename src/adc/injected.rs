@@ -0,0 +1,91 @@
+//! Injected conversions
+//!
+//! Injected group of up to 4 channels interrupts regular conversions when triggered, so
+//! currents can be sampled synchronously to PWM while a regular scan runs in the background.
+//! Each rank has its own data register, results don't need DMA.
+
+use super::{config, Adc, Instance};
+
+impl<ADC: Instance> Adc<ADC> {
+    /// Replaces injected sequence with `sequence`, sets sample times and offsets of its channels
+    ///
+    /// Scan mode must be enabled to convert more than one channel.
+    ///
+    /// # Panics
+    ///
+    /// If `sequence` is empty.
+    pub fn set_injected_sequence(&mut self, sequence: &config::InjectedSequence<ADC>) {
+        assert!(!sequence.is_empty(), "ADC injected sequence is empty");
+        let channels = &sequence.channels[..sequence.len as usize];
+        for (rank, &(channel, sample_time, offset)) in channels.iter().enumerate() {
+            self.set_channel_sample_time(channel, sample_time);
+            self.adc_reg.jofr(rank).write(|w| w.joffset().set(offset));
+        }
+        // Shorter sequences occupy the last JSQ fields
+        let first = 4 - sequence.len;
+        self.adc_reg.jsqr().write(|w| {
+            for (rank, &(channel, _, _)) in channels.iter().enumerate() {
+                unsafe { w.jsq(first + rank as u8).bits(channel) };
+            }
+            w.jl().set(sequence.len - 1)
+        });
+    }
+
+    /// Sets which external trigger starts injected conversions and if it is disabled, rising, falling or both
+    pub fn set_injected_trigger(
+        &mut self,
+        edge: config::TriggerMode,
+        trigger: config::InjectedTrigger,
+    ) {
+        self.adc_reg.cr2().modify(|_, w| {
+            unsafe { w.jextsel().bits(trigger.into()) };
+            w.jexten().set(edge.into())
+        });
+    }
+
+    /// Enables and disables automatic conversion of injected group after regular group
+    pub fn set_injected_auto_conversion(&mut self, enable: bool) {
+        self.adc_reg.cr1().modify(|_, w| w.jauto().bit(enable));
+    }
+
+    /// Enables and disables discontinuous mode of injected group, each trigger converts one channel
+    pub fn set_injected_discontinuous(&mut self, enable: bool) {
+        self.adc_reg.cr1().modify(|_, w| w.jdiscen().bit(enable));
+    }
+
+    /// Starts injected conversions by software
+    pub fn start_injected_conversion(&mut self) {
+        self.adc_reg.cr2().modify(|_, w| w.adon().set_bit());
+        self.clear_injected_end_of_conversion_flag();
+        self.adc_reg.cr2().modify(|_, w| w.jswstart().set_bit());
+    }
+
+    /// Enables and disables interrupt at the end of injected sequence (JEOC)
+    pub fn set_injected_end_of_conversion_interrupt(&mut self, enable: bool) {
+        self.adc_reg.cr1().modify(|_, w| w.jeocie().bit(enable));
+    }
+
+    /// Returns if injected sequence is converted
+    pub fn is_injected_conversion_complete(&self) -> bool {
+        self.adc_reg.sr().read().jeoc().bit_is_set()
+    }
+
+    /// Resets the injected end-of-conversion flag
+    pub fn clear_injected_end_of_conversion_flag(&mut self) {
+        self.adc_reg
+            .sr()
+            .modify(|_, w| w.jeoc().clear_bit().jstrt().clear_bit());
+    }
+
+    /// Returns result of injected conversion `rank` (0..=3) with offset subtracted
+    ///
+    /// Result is sign-extended if right aligned, so it can be negative when offset is used.
+    ///
+    /// # Panics
+    ///
+    /// If `rank` is above 3.
+    pub fn injected_sample(&self, rank: u8) -> i16 {
+        assert!(rank < 4, "ADC injected rank must be 0..=3");
+        self.adc_reg.jdr(rank as usize).read().jdata().bits() as i16
+    }
+}
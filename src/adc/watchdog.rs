@@ -0,0 +1,53 @@
+//! Analog watchdog
+//!
+//! Watchdog compares each converted value of one or all channels with low and high thresholds
+//! and sets [`Flag::AnalogWatchdog`](super::Flag::AnalogWatchdog) when it is out of range,
+//! so overcurrent or overvoltage is detected without polling.
+//!
+//! ```rust,ignore
+//! adc.set_watchdog_thresholds(0, 3000);
+//! adc.watch_channel(&pa0);
+//! adc.enable_watchdog(true, false);
+//! adc.listen(Event::AnalogWatchdog);
+//! ```
+
+use super::{Adc, Instance};
+
+impl<ADC: Instance> Adc<ADC> {
+    /// Sets 12-bit thresholds, values below `low` or above `high` trigger the watchdog
+    ///
+    /// Thresholds are compared with right aligned 12-bit values regardless of resolution
+    /// and alignment.
+    pub fn set_watchdog_thresholds(&mut self, low: u16, high: u16) {
+        self.adc_reg.ltr().write(|w| w.lt().set(low & 0xfff));
+        self.adc_reg.htr().write(|w| w.ht().set(high & 0xfff));
+    }
+
+    /// Guards only `channel`
+    pub fn watch_channel<CHANNEL>(&mut self, _channel: &CHANNEL)
+    where
+        CHANNEL: embedded_hal_02::adc::Channel<ADC, ID = u8>,
+    {
+        self.adc_reg.cr1().modify(|_, w| {
+            unsafe { w.awdch().bits(CHANNEL::channel()) };
+            w.awdsgl().set_bit()
+        });
+    }
+
+    /// Guards all converted channels
+    pub fn watch_all_channels(&mut self) {
+        self.adc_reg.cr1().modify(|_, w| w.awdsgl().clear_bit());
+    }
+
+    /// Enables watchdog on regular and/or injected conversions
+    pub fn enable_watchdog(&mut self, regular: bool, injected: bool) {
+        self.adc_reg
+            .cr1()
+            .modify(|_, w| w.awden().bit(regular).jawden().bit(injected));
+    }
+
+    /// Disables watchdog
+    pub fn disable_watchdog(&mut self) {
+        self.enable_watchdog(false, false);
+    }
+}
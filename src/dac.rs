@@ -1,15 +1,36 @@
 //! # API for the Digital to Analog converter
 //!
-//! Currently only supports writing to the DR of the DAC,
-//! just a basic one-shot conversion.
+//! Channel 1 outputs on PA4, channel 2 on PA5. Values are written with [`DacOut`]:
+//! `u16` for 12-bit and `u8` for 8-bit right aligned data. 12-bit left aligned data
+//! (upper bits of `u16`) is written with `set_value_left_aligned`.
+//!
+//! `embedded-hal` has no DAC trait, so [`DacOut`] is used instead.
+//!
+//! Both channels `(C1, C2)` can be written at once with `(u16, u16)` or `(u8, u8)` values
+//! through dual data registers. With the same trigger selected both outputs change
+//! in the same clock, for I/Q or X/Y signals.
+//!
+//! Output can be updated by a timer trigger ([`DacChannel::set_trigger`]) with samples
+//! streamed by DMA, see [`DacDma`].
+//!
+//! ```rust,ignore
+//! let mut dac = dp.DAC.constrain(gpioa.pa4.into_analog());
+//! dac.enable();
+//! dac.set_value(2048u16);
+//! dac.set_value(128u8);
+//! ```
 #![deny(unused_imports)]
 
 use crate::{
+    dma::traits::PeriAddress,
     gpio::{Analog, PA4, PA5},
     pac::DAC,
     rcc::{Enable, Reset},
 };
 
+pub mod dma;
+pub use dma::DacDma;
+
 pub struct C1;
 pub struct C2;
 
@@ -20,6 +41,51 @@ pub trait DacOut<V> {
 
 pub trait DacPin {
     fn enable(&mut self);
+    fn disable(&mut self);
+}
+
+/// Event starting conversion, i.e. transfer of written value to output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Trigger {
+    /// TIM6 trigger output
+    Tim6Trgo = 0,
+    /// TIM8 trigger output
+    Tim8Trgo = 1,
+    /// TIM7 trigger output
+    Tim7Trgo = 2,
+    /// TIM5 trigger output
+    Tim5Trgo = 3,
+    /// TIM2 trigger output
+    Tim2Trgo = 4,
+    /// TIM4 trigger output
+    Tim4Trgo = 5,
+    /// EXTI line 9 (rising edge on pin 9 of the port selected in SYSCFG)
+    Exti9 = 6,
+    /// [`DacChannel::software_trigger`]
+    Software = 7,
+}
+
+/// Trigger and DMA control of DAC channel
+pub trait DacChannel: DacPin {
+    /// Sets event updating the output or `None` to update it right after write
+    ///
+    /// Can be changed only while the channel is disabled. Timer must send
+    /// update events to TRGO, for example with `set_master_mode`.
+    fn set_trigger(&mut self, trigger: Option<Trigger>);
+    /// Updates the output if [`Trigger::Software`] is selected
+    fn software_trigger(&mut self);
+    /// Enables and disables DMA request on each trigger
+    fn enable_dma(&mut self, enable: bool);
+    /// Enables DMA underrun interrupt
+    fn listen_underrun(&mut self);
+    /// Disables DMA underrun interrupt
+    fn unlisten_underrun(&mut self);
+    /// Returns `true` if trigger came before DMA wrote the previous sample
+    fn is_underrun(&self) -> bool;
+    /// Clears DMA underrun flag
+    fn clear_underrun(&mut self);
 }
 
 pub trait Pins<DAC> {
@@ -63,12 +129,92 @@ where
 }
 
 macro_rules! dac {
-    ($CX:ident, $en:ident, $cen:ident, $cal_flag:ident, $trim:ident, $mode:ident, $dhrx:ident, $dac_dor:ident, $daccxdhr:ident) => {
+    ($CX:ident, $en:ident, $cen:ident, $cal_flag:ident, $trim:ident, $mode:ident, $dhrx:ident, $dhr12lx:ident, $dhr8rx:ident, $dac_dor:ident, $daccxdhr:ident,
+     $boff:ident, $ten:ident, $tsel:ident, $dmaen:ident, $dmaudrie:ident, $dmaudr:ident, $swtrig:ident) => {
         impl DacPin for $CX {
             fn enable(&mut self) {
                 let dac = unsafe { &(*DAC::ptr()) };
                 dac.cr().modify(|_, w| w.$en().set_bit());
             }
+
+            fn disable(&mut self) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.cr().modify(|_, w| w.$en().clear_bit());
+            }
+        }
+
+        impl DacChannel for $CX {
+            fn set_trigger(&mut self, trigger: Option<Trigger>) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.cr().modify(|_, w| match trigger {
+                    Some(t) => w.$ten().set_bit().$tsel().set(t as u8),
+                    None => w.$ten().clear_bit().$tsel().set(0),
+                });
+            }
+
+            fn software_trigger(&mut self) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.swtrigr().write(|w| w.$swtrig().set_bit());
+            }
+
+            fn enable_dma(&mut self, enable: bool) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.cr().modify(|_, w| w.$dmaen().bit(enable));
+            }
+
+            fn listen_underrun(&mut self) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.cr().modify(|_, w| w.$dmaudrie().set_bit());
+            }
+
+            fn unlisten_underrun(&mut self) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.cr().modify(|_, w| w.$dmaudrie().clear_bit());
+            }
+
+            fn is_underrun(&self) -> bool {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.sr().read().$dmaudr().bit_is_set()
+            }
+
+            fn clear_underrun(&mut self) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                // Cleared by writing 1
+                dac.sr().write(|w| w.$dmaudr().set_bit());
+            }
+        }
+
+        unsafe impl PeriAddress for $CX {
+            fn address(&self) -> u32 {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.$dhrx().as_ptr() as u32
+            }
+
+            type MemSize = u16;
+        }
+
+        impl $CX {
+            /// Enables or disables output buffer
+            ///
+            /// Buffer (default) reduces output impedance to drive loads without external op-amp,
+            /// but output doesn't reach the supply rails. Unbuffered output goes from rail to rail
+            /// and suits high-impedance loads.
+            pub fn set_output_buffer(&mut self, enable: bool) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.cr().modify(|_, w| w.$boff().bit(!enable));
+            }
+
+            /// Returns `true` if output buffer is enabled
+            pub fn is_output_buffer_enabled(&self) -> bool {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.cr().read().$boff().bit_is_clear()
+            }
+
+            /// Writes 12-bit value aligned to the most significant bit of `u16`, lower 4 bits are ignored
+            pub fn set_value_left_aligned(&mut self, val: u16) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.$dhr12lx().write(|w| unsafe { w.bits(val as u32) });
+            }
         }
 
         impl DacOut<u16> for $CX {
@@ -82,6 +228,19 @@ macro_rules! dac {
                 dac.$dac_dor().read().bits() as u16
             }
         }
+
+        impl DacOut<u8> for $CX {
+            fn set_value(&mut self, val: u8) {
+                let dac = unsafe { &(*DAC::ptr()) };
+                dac.$dhr8rx().write(|w| unsafe { w.bits(val as u32) });
+            }
+
+            /// Returns upper 8 bits of the output value
+            fn get_value(&mut self) -> u8 {
+                let dac = unsafe { &(*DAC::ptr()) };
+                (dac.$dac_dor().read().bits() >> 4) as u8
+            }
+        }
     };
 }
 
@@ -100,6 +259,91 @@ impl DacExt for DAC {
     }
 }
 
-dac!(C1, en1, cen1, cal_flag1, otrim1, mode1, dhr12r1, dor1, dacc1dhr);
+dac!(
+    C1, en1, cen1, cal_flag1, otrim1, mode1, dhr12r1, dhr12l1, dhr8r1, dor1, dacc1dhr, boff1, ten1,
+    tsel1, dmaen1, dmaudrie1, dmaudr1, swtrig1
+);
 #[cfg(not(feature = "stm32f410"))]
-dac!(C2, en2, cen2, cal_flag2, otrim2, mode2, dhr12r2, dor2, dacc2dhr);
+dac!(
+    C2, en2, cen2, cal_flag2, otrim2, mode2, dhr12r2, dhr12l2, dhr8r2, dor2, dacc2dhr, boff2, ten2,
+    tsel2, dmaen2, dmaudrie2, dmaudr2, swtrig2
+);
+
+#[cfg(not(feature = "stm32f410"))]
+impl DacPin for (C1, C2) {
+    fn enable(&mut self) {
+        let dac = unsafe { &(*DAC::ptr()) };
+        dac.cr().modify(|_, w| w.en1().set_bit().en2().set_bit());
+    }
+
+    fn disable(&mut self) {
+        let dac = unsafe { &(*DAC::ptr()) };
+        dac.cr()
+            .modify(|_, w| w.en1().clear_bit().en2().clear_bit());
+    }
+}
+
+#[cfg(not(feature = "stm32f410"))]
+impl DacOut<(u16, u16)> for (C1, C2) {
+    fn set_value(&mut self, (val1, val2): (u16, u16)) {
+        let dac = unsafe { &(*DAC::ptr()) };
+        dac.dhr12rd()
+            .write(|w| unsafe { w.bits(((val2 as u32) << 16) | val1 as u32) });
+    }
+
+    fn get_value(&mut self) -> (u16, u16) {
+        (self.0.get_value(), self.1.get_value())
+    }
+}
+
+#[cfg(not(feature = "stm32f410"))]
+impl DacOut<(u8, u8)> for (C1, C2) {
+    fn set_value(&mut self, (val1, val2): (u8, u8)) {
+        let dac = unsafe { &(*DAC::ptr()) };
+        dac.dhr8rd()
+            .write(|w| unsafe { w.bits(((val2 as u32) << 8) | val1 as u32) });
+    }
+
+    fn get_value(&mut self) -> (u8, u8) {
+        (self.0.get_value(), self.1.get_value())
+    }
+}
+
+#[cfg(not(feature = "stm32f410"))]
+impl DacChannel for (C1, C2) {
+    /// Sets the same trigger for both channels, so they are updated simultaneously
+    fn set_trigger(&mut self, trigger: Option<Trigger>) {
+        self.0.set_trigger(trigger);
+        self.1.set_trigger(trigger);
+    }
+
+    fn software_trigger(&mut self) {
+        let dac = unsafe { &(*DAC::ptr()) };
+        dac.swtrigr()
+            .write(|w| w.swtrig1().set_bit().swtrig2().set_bit());
+    }
+
+    fn enable_dma(&mut self, enable: bool) {
+        self.0.enable_dma(enable);
+        self.1.enable_dma(enable);
+    }
+
+    fn listen_underrun(&mut self) {
+        self.0.listen_underrun();
+        self.1.listen_underrun();
+    }
+
+    fn unlisten_underrun(&mut self) {
+        self.0.unlisten_underrun();
+        self.1.unlisten_underrun();
+    }
+
+    fn is_underrun(&self) -> bool {
+        self.0.is_underrun() || self.1.is_underrun()
+    }
+
+    fn clear_underrun(&mut self) {
+        self.0.clear_underrun();
+        self.1.clear_underrun();
+    }
+}
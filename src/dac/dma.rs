@@ -0,0 +1,150 @@
+//! Waveform playback from DMA buffer
+//!
+//! [`DacDma`] streams 12-bit right aligned samples to a DAC channel, one sample on each
+//! trigger, once or repeatedly. If DMA doesn't write the sample before the next trigger,
+//! DAC sets underrun flag and stops DMA requests, so `on_interrupt` must be called from
+//! both DMA stream and `TIM6_DAC` interrupt handlers: it restarts playback and reports
+//! [`Error::Underrun`].
+//!
+//! ```rust,ignore
+//! let mut dac = dp.DAC.constrain(gpioa.pa4.into_analog());
+//! dac.set_trigger(Some(Trigger::Tim6Trgo));
+//! dac.enable();
+//! let mut timer = dp.TIM6.counter_hz(&clocks);
+//! timer.set_master_mode(pac::tim6::cr2::MMS::Update);
+//! let streams = StreamsTuple::new(dp.DMA1);
+//! static SINE: [u16; 64] = [/* ... */];
+//! let mut playback = DacDma::new(dac, streams.5, &SINE, true);
+//! playback.start();
+//! timer.start(48.kHz()).unwrap();
+//! ```
+
+use super::DacChannel;
+use crate::dma::{
+    config::Priority,
+    traits::{Channel, DMASet, PeriAddress, Stream},
+    ChannelX, DmaDataSize, DmaDirection, DmaEvent, MemoryToPeripheral,
+};
+use crate::Listen;
+use core::sync::atomic::{compiler_fence, Ordering};
+use embedded_dma::ReadBuffer;
+
+/// Errors of waveform playback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Trigger came before DMA wrote the sample, playback was restarted
+    Underrun,
+    /// DMA transfer error, playback is stopped
+    TransferError,
+}
+
+/// DAC channel playing samples from DMA buffer
+pub struct DacDma<CH, STREAM, const CHANNEL: u8, BUF> {
+    ch: CH,
+    stream: STREAM,
+    buf: BUF,
+    len: usize,
+}
+
+impl<CH, STREAM, const CHANNEL: u8, BUF> DacDma<CH, STREAM, CHANNEL, BUF>
+where
+    CH: DacChannel + PeriAddress<MemSize = u16> + DMASet<STREAM, CHANNEL, MemoryToPeripheral>,
+    STREAM: Stream,
+    ChannelX<CHANNEL>: Channel,
+    BUF: ReadBuffer<Word = u16> + 'static,
+{
+    /// Configures `stream` to write samples from `buf` to `ch`, repeatedly if `circular`
+    ///
+    /// # Panics
+    ///
+    /// If `buf` is empty or longer than 65535 samples.
+    pub fn new(mut ch: CH, mut stream: STREAM, buf: BUF, circular: bool) -> Self {
+        // NOTE(unsafe) We own the buffer and don't give access to it until release
+        let (ptr, len) = unsafe { buf.read_buffer() };
+        assert!(len > 0 && len <= u16::MAX as usize);
+
+        Self::stop_stream(&mut stream);
+        stream.set_channel(ChannelX::<CHANNEL>::VALUE);
+        stream.set_direction(DmaDirection::MemoryToPeripheral);
+        stream.set_peripheral_address(ch.address());
+        stream.set_memory_address(ptr as u32);
+        stream.set_number_of_transfers(len as u16);
+        stream.set_priority(Priority::High);
+        unsafe {
+            stream.set_memory_size(DmaDataSize::HalfWord);
+            stream.set_peripheral_size(DmaDataSize::HalfWord);
+        }
+        stream.set_memory_increment(true);
+        stream.set_peripheral_increment(false);
+        stream.set_circular_mode(circular);
+        stream.set_double_buffer(false);
+        stream.set_fifo_enable(false);
+        stream.listen_only(DmaEvent::TransferComplete | DmaEvent::TransferError);
+
+        ch.clear_underrun();
+        ch.listen_underrun();
+
+        Self {
+            ch,
+            stream,
+            buf,
+            len,
+        }
+    }
+
+    fn stop_stream(stream: &mut STREAM) {
+        unsafe { stream.disable() };
+        while stream.is_enabled() {}
+        stream.clear_all_flags();
+    }
+
+    /// Starts playback from the first sample, output changes on the next trigger
+    pub fn start(&mut self) {
+        self.stream.set_number_of_transfers(self.len as u16);
+        // "Preceding reads and writes cannot be moved past subsequent writes"
+        compiler_fence(Ordering::Release);
+        unsafe { self.stream.enable() };
+        self.ch.enable_dma(true);
+    }
+
+    /// Stops DMA, output keeps the last sample
+    pub fn stop(&mut self) {
+        self.ch.enable_dma(false);
+        Self::stop_stream(&mut self.stream);
+        self.ch.clear_underrun();
+        // "No re-ordering of reads and writes across this point is allowed"
+        compiler_fence(Ordering::SeqCst);
+    }
+
+    /// Handles DMA and DAC interrupts, returns `true` when the buffer is played to the end
+    ///
+    /// In circular mode it happens each time playback wraps around.
+    pub fn on_interrupt(&mut self) -> Result<bool, Error> {
+        if self.stream.is_transfer_error() {
+            self.stop();
+            self.stream.clear_transfer_error();
+            return Err(Error::TransferError);
+        }
+        if self.ch.is_underrun() {
+            // DMA requests are stopped by underrun, restart both
+            self.stop();
+            self.start();
+            return Err(Error::Underrun);
+        }
+        if self.stream.is_transfer_complete() {
+            self.stream.clear_transfer_complete();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Stops playback and returns DAC channel, stream and buffer
+    pub fn release(mut self) -> (CH, STREAM, BUF) {
+        self.stop();
+        self.stream
+            .unlisten(DmaEvent::TransferComplete | DmaEvent::TransferError);
+        self.ch.unlisten_underrun();
+        (self.ch, self.stream, self.buf)
+    }
+}
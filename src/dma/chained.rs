@@ -0,0 +1,286 @@
+//! Transfers longer than 65535 items
+//!
+//! Number of items of one DMA transfer is limited by 16-bit `NDTR` register. [`ChainedTransfer`]
+//! splits a large buffer into sequential transfers of up to [`CHUNK_LEN`] items, next one is
+//! started by [`on_interrupt`](ChainedTransfer::on_interrupt), which must be called from the
+//! DMA stream interrupt handler. Transfer complete interrupt is always enabled.
+//!
+//! ```rust,ignore
+//! static mut FRAME: [u16; 320 * 240] = [0; 320 * 240];
+//! let frame = unsafe { &FRAME };
+//! let mut tx = ChainedTransfer::init_memory_to_peripheral(
+//!     streams.4,
+//!     spi_tx,
+//!     frame,
+//!     DmaConfig::default().memory_increment(true),
+//! );
+//! tx.start(|_tx| {});
+//!
+//! #[interrupt]
+//! fn DMA1_STREAM4() {
+//!     match tx.on_interrupt() {
+//!         Ok(true) => {} // whole frame is sent
+//!         Ok(false) => {}
+//!         Err(_e) => {} // chunk failed, next one is not started
+//!     }
+//! }
+//! ```
+
+use super::{
+    config, stream_disable,
+    traits::{Channel, DMASet, Direction, PeriAddress, Stream, StreamISR},
+    ChannelX, DmaEvent, DmaFlag, MemoryToPeripheral, PeripheralToMemory, StreamError, Transfer,
+};
+use crate::Listen;
+use core::{
+    marker::PhantomData,
+    mem, ptr,
+    sync::atomic::{compiler_fence, Ordering},
+};
+use embedded_dma::{ReadBuffer, WriteBuffer};
+use enumflags2::BitFlags;
+
+/// Maximal number of items in one transfer of the chain
+///
+/// It is a multiple of 16, so any burst configuration can be used.
+pub const CHUNK_LEN: u16 = 0xfff0;
+
+/// DMA transfer of a buffer of any length, done by chained transfers
+pub struct ChainedTransfer<STREAM, const CHANNEL: u8, PERIPHERAL, DIRECTION, BUF>
+where
+    STREAM: Stream,
+{
+    stream: STREAM,
+    peripheral: PERIPHERAL,
+    _direction: PhantomData<DIRECTION>,
+    buf: BUF,
+    // Memory address of the next transfer
+    next_address: u32,
+    // Items which are not included in started transfers
+    left: usize,
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, BUF>
+    ChainedTransfer<STREAM, CHANNEL, PERIPHERAL, MemoryToPeripheral, BUF>
+where
+    STREAM: Stream,
+    ChannelX<CHANNEL>: Channel,
+    PERIPHERAL: PeriAddress + DMASet<STREAM, CHANNEL, MemoryToPeripheral>,
+    BUF: ReadBuffer<Word = <PERIPHERAL as PeriAddress>::MemSize> + 'static,
+{
+    /// Configures the DMA stream like [`Transfer::init_memory_to_peripheral`] to send `buf`
+    ///
+    /// # Panics
+    ///
+    /// * When double buffering is enabled in `config`.
+    /// * When `buf` is empty.
+    pub fn init_memory_to_peripheral(
+        stream: STREAM,
+        peripheral: PERIPHERAL,
+        buf: BUF,
+        config: config::DmaConfig,
+    ) -> Self {
+        // NOTE(unsafe) We own the buffer and don't give access to it until release
+        let (ptr, len) = unsafe { buf.read_buffer() };
+        Self::init(stream, peripheral, buf, ptr as u32, len, config)
+    }
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, BUF>
+    ChainedTransfer<STREAM, CHANNEL, PERIPHERAL, PeripheralToMemory, BUF>
+where
+    STREAM: Stream,
+    ChannelX<CHANNEL>: Channel,
+    PERIPHERAL: PeriAddress + DMASet<STREAM, CHANNEL, PeripheralToMemory>,
+    BUF: WriteBuffer<Word = <PERIPHERAL as PeriAddress>::MemSize> + 'static,
+{
+    /// Configures the DMA stream like [`Transfer::init_peripheral_to_memory`] to fill `buf`
+    ///
+    /// # Panics
+    ///
+    /// * When double buffering is enabled in `config`.
+    /// * When `buf` is empty.
+    pub fn init_peripheral_to_memory(
+        stream: STREAM,
+        peripheral: PERIPHERAL,
+        mut buf: BUF,
+        config: config::DmaConfig,
+    ) -> Self {
+        // NOTE(unsafe) We own the buffer and don't give access to it until release
+        let (ptr, len) = unsafe { buf.write_buffer() };
+        Self::init(stream, peripheral, buf, ptr as u32, len, config)
+    }
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, DIR, BUF>
+    ChainedTransfer<STREAM, CHANNEL, PERIPHERAL, DIR, BUF>
+where
+    STREAM: Stream,
+    ChannelX<CHANNEL>: Channel,
+    DIR: Direction,
+    PERIPHERAL: PeriAddress + DMASet<STREAM, CHANNEL, DIR>,
+{
+    fn init(
+        mut stream: STREAM,
+        peripheral: PERIPHERAL,
+        buf: BUF,
+        address: u32,
+        len: usize,
+        config: config::DmaConfig,
+    ) -> Self {
+        assert!(len > 0, "Empty buffer");
+        let first = len.min(CHUNK_LEN as usize);
+        Transfer::<STREAM, CHANNEL, PERIPHERAL, DIR, BUF>::init_common(
+            &mut stream,
+            &peripheral,
+            config,
+            (address, first as u16),
+            None,
+        );
+        stream.set_circular_mode(false);
+        stream.listen(DmaEvent::TransferComplete);
+
+        let mut transfer = Self {
+            stream,
+            peripheral,
+            _direction: PhantomData,
+            buf,
+            next_address: address,
+            left: len - first,
+        };
+        transfer.advance(first);
+        transfer
+    }
+
+    // Moves next address past `n` items which are already given to DMA
+    fn advance(&mut self, n: usize) {
+        if self.stream.memory_increment() {
+            let size = mem::size_of::<<PERIPHERAL as PeriAddress>::MemSize>();
+            self.next_address += (n * size) as u32;
+        }
+    }
+
+    /// Starts the transfer, the closure will be executed right after enabling the stream.
+    pub fn start<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut PERIPHERAL),
+    {
+        // "Preceding reads and writes cannot be moved past subsequent writes"
+        compiler_fence(Ordering::Release);
+
+        unsafe {
+            self.stream.enable();
+        }
+        f(&mut self.peripheral);
+    }
+
+    /// Handles transfer complete interrupt, starts the next transfer of the chain
+    ///
+    /// Returns `true` when the whole buffer is transferred. Error flags are checked first,
+    /// on error the next transfer is not started and the error is returned.
+    pub fn on_interrupt(&mut self) -> Result<bool, StreamError> {
+        self.stream.check_errors()?;
+        if !self.stream.is_transfer_complete() {
+            return Ok(false);
+        }
+        self.stream.clear_transfer_complete();
+        if self.left == 0 {
+            // "Subsequent reads and writes cannot be moved ahead of preceding reads"
+            compiler_fence(Ordering::Acquire);
+            return Ok(true);
+        }
+        let n = self.left.min(CHUNK_LEN as usize);
+        self.stream.set_memory_address(self.next_address);
+        self.stream.set_number_of_transfers(n as u16);
+        self.left -= n;
+        self.advance(n);
+        // All stream flags must be cleared before the stream is enabled
+        self.stream.clear_all_flags();
+        unsafe {
+            self.stream.enable();
+        }
+        Ok(false)
+    }
+
+    /// Number of items which are not transferred yet
+    pub fn remaining(&self) -> usize {
+        self.left + self.stream.number_of_transfers() as usize
+    }
+
+    /// Returns `true` when the whole buffer is transferred
+    pub fn is_complete(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Access the owned peripheral
+    pub fn peripheral(&mut self) -> &mut PERIPHERAL {
+        &mut self.peripheral
+    }
+
+    /// Stops the stream and returns the underlying resources.
+    pub fn release(mut self) -> (STREAM, PERIPHERAL, BUF) {
+        stream_disable(&mut self.stream);
+        compiler_fence(Ordering::SeqCst);
+        self.stream.clear_all_flags();
+        self.stream.unlisten(DmaEvent::TransferComplete);
+
+        unsafe {
+            let stream = ptr::read(&self.stream);
+            let peripheral = ptr::read(&self.peripheral);
+            let buf = ptr::read(&self.buf);
+            mem::forget(self);
+            (stream, peripheral, buf)
+        }
+    }
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, DIR, BUF> crate::Sealed
+    for ChainedTransfer<STREAM, CHANNEL, PERIPHERAL, DIR, BUF>
+where
+    STREAM: Stream,
+{
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, DIR, BUF> crate::ClearFlags
+    for ChainedTransfer<STREAM, CHANNEL, PERIPHERAL, DIR, BUF>
+where
+    STREAM: Stream,
+{
+    type Flag = DmaFlag;
+
+    #[inline(always)]
+    fn clear_flags(&mut self, flags: impl Into<BitFlags<DmaFlag>>) {
+        self.stream.clear_flags(flags)
+    }
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, DIR, BUF> crate::ReadFlags
+    for ChainedTransfer<STREAM, CHANNEL, PERIPHERAL, DIR, BUF>
+where
+    STREAM: Stream,
+{
+    type Flag = DmaFlag;
+
+    #[inline(always)]
+    fn flags(&self) -> BitFlags<DmaFlag> {
+        self.stream.flags()
+    }
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, DIR, BUF> StreamISR
+    for ChainedTransfer<STREAM, CHANNEL, PERIPHERAL, DIR, BUF>
+where
+    STREAM: Stream,
+{
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, DIR, BUF> Drop
+    for ChainedTransfer<STREAM, CHANNEL, PERIPHERAL, DIR, BUF>
+where
+    STREAM: Stream,
+{
+    fn drop(&mut self) {
+        stream_disable(&mut self.stream);
+        compiler_fence(Ordering::SeqCst);
+    }
+}
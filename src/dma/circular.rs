@@ -0,0 +1,231 @@
+//! Circular peripheral to memory transfer
+//!
+//! [`CircTransfer`] lets DMA write peripheral data into a ring buffer endlessly. Received data
+//! is taken with [`read_available`](CircTransfer::read_available), which follows the DMA write
+//! position (`NDTR`) and detects when DMA has overwritten data that wasn't read yet.
+//! Half transfer and transfer complete interrupts can be handled with
+//! [`on_interrupt`](CircTransfer::on_interrupt).
+//!
+//! ```rust,ignore
+//! let rx_buffer = cortex_m::singleton!(: [u8; 256] = [0; 256]).unwrap();
+//! let mut rx = CircTransfer::init_peripheral_to_memory(
+//!     streams.5,
+//!     serial_rx,
+//!     rx_buffer,
+//!     DmaConfig::default().memory_increment(true),
+//! );
+//! rx.start(|_rx| {});
+//! let mut data = [0; 64];
+//! let n = rx.read_available(&mut data)?;
+//! ```
+
+use super::{
+    config, stream_disable,
+    traits::{Channel, DMASet, PeriAddress, Stream},
+    ChannelX, DMAError, PeripheralToMemory, Transfer,
+};
+use core::{
+    mem, ptr,
+    sync::atomic::{compiler_fence, Ordering},
+};
+use embedded_dma::WriteBuffer;
+
+/// Half of the ring buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Half {
+    /// First half, filled at half transfer
+    First,
+    /// Second half, filled at transfer complete
+    Second,
+}
+
+/// Circular peripheral to memory DMA transfer
+pub struct CircTransfer<STREAM, const CHANNEL: u8, PERIPHERAL, BUF>
+where
+    STREAM: Stream,
+{
+    stream: STREAM,
+    peripheral: PERIPHERAL,
+    buf: BUF,
+    len: usize,
+    // Next index to read
+    read_pos: usize,
+    // Transfer complete events which weren't followed by reading across the end of the buffer
+    laps: usize,
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, BUF> CircTransfer<STREAM, CHANNEL, PERIPHERAL, BUF>
+where
+    STREAM: Stream,
+    ChannelX<CHANNEL>: Channel,
+    PERIPHERAL: PeriAddress + DMASet<STREAM, CHANNEL, PeripheralToMemory>,
+    PERIPHERAL::MemSize: Copy,
+    BUF: WriteBuffer<Word = PERIPHERAL::MemSize> + 'static,
+{
+    /// Configures the DMA stream like [`Transfer::init_peripheral_to_memory`] in circular mode
+    ///
+    /// # Panics
+    ///
+    /// * When double buffering is enabled in `config`.
+    /// * When `buf` is empty or longer than 65535 items.
+    pub fn init_peripheral_to_memory(
+        mut stream: STREAM,
+        peripheral: PERIPHERAL,
+        mut buf: BUF,
+        config: config::DmaConfig,
+    ) -> Self {
+        // NOTE(unsafe) We own the buffer and only read parts DMA has already written
+        let (ptr, len) = unsafe { buf.write_buffer() };
+        assert!(len > 0, "Empty buffer");
+        assert!(len <= u16::MAX as usize, "Buffer is too long for DMA");
+        let len = Transfer::<STREAM, CHANNEL, PERIPHERAL, PeripheralToMemory, BUF>::init_common(
+            &mut stream,
+            &peripheral,
+            config,
+            (ptr as u32, len as u16),
+            None,
+        );
+        stream.set_circular_mode(true);
+
+        Self {
+            stream,
+            peripheral,
+            buf,
+            len: len as usize,
+            read_pos: 0,
+            laps: 0,
+        }
+    }
+
+    /// Starts the transfer, the closure will be executed right after enabling the stream.
+    pub fn start<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut PERIPHERAL),
+    {
+        // "Preceding reads and writes cannot be moved past subsequent writes"
+        compiler_fence(Ordering::Release);
+
+        unsafe {
+            self.stream.enable();
+        }
+        f(&mut self.peripheral);
+    }
+
+    /// Index DMA writes next
+    fn write_pos(&self) -> usize {
+        // NDTR is reloaded in circular mode, but can be observed as 0 for a moment
+        (self.len - self.stream.number_of_transfers() as usize) % self.len
+    }
+
+    /// Counts transfer complete event, returns `true` if there was one
+    fn update_laps(&mut self) -> bool {
+        if self.stream.is_transfer_complete() {
+            self.stream.clear_transfer_complete();
+            self.laps += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Number of samples DMA has written and which were not read yet
+    ///
+    /// Returns `Overrun` if DMA has overwritten unread data, the ring is emptied then.
+    pub fn available(&mut self) -> Result<usize, DMAError<()>> {
+        self.update_laps();
+        let mut write = self.write_pos();
+        // Transfer completed just after the check, position is already wrapped
+        if self.update_laps() {
+            write = self.write_pos();
+        }
+        // "Subsequent reads and writes cannot be moved ahead of preceding reads"
+        compiler_fence(Ordering::Acquire);
+
+        match self.laps {
+            0 if write >= self.read_pos => Ok(write - self.read_pos),
+            1 if write < self.read_pos => Ok(self.len - self.read_pos + write),
+            _ => {
+                self.read_pos = write;
+                self.laps = 0;
+                Err(DMAError::Overrun(()))
+            }
+        }
+    }
+
+    /// Copies received data to `dst`, returns number of copied samples
+    ///
+    /// Returns `Overrun` if DMA has overwritten unread data, the ring is emptied then.
+    pub fn read_available(
+        &mut self,
+        dst: &mut [PERIPHERAL::MemSize],
+    ) -> Result<usize, DMAError<()>> {
+        let n = self.available()?.min(dst.len());
+        // NOTE(unsafe) DMA doesn't write to available part of the buffer until it is read
+        let src = unsafe {
+            let (ptr, _) = self.buf.write_buffer();
+            core::slice::from_raw_parts(ptr as *const PERIPHERAL::MemSize, self.len)
+        };
+        let first = n.min(self.len - self.read_pos);
+        dst[..first].copy_from_slice(&src[self.read_pos..self.read_pos + first]);
+        dst[first..n].copy_from_slice(&src[..n - first]);
+
+        // Check that DMA hasn't reached copied data while copying
+        self.available()?;
+
+        self.read_pos += n;
+        if self.read_pos >= self.len {
+            self.read_pos -= self.len;
+            self.laps -= 1;
+        }
+        Ok(n)
+    }
+
+    /// Handles half transfer and transfer complete interrupts, calls `f` with the filled half
+    ///
+    /// Data is still taken with `read_available`.
+    pub fn on_interrupt<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Half),
+    {
+        if self.stream.is_half_transfer() {
+            self.stream.clear_half_transfer();
+            f(Half::First);
+        }
+        if self.update_laps() {
+            f(Half::Second);
+        }
+    }
+
+    /// Access the owned peripheral
+    pub fn peripheral(&mut self) -> &mut PERIPHERAL {
+        &mut self.peripheral
+    }
+
+    /// Stops the stream and returns the underlying resources.
+    pub fn release(mut self) -> (STREAM, PERIPHERAL, BUF) {
+        stream_disable(&mut self.stream);
+        compiler_fence(Ordering::SeqCst);
+        self.stream.clear_all_flags();
+        self.stream.set_circular_mode(false);
+
+        unsafe {
+            let stream = ptr::read(&self.stream);
+            let peripheral = ptr::read(&self.peripheral);
+            let buf = ptr::read(&self.buf);
+            mem::forget(self);
+            (stream, peripheral, buf)
+        }
+    }
+}
+
+impl<STREAM, const CHANNEL: u8, PERIPHERAL, BUF> Drop
+    for CircTransfer<STREAM, CHANNEL, PERIPHERAL, BUF>
+where
+    STREAM: Stream,
+{
+    fn drop(&mut self) {
+        stream_disable(&mut self.stream);
+        compiler_fence(Ordering::SeqCst);
+    }
+}
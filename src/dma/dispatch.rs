@@ -0,0 +1,183 @@
+//! Dispatching DMA stream interrupts to registered handlers
+//!
+//! [`Handlers`] keeps a handler for each stream of one DMA controller: a function and an object
+//! it is called with, usually a [`Transfer`](super::Transfer). [`dma_interrupt!`](crate::dma_interrupt)
+//! defines `DMAx_STREAMy` interrupt handlers which call [`dispatch`](Handlers::dispatch), so the
+//! transfer doesn't have to be shared with interrupt handler through a global variable.
+//!
+//! Handlers are called outside of critical section. If stream interrupt comes without
+//! registered handler, all its flags are cleared.
+//!
+//! ```rust,ignore
+//! static DMA2_HANDLERS: Handlers<pac::DMA2> = Handlers::new();
+//! stm32f4xx_hal::dma_interrupt!(DMA2 => DMA2_HANDLERS);
+//!
+//! let transfer = cortex_m::singleton!(: RxTransfer = Transfer::init_peripheral_to_memory(
+//!     streams.5, rx, buffer, None, config,
+//! )).unwrap();
+//! transfer.start(|_rx| {});
+//! DMA2_HANDLERS.register::<5, _>(transfer, |transfer| {
+//!     transfer.clear_transfer_complete();
+//! });
+//! unsafe { cortex_m::peripheral::NVIC::unmask(pac::Interrupt::DMA2_STREAM5) };
+//! ```
+
+use super::{
+    traits::{Instance, Stream},
+    StreamX,
+};
+use crate::ClearFlags;
+use core::any::TypeId;
+use core::cell::Cell;
+use core::marker::PhantomData;
+use cortex_m::interrupt::Mutex;
+
+const STREAMS: usize = 8;
+
+#[derive(Clone, Copy)]
+struct Entry {
+    target: *mut (),
+    handler: *const (),
+    call: unsafe fn(*mut (), *const ()),
+    type_id: TypeId,
+}
+
+// NOTE(unsafe) Only `Send` targets are registered
+unsafe impl Send for Entry {}
+
+unsafe fn call<T>(target: *mut (), handler: *const ()) {
+    let handler: fn(&mut T) = core::mem::transmute(handler);
+    handler(&mut *(target as *mut T));
+}
+
+/// Interrupt handlers of `DMA` streams
+pub struct Handlers<DMA> {
+    entries: Mutex<Cell<[Option<Entry>; STREAMS]>>,
+    _dma: PhantomData<fn() -> DMA>,
+}
+
+impl<DMA> Handlers<DMA> {
+    /// Creates empty handler table, usually placed in a `static`
+    pub const fn new() -> Self {
+        Self {
+            entries: Mutex::new(Cell::new([None; STREAMS])),
+            _dma: PhantomData,
+        }
+    }
+
+    fn replace(&self, stream: usize, entry: Option<Entry>) -> Option<Entry> {
+        cortex_m::interrupt::free(|cs| {
+            let cell = self.entries.borrow(cs);
+            let mut entries = cell.get();
+            let old = core::mem::replace(&mut entries[stream], entry);
+            cell.set(entries);
+            old
+        })
+    }
+}
+
+impl<DMA: Instance> Handlers<DMA> {
+    /// Sets `handler` called with `target` on interrupt of stream `S`
+    ///
+    /// Previous handler is removed, its target stays borrowed forever.
+    /// Stream events must be also listened to generate interrupt.
+    pub fn register<const S: u8, T: Send + 'static>(
+        &self,
+        target: &'static mut T,
+        handler: fn(&mut T),
+    ) where
+        StreamX<DMA, S>: Stream,
+    {
+        let entry = Entry {
+            target: target as *mut T as *mut (),
+            handler: handler as *const (),
+            call: call::<T>,
+            type_id: TypeId::of::<T>(),
+        };
+        self.replace(S as usize, Some(entry));
+    }
+
+    /// Removes handler of stream `S` and returns its target if it has type `T`
+    ///
+    /// Handler is removed even if its target has another type.
+    pub fn unregister<const S: u8, T: Send + 'static>(&self) -> Option<&'static mut T>
+    where
+        StreamX<DMA, S>: Stream,
+    {
+        let entry = self.replace(S as usize, None)?;
+        if entry.type_id == TypeId::of::<T>() {
+            // NOTE(unsafe) Target was registered as `&'static mut T` and is not used by handler
+            Some(unsafe { &mut *(entry.target as *mut T) })
+        } else {
+            None
+        }
+    }
+
+    /// Handles interrupt of stream `S`: calls its handler or clears its flags
+    pub fn dispatch<const S: u8>(&self)
+    where
+        StreamX<DMA, S>: Stream,
+    {
+        // Entry is taken out while handler runs, so target can't be unregistered meanwhile
+        match self.replace(S as usize, None) {
+            Some(entry) => {
+                unsafe { (entry.call)(entry.target, entry.handler) };
+                cortex_m::interrupt::free(|cs| {
+                    let cell = self.entries.borrow(cs);
+                    let mut entries = cell.get();
+                    // Handler could register another one
+                    entries[S as usize].get_or_insert(entry);
+                    cell.set(entries);
+                });
+            }
+            None => StreamX::<DMA, S>::new().clear_all_flags(),
+        }
+    }
+}
+
+impl<DMA> Default for Handlers<DMA> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Defines `DMAx_STREAMy` interrupt handlers which dispatch to [`Handlers`](crate::dma::dispatch::Handlers)
+///
+/// All streams of the controller or only one stream can be served:
+///
+/// ```rust,ignore
+/// static DMA1_HANDLERS: Handlers<pac::DMA1> = Handlers::new();
+/// static DMA2_HANDLERS: Handlers<pac::DMA2> = Handlers::new();
+/// dma_interrupt!(DMA1 => DMA1_HANDLERS);
+/// dma_interrupt!(DMA2_STREAM3 => DMA2_HANDLERS, 3);
+/// ```
+#[macro_export]
+macro_rules! dma_interrupt {
+    (DMA1 => $handlers:path) => {
+        $crate::dma_interrupt!(DMA1_STREAM0 => $handlers, 0);
+        $crate::dma_interrupt!(DMA1_STREAM1 => $handlers, 1);
+        $crate::dma_interrupt!(DMA1_STREAM2 => $handlers, 2);
+        $crate::dma_interrupt!(DMA1_STREAM3 => $handlers, 3);
+        $crate::dma_interrupt!(DMA1_STREAM4 => $handlers, 4);
+        $crate::dma_interrupt!(DMA1_STREAM5 => $handlers, 5);
+        $crate::dma_interrupt!(DMA1_STREAM6 => $handlers, 6);
+        $crate::dma_interrupt!(DMA1_STREAM7 => $handlers, 7);
+    };
+    (DMA2 => $handlers:path) => {
+        $crate::dma_interrupt!(DMA2_STREAM0 => $handlers, 0);
+        $crate::dma_interrupt!(DMA2_STREAM1 => $handlers, 1);
+        $crate::dma_interrupt!(DMA2_STREAM2 => $handlers, 2);
+        $crate::dma_interrupt!(DMA2_STREAM3 => $handlers, 3);
+        $crate::dma_interrupt!(DMA2_STREAM4 => $handlers, 4);
+        $crate::dma_interrupt!(DMA2_STREAM5 => $handlers, 5);
+        $crate::dma_interrupt!(DMA2_STREAM6 => $handlers, 6);
+        $crate::dma_interrupt!(DMA2_STREAM7 => $handlers, 7);
+    };
+    ($irq:ident => $handlers:path, $stream:literal) => {
+        #[no_mangle]
+        #[allow(non_snake_case)]
+        unsafe extern "C" fn $irq() {
+            $handlers.dispatch::<$stream>();
+        }
+    };
+}
@@ -6,6 +6,20 @@
 //! This module implements Memory To Memory, Peripheral To Memory and Memory to Peripheral
 //! transfers, double buffering is supported only for Peripheral To Memory and Memory to Peripheral
 //! transfers.
+//!
+//! Double buffer transfer can keep its buffers and process each one when DMA has filled or
+//! sent it with safe [Transfer::with_completed_buffer](struct.Transfer.html#method.with_completed_buffer),
+//! or exchange them with `next_transfer`.
+//!
+//! Circular peripheral to memory transfers are done by [`CircTransfer`], buffers longer than
+//! 65535 items are transferred by [`ChainedTransfer`].
+//!
+//! Priorities of all streams of a controller can be reviewed and set with [`Priorities`].
+//!
+//! [`DmaRouteExt::use_dma`] creates transfers with the channel inferred from peripheral and stream.
+//!
+//! Stream interrupts can be dispatched to registered handlers with `dispatch` module
+//! under `dma-dispatch` feature.
 
 use core::{
     fmt::{self, Debug, Formatter},
@@ -20,6 +34,16 @@ use enumflags2::BitFlags;
 
 use crate::{pac, rcc};
 
+pub mod chained;
+pub use chained::ChainedTransfer;
+pub mod circular;
+pub use circular::CircTransfer;
+#[cfg(feature = "dma-dispatch")]
+pub mod dispatch;
+pub mod priorities;
+pub use priorities::Priorities;
+pub mod route;
+pub use route::{DmaRoute, DmaRouteExt};
 pub mod traits;
 use crate::serial::RxISR;
 use traits::{
@@ -51,6 +75,62 @@ impl<T> Debug for DMAError<T> {
     }
 }
 
+/// Stream registers captured when an error is detected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamState {
+    /// Number of remaining transfers (ndt)
+    pub number_of_transfers: u16,
+    /// Memory address (m0ar)
+    pub memory_address: u32,
+    /// Alternate memory address (m1ar)
+    pub alternate_memory_address: u32,
+    /// Peripheral address (par)
+    pub peripheral_address: u32,
+    /// Stream is still enabled (en)
+    pub enabled: bool,
+    /// Buffer in use in double buffer mode (ct)
+    pub current_buffer: CurrentBuffer,
+    /// Fifo level (fs)
+    pub fifo_level: FifoLevel,
+}
+
+impl StreamState {
+    /// Reads current state of `stream`
+    pub fn read<STREAM: Stream>(stream: &STREAM) -> Self {
+        Self {
+            number_of_transfers: stream.number_of_transfers(),
+            memory_address: stream.memory_address(),
+            alternate_memory_address: stream.alternate_memory_address(),
+            peripheral_address: stream.peripheral_address(),
+            enabled: stream.is_enabled(),
+            current_buffer: stream.current_buffer(),
+            fifo_level: stream.fifo_level(),
+        }
+    }
+}
+
+/// Stream errors, see [`Stream::check_errors`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamError {
+    /// Bus error or access to reserved memory (teif), the stream is disabled by hardware.
+    Transfer(StreamState),
+    /// Peripheral request came before previous data was transferred in direct mode (dmeif).
+    DirectMode(StreamState),
+    /// Fifo overrun or underrun, or fifo threshold incompatible with burst size (feif).
+    Fifo(StreamState),
+}
+
+impl StreamError {
+    /// Stream state at the time the error was detected
+    pub fn state(&self) -> &StreamState {
+        match self {
+            StreamError::Transfer(state)
+            | StreamError::DirectMode(state)
+            | StreamError::Fifo(state) => state,
+        }
+    }
+}
+
 // most of STM32F4 have 8 DmaChannel
 #[cfg(not(feature = "gpio-f413"))]
 /// Possible Channel of a DMA Stream.
@@ -246,7 +326,7 @@ unsafe impl PeriAddress for MemoryToMemory<u32> {
 }
 
 /// How full the DMA stream's fifo is.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FifoLevel {
     /// 0 < fifo_level < 1/4.
     GtZeroLtQuarter,
@@ -485,6 +565,11 @@ where
             .write(|w| unsafe { w.pa().bits(value) });
     }
 
+    #[inline(always)]
+    fn peripheral_address(&self) -> u32 {
+        unsafe { Self::st() }.par().read().pa().bits()
+    }
+
     #[inline(always)]
     fn set_memory_address(&mut self, value: u32) {
         unsafe { Self::st() }
@@ -548,6 +633,11 @@ where
             .modify(|_, w| w.pl().set(priority.bits()));
     }
 
+    #[inline(always)]
+    fn priority(&self) -> config::Priority {
+        unsafe { Self::st() }.cr().read().pl().bits().into()
+    }
+
     #[inline(always)]
     fn set_peripheral_increment_offset(&mut self, value: PeripheralIncrementOffset) {
         unsafe { Self::st() }
@@ -572,6 +662,11 @@ where
             .modify(|_, w| w.minc().bit(increment));
     }
 
+    #[inline(always)]
+    fn memory_increment(&self) -> bool {
+        unsafe { Self::st() }.cr().read().minc().bit_is_set()
+    }
+
     #[inline(always)]
     fn set_peripheral_increment(&mut self, increment: bool) {
         unsafe { Self::st() }
@@ -579,6 +674,11 @@ where
             .modify(|_, w| w.pinc().bit(increment));
     }
 
+    #[inline(always)]
+    fn peripheral_increment(&self) -> bool {
+        unsafe { Self::st() }.cr().read().pinc().bit_is_set()
+    }
+
     #[inline(always)]
     fn set_circular_mode(&mut self, value: bool) {
         unsafe { Self::st() }
@@ -586,6 +686,11 @@ where
             .modify(|_, w| w.circ().bit(value));
     }
 
+    #[inline(always)]
+    fn circular_mode(&self) -> bool {
+        unsafe { Self::st() }.cr().read().circ().bit_is_set()
+    }
+
     #[inline(always)]
     fn set_direction(&mut self, direction: DmaDirection) {
         unsafe { Self::st() }
@@ -778,7 +883,7 @@ pub mod config {
     /// Priority of the DMA stream, defaults to `Medium`. If two requests have the same software
     /// priority level, the stream with the lower number takes priority over the stream with the
     /// higher number. For example, Stream 2 takes priority over Stream 4.
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
     pub enum Priority {
         /// Low priority.
         Low,
@@ -801,6 +906,17 @@ pub mod config {
         }
     }
 
+    impl From<u8> for Priority {
+        fn from(bits: u8) -> Self {
+            match bits & 0b11 {
+                0 => Priority::Low,
+                1 => Priority::Medium,
+                2 => Priority::High,
+                _ => Priority::VeryHigh,
+            }
+        }
+    }
+
     /// The level to fill the fifo to before performing the transaction.
     #[derive(Debug, Clone, Copy)]
     pub enum FifoThreshold {
@@ -981,7 +1097,7 @@ where
     _direction: PhantomData<DIRECTION>,
     buf: Option<BUF>,
     double_buf: Option<BUF>,
-    // Used when double buffering
+    // Used when double buffering and to resume paused transfer
     transfer_length: u16,
 }
 
@@ -1423,6 +1539,7 @@ where
     }
 
     /// Pauses the dma stream, the closure will be executed right before disabling the stream.
+    /// The transfer can be continued with [`resume`](Self::resume).
     pub fn pause<F>(&mut self, f: F)
     where
         F: FnOnce(&mut PERIPHERAL),
@@ -1447,11 +1564,65 @@ where
         }
     }
 
+    /// Checks error flags of the underlying DMA stream, see [`Stream::check_errors`].
+    pub fn check_errors(&mut self) -> Result<(), StreamError> {
+        self.stream.check_errors()
+    }
+
+    /// Get the priority level of the underlying DMA stream.
+    pub fn priority(&self) -> config::Priority {
+        self.stream.priority()
+    }
+
     /// Get the number of remaining transfers (ndt) of the underlying DMA stream.
     pub fn number_of_transfers(&self) -> u16 {
         self.stream.number_of_transfers()
     }
 
+    /// Number of items which are not transferred yet, can be used to report progress.
+    /// Same as [`number_of_transfers`](Self::number_of_transfers).
+    pub fn remaining(&self) -> u16 {
+        self.number_of_transfers()
+    }
+
+    /// Continues the transfer stopped by [`pause`](Self::pause) from the item where it stopped,
+    /// the closure will be executed right after enabling the stream. Nothing is done if the
+    /// transfer is complete.
+    ///
+    /// # Panics
+    ///
+    /// If double buffering or circular mode is enabled, because the buffers can't be shifted.
+    pub fn resume<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut PERIPHERAL),
+    {
+        let is_mem2mem = DIR::direction() == DmaDirection::MemoryToMemory;
+        assert!(
+            is_mem2mem || self.double_buf.is_none(),
+            "Double buffering enabled."
+        );
+        assert!(!self.stream.circular_mode(), "Circular mode enabled.");
+        let remaining = self.stream.number_of_transfers();
+        if remaining == 0 {
+            return;
+        }
+        // Stream restarts from the addresses in registers, move them past transferred items
+        let done = u32::from(self.transfer_length - remaining);
+        let offset = done * mem::size_of::<<PERIPHERAL as PeriAddress>::MemSize>() as u32;
+        if self.stream.memory_increment() {
+            let address = self.stream.memory_address();
+            self.stream.set_memory_address(address + offset);
+        }
+        if self.stream.peripheral_increment() {
+            let address = self.stream.peripheral_address();
+            self.stream.set_peripheral_address(address + offset);
+        }
+        self.stream.set_number_of_transfers(remaining);
+        self.transfer_length = remaining;
+        self.stream.clear_all_flags();
+        self.start(f);
+    }
+
     /// Get the underlying stream of the transfer.
     ///
     /// # Safety
@@ -1469,6 +1640,57 @@ where
         while !self.stream.is_transfer_complete() {}
     }
 
+    /// Handles transfer complete event of double buffer transfer. Calls `f` with the items of
+    /// the buffer DMA has just finished with, while DMA works with the other one. Buffers stay
+    /// in place, so received data can be processed or data to send can be refilled without
+    /// replacing buffers.
+    ///
+    /// `NotReady` is returned if the transfer isn't complete yet, `f` is not called then.
+    /// `Overrun` is returned if DMA has switched buffers again while `f` was running, so the
+    /// buffer could be accessed by DMA and the result of `f` is discarded.
+    ///
+    /// # Panics
+    ///
+    /// If double buffering is not enabled.
+    pub fn with_completed_buffer<F, T>(&mut self, f: F) -> Result<T, DMAError<()>>
+    where
+        F: FnOnce(&mut [<PERIPHERAL as PeriAddress>::MemSize], CurrentBuffer) -> T,
+        BUF: 'static,
+    {
+        assert!(self.double_buf.is_some(), "Double buffering disabled");
+        if !self.stream.is_transfer_complete() {
+            return Err(DMAError::NotReady(()));
+        }
+        self.stream.clear_transfer_complete();
+
+        // DMA has switched to the other buffer
+        let completed = !self.stream.current_buffer();
+        // "Subsequent reads and writes cannot be moved ahead of preceding reads"
+        compiler_fence(Ordering::Acquire);
+        // Only the items are lent, buffers themselves can't be replaced while DMA points to them
+        let address = match completed {
+            CurrentBuffer::FirstBuffer => self.stream.memory_address(),
+            CurrentBuffer::SecondBuffer => self.stream.alternate_memory_address(),
+        };
+        // NOTE(unsafe) Both buffers are owned by the transfer and at least `transfer_length`
+        // items long, DMA works with the other one
+        let items = unsafe {
+            core::slice::from_raw_parts_mut(
+                address as *mut <PERIPHERAL as PeriAddress>::MemSize,
+                usize::from(self.transfer_length),
+            )
+        };
+        let r = f(items, completed);
+        // "Preceding reads and writes cannot be moved past subsequent writes"
+        compiler_fence(Ordering::Release);
+
+        if self.stream.is_transfer_complete() {
+            self.stream.clear_transfer_complete();
+            return Err(DMAError::Overrun(()));
+        }
+        Ok(r)
+    }
+
     /// Applies all fields in DmaConfig.
     fn apply_config(stream: &mut STREAM, config: config::DmaConfig) {
         let msize = match mem::size_of::<<PERIPHERAL as PeriAddress>::MemSize>() {
@@ -1640,6 +1862,7 @@ where
         let (buf_ptr, buf_len) = ptr_and_len;
         self.stream.set_memory_address(buf_ptr);
         self.stream.set_number_of_transfers(buf_len);
+        self.transfer_length = buf_len;
         let old_buf = self.buf.replace(new_buf);
 
         unsafe {
@@ -1715,6 +1938,7 @@ where
         let (buf_ptr, buf_len) = ptr_and_len;
         self.stream.set_memory_address(buf_ptr);
         self.stream.set_number_of_transfers(buf_len);
+        self.transfer_length = buf_len;
         self.buf.replace(new_buf);
 
         self.stream.enable();
@@ -0,0 +1,92 @@
+//! Priorities of all streams of one DMA controller
+//!
+//! Arbiter serves pending requests by software priority, then by stream number: lower number
+//! wins among equal priorities. [`Priorities`] collects levels of all 8 streams, so latency
+//! critical transfers can be checked to be first in [`service_order`](Priorities::service_order)
+//! and set in one place before streams are given to transfers.
+//!
+//! ```rust,ignore
+//! let mut streams = StreamsTuple::new(dp.DMA1);
+//! Priorities::read()
+//!     .stream(5, Priority::VeryHigh) // audio
+//!     .stream(6, Priority::Low) // logging
+//!     .apply(&mut streams);
+//! ```
+//!
+//! Transfer configuration also sets priority of its stream, see [`DmaConfig::priority`](super::config::DmaConfig::priority).
+
+use super::{
+    config::Priority,
+    traits::{Instance, Stream},
+    StreamX, StreamsTuple,
+};
+use core::cmp::Reverse;
+use core::marker::PhantomData;
+
+const STREAMS: usize = 8;
+
+/// Priority levels of all streams of `DMA`
+pub struct Priorities<DMA> {
+    levels: [Priority; STREAMS],
+    _dma: PhantomData<DMA>,
+}
+
+impl<DMA: Instance> Priorities<DMA> {
+    /// Reads current priority levels of all streams
+    pub fn read() -> Self {
+        Self {
+            levels: [
+                StreamX::<DMA, 0>::new().priority(),
+                StreamX::<DMA, 1>::new().priority(),
+                StreamX::<DMA, 2>::new().priority(),
+                StreamX::<DMA, 3>::new().priority(),
+                StreamX::<DMA, 4>::new().priority(),
+                StreamX::<DMA, 5>::new().priority(),
+                StreamX::<DMA, 6>::new().priority(),
+                StreamX::<DMA, 7>::new().priority(),
+            ],
+            _dma: PhantomData,
+        }
+    }
+
+    /// Sets priority level of `stream`
+    ///
+    /// # Panics
+    ///
+    /// If `stream` is not in `0..=7`.
+    pub fn stream(mut self, stream: u8, priority: Priority) -> Self {
+        self.levels[stream as usize] = priority;
+        self
+    }
+
+    /// Priority level of `stream`
+    ///
+    /// # Panics
+    ///
+    /// If `stream` is not in `0..=7`.
+    pub fn get(&self, stream: u8) -> Priority {
+        self.levels[stream as usize]
+    }
+
+    /// Stream numbers in the order arbiter serves simultaneous requests
+    pub fn service_order(&self) -> [u8; STREAMS] {
+        let mut order = [0, 1, 2, 3, 4, 5, 6, 7];
+        order.sort_unstable_by_key(|&s| (Reverse(self.levels[s as usize]), s));
+        order
+    }
+
+    /// Writes priority levels to all streams
+    ///
+    /// Priority of enabled stream can't be changed, owning all streams guarantees
+    /// none of them is used by a transfer.
+    pub fn apply(&self, streams: &mut StreamsTuple<DMA>) {
+        streams.0.set_priority(self.levels[0]);
+        streams.1.set_priority(self.levels[1]);
+        streams.2.set_priority(self.levels[2]);
+        streams.3.set_priority(self.levels[3]);
+        streams.4.set_priority(self.levels[4]);
+        streams.5.set_priority(self.levels[5]);
+        streams.6.set_priority(self.levels[6]);
+        streams.7.set_priority(self.levels[7]);
+    }
+}
@@ -0,0 +1,89 @@
+//! Pairing peripherals with DMA streams without naming the channel
+//!
+//! Each peripheral request is connected to a stream by one of its channels, valid combinations
+//! are listed by [`DMASet`] implementations. [`DmaRouteExt::use_dma`] pairs a peripheral with a
+//! stream into [`DmaRoute`], the channel is inferred from `DMASet` when transfer is created, so
+//! it doesn't need to be written by hand. Invalid pairings don't compile.
+//!
+//! ```rust,ignore
+//! let streams = StreamsTuple::new(dp.DMA2);
+//! let tx = serial.split().0;
+//! let transfer = tx
+//!     .use_dma(streams.7)
+//!     .init_memory_to_peripheral(buffer, None, DmaConfig::default().memory_increment(true));
+//! ```
+//!
+//! Peripherals which have their own `use_dma` method (like `Spi` or `I2c`) can be routed with
+//! `DmaRouteExt::use_dma(peripheral, stream)`. If a stream is connected to the peripheral by
+//! several channels, the channel still has to be chosen explicitly.
+
+use super::{
+    config,
+    traits::{Channel, DMASet, PeriAddress, Stream},
+    ChannelX, MemoryToPeripheral, PeripheralToMemory, Transfer,
+};
+use embedded_dma::{ReadBuffer, WriteBuffer};
+
+/// Peripheral paired with DMA stream, ready to create a [`Transfer`]
+pub struct DmaRoute<STREAM, PERIPHERAL> {
+    stream: STREAM,
+    peripheral: PERIPHERAL,
+}
+
+/// Extension trait to pair a peripheral with a DMA stream
+pub trait DmaRouteExt: PeriAddress + Sized {
+    /// Pairs the peripheral with `stream`
+    fn use_dma<STREAM: Stream>(self, stream: STREAM) -> DmaRoute<STREAM, Self>;
+}
+
+impl<PERIPHERAL: PeriAddress> DmaRouteExt for PERIPHERAL {
+    fn use_dma<STREAM: Stream>(self, stream: STREAM) -> DmaRoute<STREAM, Self> {
+        DmaRoute {
+            stream,
+            peripheral: self,
+        }
+    }
+}
+
+impl<STREAM, PERIPHERAL> DmaRoute<STREAM, PERIPHERAL>
+where
+    STREAM: Stream,
+    PERIPHERAL: PeriAddress,
+{
+    /// Creates memory to peripheral transfer on the channel connecting the peripheral to the stream,
+    /// see [`Transfer::init_memory_to_peripheral`]
+    pub fn init_memory_to_peripheral<const CHANNEL: u8, BUF>(
+        self,
+        buf: BUF,
+        double_buf: Option<BUF>,
+        config: config::DmaConfig,
+    ) -> Transfer<STREAM, CHANNEL, PERIPHERAL, MemoryToPeripheral, BUF>
+    where
+        ChannelX<CHANNEL>: Channel,
+        PERIPHERAL: DMASet<STREAM, CHANNEL, MemoryToPeripheral>,
+        BUF: ReadBuffer<Word = <PERIPHERAL as PeriAddress>::MemSize>,
+    {
+        Transfer::init_memory_to_peripheral(self.stream, self.peripheral, buf, double_buf, config)
+    }
+
+    /// Creates peripheral to memory transfer on the channel connecting the peripheral to the stream,
+    /// see [`Transfer::init_peripheral_to_memory`]
+    pub fn init_peripheral_to_memory<const CHANNEL: u8, BUF>(
+        self,
+        buf: BUF,
+        double_buf: Option<BUF>,
+        config: config::DmaConfig,
+    ) -> Transfer<STREAM, CHANNEL, PERIPHERAL, PeripheralToMemory, BUF>
+    where
+        ChannelX<CHANNEL>: Channel,
+        PERIPHERAL: DMASet<STREAM, CHANNEL, PeripheralToMemory>,
+        BUF: WriteBuffer<Word = <PERIPHERAL as PeriAddress>::MemSize>,
+    {
+        Transfer::init_peripheral_to_memory(self.stream, self.peripheral, buf, double_buf, config)
+    }
+
+    /// Splits the pair back
+    pub fn release(self) -> (STREAM, PERIPHERAL) {
+        (self.stream, self.peripheral)
+    }
+}
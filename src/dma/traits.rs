@@ -105,6 +105,9 @@ pub trait Stream: StreamISR + crate::Listen<Event = DmaEvent> + crate::Sealed {
     /// Set the peripheral address (par) of the DMA stream.
     fn set_peripheral_address(&mut self, value: u32);
 
+    /// Get the peripheral address (par) of the DMA stream.
+    fn peripheral_address(&self) -> u32;
+
     /// Set the memory address (m0ar) of the DMA stream.
     fn set_memory_address(&mut self, value: u32);
 
@@ -154,6 +157,9 @@ pub trait Stream: StreamISR + crate::Listen<Event = DmaEvent> + crate::Sealed {
     /// Set the priority (pl) the DMA stream.
     fn set_priority(&mut self, priority: config::Priority);
 
+    /// Get the priority (pl) of the DMA stream.
+    fn priority(&self) -> config::Priority;
+
     /// Set the peripheral increment offset (pincos)
     fn set_peripheral_increment_offset(&mut self, value: PeripheralIncrementOffset);
 
@@ -172,12 +178,21 @@ pub trait Stream: StreamISR + crate::Listen<Event = DmaEvent> + crate::Sealed {
     /// Enable/disable memory increment (minc) for the DMA stream.
     fn set_memory_increment(&mut self, increment: bool);
 
+    /// Get if memory increment (minc) is enabled for the DMA stream.
+    fn memory_increment(&self) -> bool;
+
     /// Enable/disable peripheral increment (pinc) for the DMA stream.
     fn set_peripheral_increment(&mut self, increment: bool);
 
+    /// Get if peripheral increment (pinc) is enabled for the DMA stream.
+    fn peripheral_increment(&self) -> bool;
+
     /// Enable/disable circular mode (circ) for the DMA stream.
     fn set_circular_mode(&mut self, value: bool);
 
+    /// Get if circular mode (circ) is enabled for the DMA stream.
+    fn circular_mode(&self) -> bool;
+
     /// Set the direction (dir) of the DMA stream.
     fn set_direction(&mut self, direction: DmaDirection);
 
@@ -256,6 +271,28 @@ pub trait Stream: StreamISR + crate::Listen<Event = DmaEvent> + crate::Sealed {
 
     /// Get which buffer is currently in use by the DMA.
     fn current_buffer(&self) -> CurrentBuffer;
+
+    /// Checks error flags, clears all of them and returns the most severe error
+    /// (transfer, direct mode, then fifo error) together with the stream state.
+    fn check_errors(&mut self) -> Result<(), StreamError>
+    where
+        Self: Sized,
+    {
+        let flags = self.flags();
+        let errors = DmaFlag::TransferError | DmaFlag::DirectModeError | DmaFlag::FifoError;
+        if !flags.intersects(errors) {
+            return Ok(());
+        }
+        let state = StreamState::read(self);
+        self.clear_flags(flags & errors);
+        Err(if flags.is_transfer_error() {
+            StreamError::Transfer(state)
+        } else if flags.is_direct_mode_error() {
+            StreamError::DirectMode(state)
+        } else {
+            StreamError::Fifo(state)
+        })
+    }
 }
 
 /// DMA direction.
@@ -189,7 +189,6 @@ dma_map!(
     (Stream0<DMA1>:4, pac::UART5, [PeripheralToMemory]), //UART5_RX
     (Stream2<DMA1>:4, pac::UART4, [PeripheralToMemory]), //UART4_RX
     (Stream4<DMA1>:4, pac::UART4, [MemoryToPeripheral]), //UART4_TX
-    //(Stream6<DMA1>:7, pac::DAC2, [MemoryToPeripheral]), //DAC2
 );
 
 #[cfg(any(
@@ -199,11 +198,7 @@ dma_map!(
     feature = "gpio-f446",
     feature = "gpio-f469",
 ))]
-address!(
-    (pac::UART4, dr, u8),
-    (pac::UART5, dr, u8),
-    //(pac::DAC, ??),
-);
+address!((pac::UART4, dr, u8), (pac::UART5, dr, u8),);
 
 #[cfg(any(
     feature = "gpio-f417",
@@ -270,30 +265,15 @@ dma_map!(
     (Stream6<DMA2>:2, CRYP_IN, [MemoryToPeripheral]), //CRYP_IN
 );
 
-/* Not sure how DAC works with DMA
-#[cfg(any(
-    feature = "gpio-f417",
-    feature = "gpio-f410",
-    feature = "gpio-f413",
-    feature = "gpio-f427",
-    feature = "gpio-f446",
-    feature = "gpio-f469",
-))]
+#[cfg(feature = "dac")]
 dma_map!(
-    (Stream5<DMA1>:7, pac::DAC, [MemoryToPeripheral]), //DAC1
+    (Stream5<DMA1>:7, crate::dac::C1, [MemoryToPeripheral]), //DAC1
 );
-#[cfg(any(
-    feature = "gpio-f417",
-    feature = "gpio-f410",
-    feature = "gpio-f413",
-    feature = "gpio-f427",
-    feature = "gpio-f446",
-    feature = "gpio-f469",
-))]
-address!(
-    (pac::DAC, ??),
+
+#[cfg(all(feature = "dac", not(feature = "stm32f410")))]
+dma_map!(
+    (Stream6<DMA1>:7, crate::dac::C2, [MemoryToPeripheral]), //DAC2
 );
-*/
 
 #[cfg(any(
     feature = "gpio-f417",
@@ -0,0 +1,365 @@
+//! Chrom-ART accelerator (DMA2D)
+//!
+//! DMA2D fills rectangles with a color, copies rectangles between images converting pixel
+//! format, and blends images with alpha, without CPU load. Images are byte slices with known
+//! width and [`ColorMode`]. Operations wait until DMA2D finishes, so images are borrowed only
+//! while they are accessed. Transfer complete and error interrupts can be listened with
+//! [`crate::Listen`].
+//!
+//! Note that `ltdc::DisplayController` takes DMA2D for its `draw_rectangle`.
+//!
+//! ```rust,ignore
+//! let mut dma2d = Dma2d::new(dp.DMA2D);
+//! let mut frame = ImageMut::new(&mut frame_buffer, 480, ColorMode::RGB565);
+//! let sprite = Image::new(&SPRITE, 32, ColorMode::ARGB8888);
+//! dma2d.fill(&mut frame, Rect::new(0, 0, 480, 272), 0x0000)?;
+//! dma2d.blend(&sprite, (0, 0), 0xff, &mut frame, Rect::new(100, 100, 32, 32))?;
+//! ```
+
+use crate::pac::DMA2D;
+use crate::rcc::{Enable, Reset};
+use crate::ReadFlags;
+use enumflags2::BitFlags;
+
+/// Pixel format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum ColorMode {
+    ARGB8888 = 0,
+    RGB888 = 1,
+    RGB565 = 2,
+    ARGB1555 = 3,
+    ARGB4444 = 4,
+    /// Input only, 8-bit luminance
+    L8 = 5,
+    /// Input only, 4-bit alpha + 4-bit luminance
+    AL44 = 6,
+    /// Input only, 8-bit alpha + 8-bit luminance
+    AL88 = 7,
+    /// Input only, 4-bit luminance
+    L4 = 8,
+    /// Input only, 8-bit alpha
+    A8 = 9,
+    /// Input only, 4-bit alpha
+    A4 = 10,
+}
+
+impl ColorMode {
+    /// Size of pixel in bits
+    pub const fn bits_per_pixel(self) -> usize {
+        match self {
+            Self::ARGB8888 => 32,
+            Self::RGB888 => 24,
+            Self::RGB565 | Self::ARGB1555 | Self::ARGB4444 | Self::AL88 => 16,
+            Self::L8 | Self::AL44 | Self::A8 => 8,
+            Self::L4 | Self::A4 => 4,
+        }
+    }
+
+    /// Returns `true` if the format can be written by DMA2D
+    pub const fn is_output(self) -> bool {
+        (self as u8) <= Self::ARGB4444 as u8
+    }
+}
+
+/// Rectangle in pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Source image
+pub struct Image<'a> {
+    data: &'a [u8],
+    width: u16,
+    format: ColorMode,
+}
+
+impl<'a> Image<'a> {
+    /// Image of `width` pixels per line stored in `data`
+    pub fn new(data: &'a [u8], width: u16, format: ColorMode) -> Self {
+        Self {
+            data,
+            width,
+            format,
+        }
+    }
+}
+
+/// Destination image
+pub struct ImageMut<'a> {
+    data: &'a mut [u8],
+    width: u16,
+    format: ColorMode,
+}
+
+impl<'a> ImageMut<'a> {
+    /// Image of `width` pixels per line stored in `data`
+    pub fn new(data: &'a mut [u8], width: u16, format: ColorMode) -> Self {
+        Self {
+            data,
+            width,
+            format,
+        }
+    }
+}
+
+/// Returns address of rectangle and line offset after it in pixels
+///
+/// # Panics
+///
+/// If the rectangle doesn't fit the image or doesn't start on byte boundary.
+fn area(data: &[u8], width: u16, format: ColorMode, rect: Rect) -> (u32, u16) {
+    assert!(
+        rect.x as usize + rect.width as usize <= width as usize,
+        "Rectangle is out of image"
+    );
+    let bpp = format.bits_per_pixel();
+    let start = (rect.y as usize * width as usize + rect.x as usize) * bpp;
+    let end = ((rect.y as usize + rect.height as usize) * width as usize) * bpp;
+    assert!(end / 8 <= data.len(), "Rectangle is out of image");
+    assert!(start % 8 == 0, "Rectangle is not byte aligned");
+    (
+        data.as_ptr() as u32 + (start / 8) as u32,
+        width - rect.width,
+    )
+}
+
+/// DMA2D errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Bus error while accessing memory
+    Transfer,
+    /// Wrong configuration, for example misaligned address
+    Configuration,
+}
+
+/// DMA2D interrupt events
+#[enumflags2::bitflags]
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    TransferError = 1 << 8,
+    TransferComplete = 1 << 9,
+    TransferWatermark = 1 << 10,
+    ClutAccessError = 1 << 11,
+    ClutTransferComplete = 1 << 12,
+    ConfigurationError = 1 << 13,
+}
+
+/// DMA2D status flags
+#[enumflags2::bitflags]
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Flag {
+    TransferError = 1 << 0,
+    TransferComplete = 1 << 1,
+    TransferWatermark = 1 << 2,
+    ClutAccessError = 1 << 3,
+    ClutTransferComplete = 1 << 4,
+    ConfigurationError = 1 << 5,
+}
+
+// Transfer modes (CR.MODE)
+const MODE_M2M_PFC: u8 = 0b01;
+const MODE_M2M_BLEND: u8 = 0b10;
+const MODE_R2M: u8 = 0b11;
+// Alpha value of FGPFCCR multiplied with alpha of pixels
+const AM_MULTIPLY: u8 = 0b10;
+
+/// Chrom-ART accelerator
+pub struct Dma2d {
+    dma2d: DMA2D,
+}
+
+impl Dma2d {
+    /// Enables and resets DMA2D
+    pub fn new(dma2d: DMA2D) -> Self {
+        unsafe {
+            DMA2D::enable_unchecked();
+            DMA2D::reset_unchecked();
+        }
+        Self { dma2d }
+    }
+
+    /// Fills `rect` of `dst` with `color` in format of `dst`
+    ///
+    /// # Panics
+    ///
+    /// If `dst` format can't be written by DMA2D or `rect` doesn't fit in it.
+    pub fn fill(&mut self, dst: &mut ImageMut, rect: Rect, color: u32) -> Result<(), Error> {
+        self.set_output(dst, rect);
+        self.dma2d.ocolr().write_with_zero(|w| w.bits(color));
+        self.start(MODE_R2M)
+    }
+
+    /// Copies `rect.width` x `rect.height` pixels from `src` at `src_pos` to `rect` of `dst`,
+    /// converting pixel format
+    ///
+    /// # Panics
+    ///
+    /// If `dst` format can't be written by DMA2D or rectangles don't fit in images.
+    pub fn copy(
+        &mut self,
+        src: &Image,
+        src_pos: (u16, u16),
+        dst: &mut ImageMut,
+        rect: Rect,
+    ) -> Result<(), Error> {
+        self.set_foreground(src, src_pos, rect, 0xff);
+        self.set_output(dst, rect);
+        self.start(MODE_M2M_PFC)
+    }
+
+    /// Blends `rect.width` x `rect.height` pixels of `fg` at `fg_pos` over `rect` of `dst`
+    ///
+    /// Alpha of `fg` pixels is multiplied by `alpha`, so 255 keeps it unchanged.
+    ///
+    /// # Panics
+    ///
+    /// If `dst` format can't be written by DMA2D or rectangles don't fit in images.
+    pub fn blend(
+        &mut self,
+        fg: &Image,
+        fg_pos: (u16, u16),
+        alpha: u8,
+        dst: &mut ImageMut,
+        rect: Rect,
+    ) -> Result<(), Error> {
+        self.set_foreground(fg, fg_pos, rect, alpha);
+        // Destination is also background
+        let (addr, offset) = self.set_output(dst, rect);
+        self.dma2d.bgmar().write_with_zero(|w| w.bits(addr));
+        self.dma2d
+            .bgor()
+            .write(|w| unsafe { w.bits(offset as u32) });
+        self.dma2d
+            .bgpfccr()
+            .write(|w| unsafe { w.cm().bits(dst.format as u8) });
+        self.start(MODE_M2M_BLEND)
+    }
+
+    fn set_foreground(&mut self, src: &Image, (x, y): (u16, u16), rect: Rect, alpha: u8) {
+        let (addr, offset) = area(
+            src.data,
+            src.width,
+            src.format,
+            Rect::new(x, y, rect.width, rect.height),
+        );
+        self.dma2d.fgmar().write_with_zero(|w| w.bits(addr));
+        self.dma2d
+            .fgor()
+            .write(|w| unsafe { w.bits(offset as u32) });
+        self.dma2d.fgpfccr().write(|w| {
+            unsafe {
+                w.cm().bits(src.format as u8);
+                w.am().bits(AM_MULTIPLY);
+            }
+            w.alpha().set(alpha)
+        });
+    }
+
+    fn set_output(&mut self, dst: &mut ImageMut, rect: Rect) -> (u32, u16) {
+        assert!(dst.format.is_output(), "Unsupported output color mode");
+        let (addr, offset) = area(dst.data, dst.width, dst.format, rect);
+        self.dma2d
+            .opfccr()
+            .write(|w| unsafe { w.cm().bits(dst.format as u8) });
+        self.dma2d.omar().write_with_zero(|w| w.bits(addr));
+        self.dma2d.oor().write(|w| unsafe { w.bits(offset as u32) });
+        self.dma2d
+            .nlr()
+            .write(|w| unsafe { w.bits(((rect.width as u32) << 16) | rect.height as u32) });
+        (addr, offset)
+    }
+
+    /// Starts transfer in `mode` and waits for its end
+    fn start(&mut self, mode: u8) -> Result<(), Error> {
+        let errors = Flag::TransferError | Flag::ConfigurationError;
+        self.dma2d
+            .ifcr()
+            .write(|w| unsafe { w.bits((errors | Flag::TransferComplete).bits()) });
+        self.dma2d
+            .cr()
+            .modify(|_, w| w.mode().set(mode).start().set_bit());
+        while self.dma2d.cr().read().start().bit_is_set() {}
+
+        let flags = self.flags();
+        self.dma2d
+            .ifcr()
+            .write(|w| unsafe { w.bits(errors.bits()) });
+        if flags.contains(Flag::ConfigurationError) {
+            Err(Error::Configuration)
+        } else if flags.contains(Flag::TransferError) {
+            Err(Error::Transfer)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Disables DMA2D and returns the peripheral
+    pub fn release(self) -> DMA2D {
+        unsafe {
+            DMA2D::disable_unchecked();
+        }
+        self.dma2d
+    }
+}
+
+impl crate::Listen for Dma2d {
+    type Event = Event;
+    fn listen(&mut self, event: impl Into<BitFlags<Event>>) {
+        let bits = event.into().bits();
+        self.dma2d
+            .cr()
+            .modify(|r, w| unsafe { w.bits(r.bits() | bits) });
+    }
+    fn listen_only(&mut self, event: impl Into<BitFlags<Event>>) {
+        let all = BitFlags::<Event>::ALL.bits();
+        let bits = event.into().bits();
+        self.dma2d
+            .cr()
+            .modify(|r, w| unsafe { w.bits((r.bits() & !all) | bits) });
+    }
+    fn unlisten(&mut self, event: impl Into<BitFlags<Event>>) {
+        let bits = event.into().bits();
+        self.dma2d
+            .cr()
+            .modify(|r, w| unsafe { w.bits(r.bits() & !bits) });
+    }
+}
+
+impl crate::ReadFlags for Dma2d {
+    type Flag = Flag;
+    fn flags(&self) -> BitFlags<Flag> {
+        BitFlags::from_bits_truncate(self.dma2d.isr().read().bits())
+    }
+}
+
+impl crate::ClearFlags for Dma2d {
+    type Flag = Flag;
+    fn clear_flags(&mut self, flags: impl Into<BitFlags<Flag>>) {
+        self.dma2d
+            .ifcr()
+            .write(|w| unsafe { w.bits(flags.into().bits()) });
+    }
+}
@@ -33,6 +33,14 @@ impl Dwt {
     pub unsafe fn release(self) -> (DWT, DCB) {
         (self.dwt, self.dcb)
     }
+    /// Current value of the cycle counter
+    pub fn cycle_count(&self) -> u32 {
+        DWT::cycle_count()
+    }
+    /// Frequency of the cycle counter (HCLK)
+    pub fn clock(&self) -> Hertz {
+        self.clock
+    }
     /// Create a delay instance
     pub fn delay(&self) -> Delay {
         Delay { clock: self.clock }
@@ -66,6 +74,15 @@ impl Delay {
         let ticks = duration.ticks as u64;
         Delay::delay_ticks(DWT::cycle_count(), ticks);
     }
+    /// Delay for `cycles` core clock cycles
+    pub fn delay_cycles(&self, cycles: u32) {
+        Delay::delay_ticks(DWT::cycle_count(), cycles as u64);
+    }
+    /// Converts `value` with `per_sec` units in second to ticks, rounding up
+    /// so delay is never shorter than requested
+    fn ticks_for(&self, value: u64, per_sec: u64) -> u64 {
+        (value * self.clock.raw() as u64 + per_sec - 1) / per_sec
+    }
     /// Delay ticks
     /// NOTE DCB and DWT need to be set up for this to work, so it is private
     fn delay_ticks(mut start: u32, ticks: u64) {
@@ -101,7 +118,7 @@ impl<T: Into<u64>> embedded_hal_02::blocking::delay::DelayUs<T> for Delay {
     fn delay_us(&mut self, us: T) {
         // Convert us to ticks
         let start = DWT::cycle_count();
-        let ticks = (us.into() * self.clock.raw() as u64) / 1_000_000;
+        let ticks = self.ticks_for(us.into(), 1_000_000);
         Delay::delay_ticks(start, ticks);
     }
 }
@@ -109,7 +126,7 @@ impl<T: Into<u64>> embedded_hal_02::blocking::delay::DelayMs<T> for Delay {
     fn delay_ms(&mut self, ms: T) {
         // Convert ms to ticks
         let start = DWT::cycle_count();
-        let ticks = (ms.into() * self.clock.raw() as u64) / 1_000;
+        let ticks = self.ticks_for(ms.into(), 1_000);
         Delay::delay_ticks(start, ticks);
     }
 }
@@ -118,21 +135,21 @@ impl embedded_hal::delay::DelayNs for Delay {
     fn delay_ns(&mut self, ns: u32) {
         // Convert us to ticks
         let start = DWT::cycle_count();
-        let ticks = (ns as u64 * self.clock.raw() as u64) / 1_000_000_000;
+        let ticks = self.ticks_for(ns as u64, 1_000_000_000);
         Delay::delay_ticks(start, ticks);
     }
 
     fn delay_us(&mut self, us: u32) {
         // Convert us to ticks
         let start = DWT::cycle_count();
-        let ticks = (us as u64 * self.clock.raw() as u64) / 1_000_000;
+        let ticks = self.ticks_for(us as u64, 1_000_000);
         Delay::delay_ticks(start, ticks);
     }
 
     fn delay_ms(&mut self, ms: u32) {
         // Convert ms to ticks
         let start = DWT::cycle_count();
-        let ticks = (ms as u64 * self.clock.raw() as u64) / 1_000;
+        let ticks = self.ticks_for(ms as u64, 1_000);
         Delay::delay_ticks(start, ticks);
     }
 }
@@ -176,12 +193,19 @@ impl<'l> StopWatch<'l> {
     /// If lap count exceeds maximum, the last lap is updated
     pub fn lap(&mut self) -> &mut Self {
         let c = DWT::cycle_count();
-        if self.timei < self.times.len() {
+        if self.timei < self.times.len() - 1 {
             self.timei += 1;
         }
         self.times[self.timei] = c;
         self
     }
+    /// Time passed since the last recorded lap (or reset), no lap is recorded
+    pub fn elapsed(&self) -> ClockDuration {
+        ClockDuration {
+            ticks: DWT::cycle_count().wrapping_sub(self.times[self.timei]),
+            clock: self.clock,
+        }
+    }
     /// Calculate the time of lap n (n starting with 1).
     ///
     /// Returns None if `n` is out of range
@@ -198,7 +222,7 @@ impl<'l> StopWatch<'l> {
 }
 
 /// Clock difference with capability to calculate SI units (s)
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ClockDuration {
     ticks: u32,
     clock: Hertz,
@@ -26,6 +26,13 @@ impl Instance for FMPI2C1 {
     }
 }
 
+impl crate::rcc::PeripheralClock for FMPI2C1 {
+    /// HSI, selected by the driver
+    fn kernel_clock(_clocks: &crate::rcc::Clocks) -> Hertz {
+        crate::rcc::HSI.Hz()
+    }
+}
+
 /// I2C FastMode+ abstraction
 pub struct FMPI2c<I2C: Instance> {
     i2c: I2C,
@@ -34,7 +34,8 @@
 //! - Output
 //!     - **PushPull**: Output which either drives the pin high or low
 //!     - **OpenDrain**: Output which leaves the gate floating, or pulls it do ground in drain
-//!     mode. Can be used as an input in the `open` configuration
+//!     mode. Can be used as an input in the `open` configuration. `OpenDrain<true>` also
+//!     enables internal pull-up resistor
 //!
 //! ## Changing modes
 //! The simplest way to change the pin mode is to use the `into_<mode>` functions. These return a
@@ -53,23 +54,37 @@
 //!
 //! To make a pin dynamic, use the `into_dynamic` function, and then use the `make_<mode>` functions to
 //! change the mode
+//!
+//! ## Whole port access
+//! `Parts::into_port` gives up all pins of the port and returns [`Port`], which reads and writes
+//! all 16 pins at once with bit masks. Parallel buses on consecutive pins are in [`bus`].
 
 use core::marker::PhantomData;
 
 pub mod alt;
+pub mod bus;
 mod convert;
+pub mod debounce;
 pub use convert::PinMode;
 mod partially_erased;
 pub use partially_erased::{PEPin, PartiallyErasedPin};
 mod erased;
 pub use erased::{EPin, ErasedPin};
-mod exti;
-pub use exti::ExtiPin;
+mod any;
+pub use any::AnyPin;
+pub mod exti;
+pub use exti::{ExtiExt, ExtiPin};
 mod dynamic;
 pub use dynamic::{Dynamic, DynamicPin};
+mod lock;
+pub use lock::LockedPin;
+mod tristate;
+pub use tristate::TriState;
 mod hal_02;
 mod hal_1;
 pub mod outport;
+mod port;
+pub use port::Port;
 
 pub use embedded_hal_02::digital::v2::PinState;
 
@@ -127,9 +142,11 @@ pub enum Pull {
 }
 
 /// Open drain input or output (type state)
+///
+/// - `PULL_UP` enables internal pull-up resistor of the output.
 #[derive(Debug, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct OpenDrain;
+pub struct OpenDrain<const PULL_UP: bool = false>;
 
 /// Output mode (type state)
 #[derive(Debug, Default)]
@@ -169,7 +186,7 @@ pub(crate) mod marker {
 impl<MODE> marker::Interruptible for Output<MODE> {}
 impl marker::Interruptible for Input {}
 impl marker::Readable for Input {}
-impl marker::Readable for Output<OpenDrain> {}
+impl<const PULL_UP: bool> marker::Readable for Output<OpenDrain<PULL_UP>> {}
 impl<const A: u8, Otype> marker::Interruptible for Alternate<A, Otype> {}
 impl<const A: u8, Otype> marker::Readable for Alternate<A, Otype> {}
 impl marker::Active for Input {}
@@ -578,6 +595,13 @@ macro_rules! gpio {
                 }
             }
 
+            impl Parts {
+                /// Gives up all pins to access the port as a whole, see [`Port`](super::Port)
+                pub fn into_port(self) -> super::Port<$port_id> {
+                    super::Port::new()
+                }
+            }
+
             #[doc="Common type for "]
             #[doc=stringify!($GPIOX)]
             #[doc=" related pins"]
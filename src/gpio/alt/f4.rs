@@ -4459,6 +4459,11 @@ pub mod uart7 {
         type Rx<Otype> = Rx<Otype>;
         type Tx<Otype> = Tx<Otype>;
     }
+    // No hardware flow control
+    impl SerialRs232 for UART {
+        type Cts = NoPin;
+        type Rts = NoPin;
+    }
 }
 
 #[cfg(feature = "uart8")]
@@ -4486,6 +4491,11 @@ pub mod uart8 {
         type Rx<Otype> = Rx<Otype>;
         type Tx<Otype> = Tx<Otype>;
     }
+    // No hardware flow control
+    impl SerialRs232 for UART {
+        type Cts = NoPin;
+        type Rts = NoPin;
+    }
 }
 
 #[cfg(feature = "uart9")]
@@ -4511,6 +4521,11 @@ pub mod uart9 {
         type Rx<Otype> = Rx<Otype>;
         type Tx<Otype> = Tx<Otype>;
     }
+    // No hardware flow control
+    impl SerialRs232 for UART {
+        type Cts = NoPin;
+        type Rts = NoPin;
+    }
 }
 
 #[cfg(feature = "uart10")]
@@ -4536,6 +4551,11 @@ pub mod uart10 {
         type Rx<Otype> = Rx<Otype>;
         type Tx<Otype> = Tx<Otype>;
     }
+    // No hardware flow control
+    impl SerialRs232 for UART {
+        type Cts = NoPin;
+        type Rts = NoPin;
+    }
 }
 
 #[cfg(feature = "otg-fs")]
@@ -0,0 +1,183 @@
+use super::*;
+
+/// Fully erased pin which keeps port register block address and pin mask
+///
+/// Unlike [`ErasedPin`] port register block is not calculated on each access, so pin
+/// operations compile to the same single register access as of [`Pin`]. Takes two words.
+///
+/// `MODE` is one of the pin modes (see [Modes](crate::gpio#modes) section).
+pub struct AnyPin<MODE> {
+    pub(super) block: *const crate::pac::gpioa::RegisterBlock,
+    pub(super) mask: u32,
+    _mode: PhantomData<MODE>,
+}
+
+// NOTE(unsafe) `block` points to memory mapped GPIO registers, accesses are atomic
+unsafe impl<MODE> Send for AnyPin<MODE> {}
+
+impl<MODE> fmt::Debug for AnyPin<MODE> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_fmt(format_args!(
+            "P({}{})<{}>",
+            self.port_id(),
+            self.pin_id(),
+            crate::stripped_type_name::<MODE>()
+        ))
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<MODE> defmt::Format for AnyPin<MODE> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "P({}{})<{}>",
+            self.port_id(),
+            self.pin_id(),
+            crate::stripped_type_name::<MODE>()
+        );
+    }
+}
+
+impl<MODE> PinExt for AnyPin<MODE> {
+    type Mode = MODE;
+
+    #[inline(always)]
+    fn pin_id(&self) -> u8 {
+        self.mask.trailing_zeros() as u8
+    }
+    #[inline(always)]
+    fn port_id(&self) -> u8 {
+        ((self.block as usize - crate::pac::GPIOA::ptr() as usize) / 0x0400) as u8
+    }
+}
+
+impl<MODE> AnyPin<MODE> {
+    fn new(block: *const crate::pac::gpioa::RegisterBlock, pin: u8) -> Self {
+        Self::from_block_mask(block, 1 << pin)
+    }
+
+    pub(super) fn from_block_mask(
+        block: *const crate::pac::gpioa::RegisterBlock,
+        mask: u32,
+    ) -> Self {
+        Self {
+            block,
+            mask,
+            _mode: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn block(&self) -> &crate::pac::gpioa::RegisterBlock {
+        unsafe { &*self.block }
+    }
+
+    /// Convert type erased pin to `Pin` with fixed type
+    pub fn restore<const P: char, const N: u8>(self) -> Pin<P, N, MODE> {
+        assert_eq!(self.block, gpiox::<P>());
+        assert_eq!(self.pin_id(), N);
+        Pin::new()
+    }
+}
+
+impl<MODE> AnyPin<Output<MODE>> {
+    /// Drives the pin high
+    #[inline(always)]
+    pub fn set_high(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { self.block().bsrr().write(|w| w.bits(self.mask)) };
+    }
+
+    /// Drives the pin low
+    #[inline(always)]
+    pub fn set_low(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { self.block().bsrr().write(|w| w.bits(self.mask << 16)) };
+    }
+
+    /// Is the pin in drive high or low mode?
+    #[inline(always)]
+    pub fn get_state(&self) -> PinState {
+        if self.is_set_low() {
+            PinState::Low
+        } else {
+            PinState::High
+        }
+    }
+
+    /// Drives the pin high or low depending on the provided value
+    #[inline(always)]
+    pub fn set_state(&mut self, state: PinState) {
+        match state {
+            PinState::Low => self.set_low(),
+            PinState::High => self.set_high(),
+        }
+    }
+
+    /// Is the pin in drive high mode?
+    #[inline(always)]
+    pub fn is_set_high(&self) -> bool {
+        !self.is_set_low()
+    }
+
+    /// Is the pin in drive low mode?
+    #[inline(always)]
+    pub fn is_set_low(&self) -> bool {
+        self.block().odr().read().bits() & self.mask == 0
+    }
+
+    /// Toggle pin output
+    #[inline(always)]
+    pub fn toggle(&mut self) {
+        if self.is_set_low() {
+            self.set_high()
+        } else {
+            self.set_low()
+        }
+    }
+}
+
+impl<MODE> AnyPin<MODE>
+where
+    MODE: marker::Readable,
+{
+    /// Is the input pin high?
+    #[inline(always)]
+    pub fn is_high(&self) -> bool {
+        !self.is_low()
+    }
+
+    /// Is the input pin low?
+    #[inline(always)]
+    pub fn is_low(&self) -> bool {
+        self.block().idr().read().bits() & self.mask == 0
+    }
+}
+
+impl<const P: char, const N: u8, MODE> From<Pin<P, N, MODE>> for AnyPin<MODE> {
+    /// Pin-to-erased pin conversion using the [`From`] trait.
+    ///
+    /// Note that [`From`] is the reciprocal of [`Into`].
+    fn from(_: Pin<P, N, MODE>) -> Self {
+        Self::new(gpiox::<P>(), N)
+    }
+}
+
+impl<const P: char, MODE> From<PartiallyErasedPin<P, MODE>> for AnyPin<MODE> {
+    /// Partially erased pin-to-erased pin conversion using the [`From`] trait.
+    ///
+    /// Note that [`From`] is the reciprocal of [`Into`].
+    fn from(p: PartiallyErasedPin<P, MODE>) -> Self {
+        Self::new(gpiox::<P>(), p.i)
+    }
+}
+
+impl<MODE> From<ErasedPin<MODE>> for AnyPin<MODE> {
+    /// Erased pin-to-erased pin conversion using the [`From`] trait.
+    ///
+    /// Note that [`From`] is the reciprocal of [`Into`].
+    fn from(p: ErasedPin<MODE>) -> Self {
+        Self::new(p.block(), p.pin_id())
+    }
+}
@@ -0,0 +1,200 @@
+//! Parallel bus on consecutive pins of one port
+//!
+//! [`Bus8`] and [`Bus16`] write and read all lines of the bus in one register access and switch
+//! its direction at runtime. Together with [`Strobe`] lines they can drive 8080-style LCDs or
+//! external latches without FSMC.
+//!
+//! ```rust,ignore
+//! let gpiod = dp.GPIOD.split();
+//! let mut bus = Bus8::new([
+//!     gpiod.pd0.into(), gpiod.pd1.into(), gpiod.pd2.into(), gpiod.pd3.into(),
+//!     gpiod.pd4.into(), gpiod.pd5.into(), gpiod.pd6.into(), gpiod.pd7.into(),
+//! ]);
+//! let mut wr = Strobe::new(gpiod.pd8.into_push_pull_output());
+//! let mut rd = Strobe::new(gpiod.pd9.into_push_pull_output());
+//! bus.write_strobed(0x2c, &mut wr);
+//! let status = bus.read_strobed(&mut rd);
+//! ```
+//!
+//! Strobe pulses are as short as two GPIO writes, use [`Strobe::activate`] and
+//! [`Strobe::deactivate`] with a delay if the device needs longer pulses.
+
+use super::{gpiox, Output, PEPin, Pin, PinMode, PushPull};
+
+/// Direction of bus lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// Lines are inputs
+    Input,
+    /// Lines are push pull outputs
+    Output,
+}
+
+/// Parallel bus of `WIDTH` consecutive pins of port `P`
+pub struct Bus<const P: char, const WIDTH: usize> {
+    pins: [PEPin<P, Output<PushPull>>; WIDTH],
+    first: u8,
+    direction: Direction,
+}
+
+/// 8 bit parallel bus
+pub type Bus8<const P: char> = Bus<P, 8>;
+/// 16 bit parallel bus
+pub type Bus16<const P: char> = Bus<P, 16>;
+
+impl<const P: char, const WIDTH: usize> Bus<P, WIDTH> {
+    /// Makes a bus of `pins` and configures them as push pull outputs
+    ///
+    /// Pin `i` of the array is bit `i` of bus value.
+    ///
+    /// # Panics
+    ///
+    /// If `pins` are not consecutive pins in ascending order.
+    pub fn new<MODE: PinMode>(pins: [PEPin<P, MODE>; WIDTH]) -> Self {
+        let first = pins[0].i;
+        for (idx, pin) in pins.iter().enumerate() {
+            assert_eq!(pin.i as usize, first as usize + idx);
+        }
+        Self {
+            pins: pins.map(|pin| pin.into_mode()),
+            first,
+            direction: Direction::Output,
+        }
+    }
+
+    const fn value_mask() -> u32 {
+        if WIDTH >= 32 {
+            u32::MAX
+        } else {
+            (1 << WIDTH) - 1
+        }
+    }
+
+    /// Current direction of the bus
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Switches direction of all lines
+    pub fn set_direction(&mut self, direction: Direction) {
+        if direction == self.direction {
+            return;
+        }
+        let width_fields = if WIDTH >= 16 {
+            u32::MAX
+        } else {
+            (1 << (2 * WIDTH)) - 1
+        };
+        let fields = width_fields << (2 * self.first);
+        let value = match direction {
+            Direction::Input => 0,
+            Direction::Output => 0x5555_5555 & fields,
+        };
+        unsafe {
+            (*gpiox::<P>())
+                .moder()
+                .modify(|r, w| w.bits((r.bits() & !fields) | value));
+        }
+        self.direction = direction;
+    }
+
+    /// Configures lines as inputs
+    pub fn make_input(&mut self) {
+        self.set_direction(Direction::Input)
+    }
+
+    /// Configures lines as push pull outputs
+    pub fn make_output(&mut self) {
+        self.set_direction(Direction::Output)
+    }
+
+    /// Sets output levels of lines to the bits of `value` in one atomic write
+    ///
+    /// Levels are latched in input direction too and appear on lines when they become outputs.
+    #[inline(always)]
+    pub fn write(&mut self, value: u16) {
+        let value = u32::from(value) & Self::value_mask();
+        let set = value << self.first;
+        let reset = (!value & Self::value_mask()) << self.first;
+        unsafe {
+            (*gpiox::<P>())
+                .bsrr()
+                .write(|w| w.bits(set | (reset << 16)))
+        }
+    }
+
+    /// Reads levels of lines
+    #[inline(always)]
+    pub fn read(&self) -> u16 {
+        let idr = unsafe { (*gpiox::<P>()).idr().read().bits() };
+        ((idr >> self.first) & Self::value_mask()) as u16
+    }
+
+    /// Drives `value` to the bus and pulses `wr`
+    ///
+    /// Bus is switched to output direction if needed.
+    pub fn write_strobed<const SP: char, const SN: u8>(
+        &mut self,
+        value: u16,
+        wr: &mut Strobe<SP, SN>,
+    ) {
+        self.write(value);
+        self.make_output();
+        wr.pulse();
+    }
+
+    /// Activates `rd`, reads the bus and deactivates `rd`
+    ///
+    /// Bus is switched to input direction if needed.
+    pub fn read_strobed<const SP: char, const SN: u8>(&mut self, rd: &mut Strobe<SP, SN>) -> u16 {
+        self.make_input();
+        rd.activate();
+        let value = self.read();
+        rd.deactivate();
+        value
+    }
+
+    /// Configures lines as outputs and returns them
+    pub fn release(mut self) -> [PEPin<P, Output<PushPull>>; WIDTH] {
+        self.make_output();
+        self.pins
+    }
+}
+
+/// Active low strobe line, like WR or RD of 8080 interface
+pub struct Strobe<const P: char, const N: u8> {
+    pin: Pin<P, N, Output<PushPull>>,
+}
+
+impl<const P: char, const N: u8> Strobe<P, N> {
+    /// Makes strobe of `pin` and sets it inactive (high)
+    pub fn new(mut pin: Pin<P, N, Output<PushPull>>) -> Self {
+        pin.set_high();
+        Self { pin }
+    }
+
+    /// Drives the line low
+    #[inline(always)]
+    pub fn activate(&mut self) {
+        self.pin.set_low()
+    }
+
+    /// Drives the line high
+    #[inline(always)]
+    pub fn deactivate(&mut self) {
+        self.pin.set_high()
+    }
+
+    /// Drives a short low pulse
+    #[inline(always)]
+    pub fn pulse(&mut self) {
+        self.activate();
+        self.deactivate();
+    }
+
+    /// Returns the pin
+    pub fn release(self) -> Pin<P, N, Output<PushPull>> {
+        self.pin
+    }
+}
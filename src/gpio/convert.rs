@@ -61,6 +61,12 @@ impl<const P: char, const N: u8, MODE: PinMode> Pin<P, N, MODE> {
         self.into_mode()
     }
 
+    /// Configures the pin to operate as an open drain output pin with internal pull-up resistor
+    /// Initial state will be low.
+    pub fn into_pull_up_open_drain_output(self) -> Pin<P, N, Output<OpenDrain<true>>> {
+        self.into_mode()
+    }
+
     /// Configures the pin to operate as an push pull output pin
     /// Initial state will be low.
     pub fn into_push_pull_output(mut self) -> Pin<P, N, Output<PushPull>> {
@@ -136,6 +142,14 @@ macro_rules! change_mode {
                 }
             }
 
+            // Pull resistor of the previous mode is disabled
+            if MODE::PUPDR != M::PUPDR {
+                let pupdr = M::PUPDR.unwrap_or(0);
+                $block
+                    .pupdr()
+                    .modify(|r, w| w.bits((r.bits() & !(0b11 << offset)) | (pupdr << offset)));
+            }
+
             if MODE::MODER != M::MODER {
                 $block
                     .moder()
@@ -162,6 +176,22 @@ impl<MODE: PinMode> ErasedPin<MODE> {
     }
 }
 
+use super::AnyPin;
+impl<MODE: PinMode> AnyPin<MODE> {
+    #[inline(always)]
+    pub(super) fn mode<M: PinMode>(&mut self) {
+        let n = self.pin_id();
+        change_mode!(self.block(), n);
+    }
+
+    #[inline(always)]
+    /// Converts pin into specified mode
+    pub fn into_mode<M: PinMode>(mut self) -> AnyPin<M> {
+        self.mode::<M>();
+        AnyPin::from_block_mask(self.block, self.mask)
+    }
+}
+
 use super::PartiallyErasedPin;
 impl<const P: char, MODE: PinMode> PartiallyErasedPin<P, MODE> {
     #[inline(always)]
@@ -305,6 +335,8 @@ pub trait PinMode: crate::Sealed {
     const OTYPER: Option<u32> = None;
     #[doc(hidden)]
     const AFR: Option<u32> = None;
+    #[doc(hidden)]
+    const PUPDR: Option<u32> = None;
 }
 
 impl crate::Sealed for Input {}
@@ -323,6 +355,12 @@ impl PinMode for Output<OpenDrain> {
     const OTYPER: Option<u32> = Some(0b1);
 }
 
+impl PinMode for Output<OpenDrain<true>> {
+    const MODER: u32 = 0b01;
+    const OTYPER: Option<u32> = Some(0b1);
+    const PUPDR: Option<u32> = Some(0b01);
+}
+
 impl PinMode for Output<PushPull> {
     const MODER: u32 = 0b01;
     const OTYPER: Option<u32> = Some(0b0);
@@ -0,0 +1,164 @@
+//! Debounced button input
+//!
+//! [`DebouncedInput`] samples a pin on each call of [`update`](DebouncedInput::update), which is
+//! usually done from a periodic timer interrupt, and reports [`Event`]s after the level has been
+//! stable for the debounce time.
+//!
+//! ```rust,ignore
+//! let button = gpioc.pc13.into_pull_up_input();
+//! // `update` is called every millisecond
+//! let mut button = DebouncedInput::new(
+//!     button,
+//!     1.millis(),
+//!     Config::default().debounce(20.millis()).long_press(1000.millis()),
+//! );
+//!
+//! // in timer interrupt
+//! match button.update() {
+//!     Some(Event::Pressed) => {}
+//!     Some(Event::LongPress) => {}
+//!     Some(Event::Released) => {}
+//!     None => {}
+//! }
+//! ```
+
+use core::convert::Infallible;
+use embedded_hal::digital::InputPin;
+use fugit::MillisDurationU32 as MilliSeconds;
+
+/// Debounced input event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// Input became active
+    Pressed,
+    /// Input stayed active for long press time
+    LongPress,
+    /// Input became inactive
+    Released,
+}
+
+/// Debounce configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Config {
+    debounce: MilliSeconds,
+    long_press: Option<MilliSeconds>,
+    active_low: bool,
+}
+
+impl Config {
+    /// Time the level has to be stable to be accepted
+    pub fn debounce(mut self, debounce: MilliSeconds) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Time the input has to stay active to report [`Event::LongPress`]
+    pub fn long_press(mut self, long_press: MilliSeconds) -> Self {
+        self.long_press = Some(long_press);
+        self
+    }
+
+    /// Active level of the input, `true` for button connected to ground
+    pub fn active_low(mut self, active_low: bool) -> Self {
+        self.active_low = active_low;
+        self
+    }
+}
+
+impl Default for Config {
+    /// 20 ms debounce, no long press, active low
+    fn default() -> Self {
+        Self {
+            debounce: MilliSeconds::from_ticks(20),
+            long_press: None,
+            active_low: true,
+        }
+    }
+}
+
+/// Pin input filtered from contact bounce
+pub struct DebouncedInput<PIN> {
+    pin: PIN,
+    active_low: bool,
+    debounce_ticks: u32,
+    long_press_ticks: Option<u32>,
+    pressed: bool,
+    changing: u32,
+    held: u32,
+}
+
+impl<PIN> DebouncedInput<PIN>
+where
+    PIN: InputPin<Error = Infallible>,
+{
+    /// Creates debounced input of `pin`, sampled every `tick`
+    ///
+    /// Input is considered inactive at start.
+    ///
+    /// # Panics
+    ///
+    /// If `tick` is zero.
+    pub fn new(pin: PIN, tick: MilliSeconds, config: Config) -> Self {
+        let tick = tick.ticks();
+        assert!(tick > 0);
+        let ticks = |time: MilliSeconds| (time.ticks() + tick - 1) / tick;
+        Self {
+            pin,
+            active_low: config.active_low,
+            debounce_ticks: ticks(config.debounce).max(1),
+            long_press_ticks: config.long_press.map(ticks),
+            pressed: false,
+            changing: 0,
+            held: 0,
+        }
+    }
+
+    /// Samples the pin, must be called every tick
+    pub fn update(&mut self) -> Option<Event> {
+        let low = match self.pin.is_low() {
+            Ok(low) => low,
+            Err(e) => match e {},
+        };
+        let active = low == self.active_low;
+
+        if active != self.pressed {
+            self.changing += 1;
+            if self.changing >= self.debounce_ticks {
+                self.changing = 0;
+                self.held = 0;
+                self.pressed = active;
+                return Some(if active {
+                    Event::Pressed
+                } else {
+                    Event::Released
+                });
+            }
+        } else {
+            self.changing = 0;
+        }
+
+        if self.pressed {
+            if let Some(long_press) = self.long_press_ticks {
+                if self.held < long_press {
+                    self.held += 1;
+                    if self.held == long_press {
+                        return Some(Event::LongPress);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Is debounced input active?
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Returns the pin
+    pub fn release(self) -> PIN {
+        self.pin
+    }
+}
@@ -11,6 +11,7 @@ pub struct DynamicPin<const P: char, const N: u8> {
 }
 
 /// Tracks the current pin state for dynamic pins
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Dynamic {
     /// Floating input mode
     InputFloating,
@@ -22,6 +23,12 @@ pub enum Dynamic {
     OutputPushPull,
     /// Open-drain output mode
     OutputOpenDrain,
+    /// Analog mode
+    Analog,
+    /// Push-pull alternate function mode with function number
+    Alternate(u8),
+    /// Open-drain alternate function mode with function number
+    AlternateOpenDrain(u8),
 }
 
 /// Error for [DynamicPin]
@@ -43,7 +50,7 @@ impl Dynamic {
         use Dynamic::*;
         match self {
             InputFloating | InputPullUp | InputPullDown | OutputOpenDrain => true,
-            OutputPushPull => false,
+            OutputPushPull | Analog | Alternate(_) | AlternateOpenDrain(_) => false,
         }
     }
 
@@ -52,9 +59,20 @@ impl Dynamic {
         use Dynamic::*;
         match self {
             InputFloating | InputPullUp | InputPullDown => false,
+            Analog | Alternate(_) | AlternateOpenDrain(_) => false,
             OutputPushPull | OutputOpenDrain => true,
         }
     }
+
+    /// Is pin in analog mode
+    pub fn is_analog(&self) -> bool {
+        matches!(self, Dynamic::Analog)
+    }
+
+    /// Is pin controlled by alternate function
+    pub fn is_alternate(&self) -> bool {
+        matches!(self, Dynamic::Alternate(_) | Dynamic::AlternateOpenDrain(_))
+    }
 }
 
 // For conversion simplify
@@ -68,6 +86,12 @@ impl<const P: char, const N: u8> DynamicPin<P, N> {
         Self { mode }
     }
 
+    /// Current pin mode
+    #[inline]
+    pub fn mode(&self) -> Dynamic {
+        self.mode
+    }
+
     /// Switch pin into pull-up input
     #[inline]
     pub fn make_pull_up_input(&mut self) {
@@ -117,6 +141,39 @@ impl<const P: char, const N: u8> DynamicPin<P, N> {
         Pin::<P, N, Unknown>::new().into_open_drain_output_in_state(state);
         self.mode = Dynamic::OutputOpenDrain;
     }
+    /// Switch pin into analog mode
+    #[inline]
+    pub fn make_analog(&mut self) {
+        // NOTE(unsafe), we have a mutable reference to the current pin
+        Pin::<P, N, Unknown>::new().into_analog();
+        self.mode = Dynamic::Analog;
+    }
+    /// Switch pin into push-pull alternate function `A`
+    #[inline]
+    pub fn make_alternate<const A: u8>(&mut self)
+    where
+        Pin<P, N>: marker::IntoAf<A>,
+    {
+        // NOTE(unsafe), we have a mutable reference to the current pin
+        Pin::<P, N, Unknown>::new().into_mode::<Alternate<A, PushPull>>();
+        self.mode = Dynamic::Alternate(A);
+    }
+    /// Switch pin into open-drain alternate function `A`
+    #[inline]
+    pub fn make_alternate_open_drain<const A: u8>(&mut self)
+    where
+        Pin<P, N>: marker::IntoAf<A>,
+    {
+        // NOTE(unsafe), we have a mutable reference to the current pin
+        Pin::<P, N, Unknown>::new().into_mode::<Alternate<A, OpenDrain>>();
+        self.mode = Dynamic::AlternateOpenDrain(A);
+    }
+
+    /// Converts pin back into pin with fixed mode `M`
+    #[inline]
+    pub fn into_mode<M: PinMode>(self) -> Pin<P, N, M> {
+        Pin::<P, N, Unknown>::new().into_mode()
+    }
 
     /// Drives the pin high
     pub fn set_high(&mut self) -> Result<(), PinModeError> {
@@ -1,9 +1,32 @@
+//! External interrupts of GPIO pins
+//!
+//! [`ExtiPin`] configures EXTI line of a pin, `Callbacks` under `exti-callbacks` feature
+//! also dispatches line interrupts to registered functions. `ExtiInput` under `async` feature
+//! waits for pin levels and edges asynchronously.
+//!
+//! Lines `16..` are connected to other peripherals, [`ExtiExt`] configures them as interrupt
+//! or event sources, for example to wake up from Stop mode.
+//!
+//! ```rust,ignore
+//! dp.EXTI.listen(Line::RtcWakeup, Edge::Rising);
+//! ```
+
 use super::{marker, Edge, Pin, PinExt};
 use crate::{
     pac::{Interrupt, EXTI},
     syscfg::SysCfg,
 };
 
+#[cfg(feature = "exti-callbacks")]
+mod callbacks;
+#[cfg(feature = "exti-callbacks")]
+pub use callbacks::{Callback, Callbacks};
+
+#[cfg(feature = "async")]
+pub mod asynch;
+#[cfg(feature = "async")]
+pub use asynch::ExtiInput;
+
 impl<const P: char, const N: u8, MODE> Pin<P, N, MODE> {
     /// NVIC interrupt number of interrupt from this pin
     ///
@@ -23,6 +46,20 @@ impl<const P: char, const N: u8, MODE> Pin<P, N, MODE> {
     }
 }
 
+/// NVIC interrupt of GPIO EXTI `line`
+#[cfg(any(feature = "exti-callbacks", feature = "async"))]
+fn interrupt(line: u8) -> Interrupt {
+    match line {
+        0 => Interrupt::EXTI0,
+        1 => Interrupt::EXTI1,
+        2 => Interrupt::EXTI2,
+        3 => Interrupt::EXTI3,
+        4 => Interrupt::EXTI4,
+        5..=9 => Interrupt::EXTI9_5,
+        _ => Interrupt::EXTI15_10,
+    }
+}
+
 /// External Interrupt Pin
 pub trait ExtiPin {
     /// Make corresponding EXTI line sensitive to this pin
@@ -126,3 +163,117 @@ where
         unsafe { ((*EXTI::ptr()).pr().read().bits() & (1 << self.pin_id())) != 0 }
     }
 }
+
+/// EXTI lines connected to other peripherals
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Line {
+    /// PVD output
+    Pvd = 16,
+    /// RTC Alarm A and B
+    RtcAlarm = 17,
+    /// USB OTG FS wakeup
+    #[cfg(feature = "otg-fs")]
+    OtgFsWakeup = 18,
+    /// Ethernet wakeup
+    #[cfg(feature = "eth")]
+    EthernetWakeup = 19,
+    /// USB OTG HS wakeup
+    #[cfg(feature = "otg-hs")]
+    OtgHsWakeup = 20,
+    /// RTC tamper and timestamp
+    RtcTamperTimestamp = 21,
+    /// RTC wakeup timer
+    RtcWakeup = 22,
+    /// LPTIM1 wakeup
+    #[cfg(feature = "lptim1")]
+    Lptim1Wakeup = 23,
+}
+
+impl Line {
+    #[inline(always)]
+    fn mask(self) -> u32 {
+        1 << self as u8
+    }
+}
+
+/// Configuration of EXTI lines connected to other peripherals
+pub trait ExtiExt {
+    /// Generates interrupt on `edge` of `line`
+    fn listen(&mut self, line: Line, edge: Edge);
+
+    /// Stops generating interrupt on `line`, edge triggers are disabled if event is not listened
+    fn unlisten(&mut self, line: Line);
+
+    /// Generates event (for `WFE` wakeup) on `edge` of `line`
+    fn listen_event(&mut self, line: Line, edge: Edge);
+
+    /// Stops generating event on `line`, edge triggers are disabled if interrupt is not listened
+    fn unlisten_event(&mut self, line: Line);
+
+    /// Reads the pending bit of `line`
+    fn is_pending(&self, line: Line) -> bool;
+
+    /// Clears the pending bit of `line`
+    fn clear_pending(&mut self, line: Line);
+}
+
+impl ExtiExt for EXTI {
+    fn listen(&mut self, line: Line, edge: Edge) {
+        set_edge(self, line.mask(), edge);
+        let bit = line.mask();
+        self.imr().modify(|r, w| unsafe { w.bits(r.bits() | bit) });
+    }
+
+    fn unlisten(&mut self, line: Line) {
+        let bit = line.mask();
+        self.imr().modify(|r, w| unsafe { w.bits(r.bits() & !bit) });
+        if self.emr().read().bits() & bit == 0 {
+            clear_edge(self, bit);
+        }
+    }
+
+    fn listen_event(&mut self, line: Line, edge: Edge) {
+        set_edge(self, line.mask(), edge);
+        let bit = line.mask();
+        self.emr().modify(|r, w| unsafe { w.bits(r.bits() | bit) });
+    }
+
+    fn unlisten_event(&mut self, line: Line) {
+        let bit = line.mask();
+        self.emr().modify(|r, w| unsafe { w.bits(r.bits() & !bit) });
+        if self.imr().read().bits() & bit == 0 {
+            clear_edge(self, bit);
+        }
+    }
+
+    #[inline(always)]
+    fn is_pending(&self, line: Line) -> bool {
+        self.pr().read().bits() & line.mask() != 0
+    }
+
+    #[inline(always)]
+    fn clear_pending(&mut self, line: Line) {
+        self.pr().write(|w| unsafe { w.bits(line.mask()) });
+    }
+}
+
+fn set_edge(exti: &EXTI, bit: u32, edge: Edge) {
+    let (rising, falling) = match edge {
+        Edge::Rising => (bit, 0),
+        Edge::Falling => (0, bit),
+        Edge::RisingFalling => (bit, bit),
+    };
+    exti.rtsr()
+        .modify(|r, w| unsafe { w.bits((r.bits() & !bit) | rising) });
+    exti.ftsr()
+        .modify(|r, w| unsafe { w.bits((r.bits() & !bit) | falling) });
+}
+
+fn clear_edge(exti: &EXTI, bit: u32) {
+    exti.rtsr()
+        .modify(|r, w| unsafe { w.bits(r.bits() & !bit) });
+    exti.ftsr()
+        .modify(|r, w| unsafe { w.bits(r.bits() & !bit) });
+}
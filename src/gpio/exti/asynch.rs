@@ -0,0 +1,216 @@
+//! Interrupt driven async GPIO input
+//!
+//! [`ExtiInput`] implements [`embedded_hal_async::digital::Wait`]. Futures enable EXTI line of
+//! the pin while waiting, so [`on_interrupt`] must be called from `EXTI0`-`EXTI4`, `EXTI9_5` and
+//! `EXTI15_10` handlers of used lines. NVIC interrupt of the line is unmasked by
+//! [`ExtiInput::new`].
+//!
+//! ```rust,ignore
+//! let mut button = ExtiInput::new(gpioc.pc13.into_pull_up_input(), &mut syscfg);
+//! button.wait_for_falling_edge().await.unwrap();
+//!
+//! #[interrupt]
+//! fn EXTI15_10() {
+//!     stm32f4xx_hal::gpio::exti::asynch::on_interrupt();
+//! }
+//! ```
+
+use core::cell::Cell;
+use core::convert::Infallible;
+use core::future::poll_fn;
+use core::task::Poll;
+
+use cortex_m::interrupt::{self, Mutex};
+use cortex_m::peripheral::NVIC;
+use embedded_hal::digital::{ErrorType, InputPin};
+
+use super::{interrupt, ExtiPin};
+use crate::gpio::{Edge, PinExt};
+use crate::pac::EXTI;
+use crate::syscfg::SysCfg;
+use crate::waker::WakerRegistration;
+
+const LINES: usize = 16;
+
+static WAKERS: [WakerRegistration; LINES] = [
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+    WakerRegistration::new(),
+];
+
+// Lines with waiting futures
+static WAITING: Mutex<Cell<u16>> = Mutex::new(Cell::new(0));
+
+/// Wakes tasks waiting on GPIO EXTI lines, call from EXTI interrupt handlers
+///
+/// Lines without waiting task are not changed, so they can be handled by other code.
+pub fn on_interrupt() {
+    let exti = unsafe { &*EXTI::ptr() };
+    let pending = interrupt::free(|cs| {
+        let waiting = WAITING.borrow(cs);
+        let enabled = exti.imr().read().bits() & u32::from(waiting.get());
+        let pending = exti.pr().read().bits() & enabled;
+        exti.imr()
+            .modify(|r, w| unsafe { w.bits(r.bits() & !pending) });
+        exti.pr().write(|w| unsafe { w.bits(pending) });
+        waiting.set(waiting.get() & !(pending as u16));
+        pending
+    });
+    for (line, waker) in WAKERS.iter().enumerate() {
+        if pending & (1 << line) != 0 {
+            waker.wake();
+        }
+    }
+}
+
+/// Input pin which waits for levels and edges asynchronously
+pub struct ExtiInput<PIN> {
+    pin: PIN,
+}
+
+impl<PIN> ExtiInput<PIN>
+where
+    PIN: ExtiPin + PinExt + InputPin<Error = Infallible>,
+{
+    /// Connects EXTI line to `pin` and unmasks its NVIC interrupt
+    ///
+    /// NVIC interrupt is shared by lines `5..=9` and `10..=15`, so it stays unmasked
+    /// when `ExtiInput` is released.
+    pub fn new(mut pin: PIN, syscfg: &mut SysCfg) -> Self {
+        pin.make_interrupt_source(syscfg);
+        unsafe { NVIC::unmask(interrupt(pin.pin_id())) };
+        Self { pin }
+    }
+
+    /// Disables EXTI line and returns the pin
+    pub fn release(self) -> PIN {
+        disable(self.pin.pin_id());
+        self.pin
+    }
+
+    fn level(&mut self) -> bool {
+        match self.pin.is_high() {
+            Ok(high) => high,
+            Err(e) => match e {},
+        }
+    }
+
+    // Waits for `edge`, or returns at once if pin already has `level`
+    async fn wait(&mut self, edge: Edge, level: Option<bool>) {
+        let line = self.pin.pin_id();
+        let bit = 1 << line;
+        let exti = unsafe { &*EXTI::ptr() };
+        let (rising, falling) = match edge {
+            Edge::Rising => (bit, 0),
+            Edge::Falling => (0, bit),
+            Edge::RisingFalling => (bit, bit),
+        };
+        interrupt::free(|cs| {
+            exti.rtsr()
+                .modify(|r, w| unsafe { w.bits((r.bits() & !bit) | rising) });
+            exti.ftsr()
+                .modify(|r, w| unsafe { w.bits((r.bits() & !bit) | falling) });
+            exti.pr().write(|w| unsafe { w.bits(bit) });
+            let waiting = WAITING.borrow(cs);
+            waiting.set(waiting.get() | bit as u16);
+            exti.imr().modify(|r, w| unsafe { w.bits(r.bits() | bit) });
+        });
+        // Line is disabled if the future is dropped before completion
+        let _guard = Guard(line);
+
+        // Level is checked after enabling the line, so the edge can't be missed
+        if let Some(high) = level {
+            if self.level() == high {
+                return;
+            }
+        }
+        poll_fn(|cx| {
+            WAKERS[line as usize].register(cx.waker());
+            if interrupt::free(|cs| WAITING.borrow(cs).get() & (bit as u16) == 0) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+struct Guard(u8);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        disable(self.0);
+    }
+}
+
+fn disable(line: u8) {
+    let exti = unsafe { &*EXTI::ptr() };
+    let bit = 1 << line;
+    interrupt::free(|cs| {
+        let waiting = WAITING.borrow(cs);
+        waiting.set(waiting.get() & !(bit as u16));
+        exti.imr().modify(|r, w| unsafe { w.bits(r.bits() & !bit) });
+        exti.pr().write(|w| unsafe { w.bits(bit) });
+    });
+}
+
+impl<PIN> ErrorType for ExtiInput<PIN> {
+    type Error = Infallible;
+}
+
+impl<PIN> InputPin for ExtiInput<PIN>
+where
+    PIN: InputPin<Error = Infallible>,
+{
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_high()
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_low()
+    }
+}
+
+impl<PIN> embedded_hal_async::digital::Wait for ExtiInput<PIN>
+where
+    PIN: ExtiPin + PinExt + InputPin<Error = Infallible>,
+{
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait(Edge::Rising, Some(true)).await;
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait(Edge::Falling, Some(false)).await;
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait(Edge::Rising, None).await;
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait(Edge::Falling, None).await;
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait(Edge::RisingFalling, None).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+//! Per-line GPIO interrupt callbacks
+//!
+//! [`Callbacks`] stores a function for each of 16 GPIO EXTI lines. [`register`](Callbacks::register)
+//! connects the pin to its line, selects the edge, enables the line and unmasks its interrupt
+//! in NVIC. [`exti_interrupt!`](crate::exti_interrupt) defines `EXTI0`-`EXTI4`, `EXTI9_5` and
+//! `EXTI15_10` handlers which clear pending lines and call their callbacks.
+//!
+//! Callbacks are plain functions (closures without captures), they are called outside
+//! of critical section.
+//!
+//! ```rust,ignore
+//! static EXTI_CALLBACKS: Callbacks = Callbacks::new();
+//! stm32f4xx_hal::exti_interrupt!(EXTI_CALLBACKS);
+//!
+//! let mut syscfg = dp.SYSCFG.constrain();
+//! let mut button = gpioc.pc13.into_pull_up_input();
+//! EXTI_CALLBACKS.register(&mut button, &mut syscfg, &mut dp.EXTI, Edge::Falling, || {
+//!     // button pressed
+//! });
+//! ```
+
+use super::{interrupt, ExtiPin};
+use crate::gpio::{Edge, PinExt};
+use crate::pac::EXTI;
+use crate::syscfg::SysCfg;
+use core::cell::Cell;
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
+
+/// Function called on pin edge
+pub type Callback = fn();
+
+const LINES: usize = 16;
+
+/// Callbacks of GPIO EXTI lines
+pub struct Callbacks {
+    handlers: Mutex<Cell<[Option<Callback>; LINES]>>,
+}
+
+impl Callbacks {
+    /// Creates empty callback table, usually placed in a `static`
+    pub const fn new() -> Self {
+        Self {
+            handlers: Mutex::new(Cell::new([None; LINES])),
+        }
+    }
+
+    /// Sets `callback` called on `edge` of `pin` and returns previous callback of its line
+    ///
+    /// The line is connected to the pin and enabled, its interrupt is unmasked in NVIC,
+    /// so this should not be called inside of critical section.
+    pub fn register<PIN>(
+        &self,
+        pin: &mut PIN,
+        syscfg: &mut SysCfg,
+        exti: &mut EXTI,
+        edge: Edge,
+        callback: Callback,
+    ) -> Option<Callback>
+    where
+        PIN: ExtiPin + PinExt,
+    {
+        let line = pin.pin_id();
+        let old = self.replace(line, Some(callback));
+        pin.make_interrupt_source(syscfg);
+        pin.trigger_on_edge(exti, edge);
+        pin.clear_interrupt_pending_bit();
+        pin.enable_interrupt(exti);
+        unsafe { NVIC::unmask(interrupt(line)) };
+        old
+    }
+
+    /// Disables interrupt of `pin` line, removes its callback and returns it
+    ///
+    /// NVIC interrupt stays unmasked, as it can be shared with other lines.
+    pub fn unregister<PIN>(&self, pin: &mut PIN, exti: &mut EXTI) -> Option<Callback>
+    where
+        PIN: ExtiPin + PinExt,
+    {
+        pin.disable_interrupt(exti);
+        pin.clear_interrupt_pending_bit();
+        self.replace(pin.pin_id(), None)
+    }
+
+    fn replace(&self, line: u8, callback: Option<Callback>) -> Option<Callback> {
+        cortex_m::interrupt::free(|cs| {
+            let cell = self.handlers.borrow(cs);
+            let mut handlers = cell.get();
+            let old = core::mem::replace(&mut handlers[line as usize], callback);
+            cell.set(handlers);
+            old
+        })
+    }
+
+    /// Handles interrupt of `lines` (bit mask): clears pending enabled lines and calls their callbacks
+    ///
+    /// Lines without callback are only cleared.
+    pub fn dispatch(&self, lines: u16) {
+        // Only pending and mask registers are accessed
+        let exti = unsafe { &*EXTI::ptr() };
+        let pending = exti.pr().read().bits() & exti.imr().read().bits() & u32::from(lines);
+        exti.pr().write(|w| unsafe { w.bits(pending) });
+        let handlers = cortex_m::interrupt::free(|cs| self.handlers.borrow(cs).get());
+        for (line, handler) in handlers.iter().enumerate() {
+            if pending & (1 << line) != 0 {
+                if let Some(callback) = handler {
+                    callback();
+                }
+            }
+        }
+    }
+}
+
+impl Default for Callbacks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Defines GPIO EXTI interrupt handlers which dispatch to [`Callbacks`](crate::gpio::exti::Callbacks)
+///
+/// ```rust,ignore
+/// static EXTI_CALLBACKS: Callbacks = Callbacks::new();
+/// exti_interrupt!(EXTI_CALLBACKS);
+/// ```
+#[macro_export]
+macro_rules! exti_interrupt {
+    ($irq:ident => $callbacks:path, $lines:literal) => {
+        #[no_mangle]
+        #[allow(non_snake_case)]
+        unsafe extern "C" fn $irq() {
+            $callbacks.dispatch($lines);
+        }
+    };
+    ($callbacks:path) => {
+        $crate::exti_interrupt!(EXTI0 => $callbacks, 0x0001);
+        $crate::exti_interrupt!(EXTI1 => $callbacks, 0x0002);
+        $crate::exti_interrupt!(EXTI2 => $callbacks, 0x0004);
+        $crate::exti_interrupt!(EXTI3 => $callbacks, 0x0008);
+        $crate::exti_interrupt!(EXTI4 => $callbacks, 0x0010);
+        $crate::exti_interrupt!(EXTI9_5 => $callbacks, 0x03e0);
+        $crate::exti_interrupt!(EXTI15_10 => $callbacks, 0xfc00);
+    };
+}
@@ -1,8 +1,8 @@
 use core::convert::Infallible;
 
 use super::{
-    dynamic::PinModeError, marker, DynamicPin, ErasedPin, Input, OpenDrain, Output,
-    PartiallyErasedPin, Pin, PinMode, PinState,
+    dynamic::PinModeError, marker, AnyPin, DynamicPin, ErasedPin, Input, LockedPin, OpenDrain,
+    Output, PartiallyErasedPin, Pin, PinMode, PinState,
 };
 
 use embedded_hal_02::digital::v2::{
@@ -105,6 +105,41 @@ where
     }
 }
 
+// Implementations for `LockedPin`
+
+impl<const P: char, const N: u8, MODE> OutputPin for LockedPin<P, N, Output<MODE>> {
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set_high();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set_low();
+        Ok(())
+    }
+}
+
+impl<const P: char, const N: u8, MODE> InputPin for LockedPin<P, N, MODE>
+where
+    MODE: marker::Readable,
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        Ok(Pin::is_high(&**self))
+    }
+
+    #[inline(always)]
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        Ok(Pin::is_low(&**self))
+    }
+}
+
 // Implementations for `ErasedPin`
 
 impl<MODE> OutputPin for ErasedPin<Output<MODE>> {
@@ -162,6 +197,63 @@ where
     }
 }
 
+// Implementations for `AnyPin`
+
+impl<MODE> OutputPin for AnyPin<Output<MODE>> {
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set_high();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set_low();
+        Ok(())
+    }
+}
+
+impl<MODE> StatefulOutputPin for AnyPin<Output<MODE>> {
+    #[inline(always)]
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.is_set_high())
+    }
+
+    #[inline(always)]
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        Ok(self.is_set_low())
+    }
+}
+
+impl<MODE> ToggleableOutputPin for AnyPin<Output<MODE>> {
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.toggle();
+        Ok(())
+    }
+}
+
+impl<MODE> InputPin for AnyPin<MODE>
+where
+    MODE: marker::Readable,
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.is_high())
+    }
+
+    #[inline(always)]
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        Ok(self.is_low())
+    }
+}
+
 // Implementations for `PartiallyErasedPin`
 
 impl<const P: char, MODE> OutputPin for PartiallyErasedPin<P, Output<MODE>> {
@@ -1,7 +1,8 @@
 use core::convert::Infallible;
 
 use super::{
-    dynamic::PinModeError, marker, DynamicPin, ErasedPin, Output, PartiallyErasedPin, Pin,
+    dynamic::PinModeError, marker, AnyPin, DynamicPin, ErasedPin, LockedPin, Output,
+    PartiallyErasedPin, Pin,
 };
 
 use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
@@ -52,6 +53,52 @@ where
     }
 }
 
+// Implementations for `LockedPin`
+impl<const P: char, const N: u8, MODE> ErrorType for LockedPin<P, N, MODE> {
+    type Error = Infallible;
+}
+
+impl<const P: char, const N: u8, MODE> OutputPin for LockedPin<P, N, Output<MODE>> {
+    #[inline(always)]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set_high();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set_low();
+        Ok(())
+    }
+}
+
+impl<const P: char, const N: u8, MODE> StatefulOutputPin for LockedPin<P, N, Output<MODE>> {
+    #[inline(always)]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(Pin::is_set_high(&**self))
+    }
+
+    #[inline(always)]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(Pin::is_set_low(&**self))
+    }
+}
+
+impl<const P: char, const N: u8, MODE> InputPin for LockedPin<P, N, MODE>
+where
+    MODE: marker::Readable,
+{
+    #[inline(always)]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(Pin::is_high(&**self))
+    }
+
+    #[inline(always)]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(Pin::is_low(&**self))
+    }
+}
+
 // Implementations for `ErasedPin`
 impl<MODE> ErrorType for ErasedPin<MODE> {
     type Error = core::convert::Infallible;
@@ -98,6 +145,52 @@ where
     }
 }
 
+// Implementations for `AnyPin`
+impl<MODE> ErrorType for AnyPin<MODE> {
+    type Error = Infallible;
+}
+
+impl<MODE> OutputPin for AnyPin<Output<MODE>> {
+    #[inline(always)]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set_high();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set_low();
+        Ok(())
+    }
+}
+
+impl<MODE> StatefulOutputPin for AnyPin<Output<MODE>> {
+    #[inline(always)]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(Self::is_set_high(self))
+    }
+
+    #[inline(always)]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(Self::is_set_low(self))
+    }
+}
+
+impl<MODE> InputPin for AnyPin<MODE>
+where
+    MODE: marker::Readable,
+{
+    #[inline(always)]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(Self::is_high(self))
+    }
+
+    #[inline(always)]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(Self::is_low(self))
+    }
+}
+
 // Implementations for `PartiallyErasedPin`
 impl<const P: char, MODE> ErrorType for PartiallyErasedPin<P, MODE> {
     type Error = Infallible;
@@ -0,0 +1,94 @@
+use super::*;
+use core::ops::Deref;
+
+/// Pin with configuration locked until reset
+///
+/// Mode, output type, speed, pull and alternate function of the pin can't be changed
+/// neither by software nor by stray writes, see [`Pin::lock`]. Output level and input
+/// can still be used.
+///
+/// Lock key sequence freezes the whole lock register of the port, so only one
+/// [`Pin::lock`] call per port succeeds until reset.
+pub struct LockedPin<const P: char, const N: u8, MODE> {
+    pin: Pin<P, N, MODE>,
+}
+
+impl<const P: char, const N: u8, MODE> Pin<P, N, MODE> {
+    /// Locks configuration of the pin until next reset
+    ///
+    /// Runs the LCKR key sequence in a critical section and checks that the pin is locked.
+    /// Returns the pin back if it isn't, which happens when another pin of the port
+    /// was locked before: lock register can't be changed after the first sequence.
+    pub fn lock(self) -> Result<LockedPin<P, N, MODE>, Self> {
+        let locked = cortex_m::interrupt::free(|_| unsafe {
+            let gpio = &*gpiox::<P>();
+            let pin = 1 << N;
+            gpio.lckr().write(|w| w.bits(pin).lckk().set_bit());
+            gpio.lckr().write(|w| w.bits(pin));
+            gpio.lckr().write(|w| w.bits(pin).lckk().set_bit());
+            gpio.lckr().read();
+            let lckr = gpio.lckr().read();
+            lckr.lckk().bit_is_set() && lckr.bits() & pin != 0
+        });
+        if locked {
+            Ok(LockedPin { pin: self })
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<const P: char, const N: u8, MODE> LockedPin<P, N, MODE> {
+    /// Is configuration of the pin locked?
+    ///
+    /// Always `true`, [`Pin::lock`] checks it.
+    pub fn is_locked(&self) -> bool {
+        unsafe { (*gpiox::<P>()).lckr().read().bits() & (1 << N) != 0 }
+    }
+}
+
+impl<const P: char, const N: u8, MODE> Deref for LockedPin<P, N, MODE> {
+    type Target = Pin<P, N, MODE>;
+    fn deref(&self) -> &Self::Target {
+        &self.pin
+    }
+}
+
+impl<const P: char, const N: u8, MODE> PinExt for LockedPin<P, N, MODE> {
+    type Mode = MODE;
+
+    #[inline(always)]
+    fn pin_id(&self) -> u8 {
+        N
+    }
+    #[inline(always)]
+    fn port_id(&self) -> u8 {
+        P as u8 - b'A'
+    }
+}
+
+impl<const P: char, const N: u8, MODE> LockedPin<P, N, Output<MODE>> {
+    /// Drives the pin high
+    #[inline(always)]
+    pub fn set_high(&mut self) {
+        self.pin.set_high()
+    }
+
+    /// Drives the pin low
+    #[inline(always)]
+    pub fn set_low(&mut self) {
+        self.pin.set_low()
+    }
+
+    /// Drives the pin high or low depending on the provided value
+    #[inline(always)]
+    pub fn set_state(&mut self, state: PinState) {
+        self.pin.set_state(state)
+    }
+
+    /// Toggle pin output
+    #[inline(always)]
+    pub fn toggle(&mut self) {
+        self.pin.toggle()
+    }
+}
@@ -0,0 +1,143 @@
+//! Whole GPIO port access
+//!
+//! [`Port`] owns all pins of a port, so all 16 lines can be read or written in one bus access,
+//! for example for parallel protocols or LED matrices.
+//!
+//! Mode changing methods affect only pins of their `mask`. Be careful with pins in
+//! non-default state after reset, like debug pins `PA13`-`PA15`, `PB3` and `PB4`.
+//!
+//! ```rust,ignore
+//! let mut port = dp.GPIOE.split().into_port();
+//! port.make_output(0x00ff);
+//! port.write(0x0055);
+//! // Only pins 0..=3 are changed, atomically
+//! port.modify(0x000f, 0x000a);
+//! let inputs = port.read() >> 8;
+//! ```
+
+use super::{gpiox, Pull, Speed};
+
+/// All pins of GPIO port `P`
+pub struct Port<const P: char> {
+    _private: (),
+}
+
+// Repeats each bit of `mask` twice, for 2-bit fields of mode, speed and pull registers
+const fn double_mask(mask: u16) -> u32 {
+    let mut wide = 0;
+    let mut i = 0;
+    while i < 16 {
+        if mask & (1 << i) != 0 {
+            wide |= 0b11 << (2 * i);
+        }
+        i += 1;
+    }
+    wide
+}
+
+// Writes 2-bit `value` to the fields of `mask` pins
+const fn fields(mask: u16, value: u32) -> u32 {
+    double_mask(mask) & (value * 0x5555_5555)
+}
+
+impl<const P: char> Port<P> {
+    pub(crate) fn new() -> Self {
+        Self { _private: () }
+    }
+
+    /// Reads input levels of all pins (IDR)
+    #[inline(always)]
+    pub fn read(&self) -> u16 {
+        unsafe { (*gpiox::<P>()).idr().read().bits() as u16 }
+    }
+
+    /// Reads output levels set for all pins (ODR)
+    #[inline(always)]
+    pub fn output(&self) -> u16 {
+        unsafe { (*gpiox::<P>()).odr().read().bits() as u16 }
+    }
+
+    /// Sets output levels of all pins (ODR)
+    #[inline(always)]
+    pub fn write(&mut self, value: u16) {
+        unsafe { (*gpiox::<P>()).odr().write(|w| w.bits(u32::from(value))) }
+    }
+
+    /// Sets `mask` pins to the levels of corresponding `value` bits in one atomic BSRR write,
+    /// other pins are not changed
+    #[inline(always)]
+    pub fn modify(&mut self, mask: u16, value: u16) {
+        let set = value & mask;
+        let reset = !value & mask;
+        unsafe {
+            (*gpiox::<P>())
+                .bsrr()
+                .write(|w| w.bits(u32::from(set) | (u32::from(reset) << 16)))
+        }
+    }
+
+    /// Sets `mask` pins high
+    #[inline(always)]
+    pub fn set_high(&mut self, mask: u16) {
+        unsafe { (*gpiox::<P>()).bsrr().write(|w| w.bits(u32::from(mask))) }
+    }
+
+    /// Sets `mask` pins low
+    #[inline(always)]
+    pub fn set_low(&mut self, mask: u16) {
+        unsafe {
+            (*gpiox::<P>())
+                .bsrr()
+                .write(|w| w.bits(u32::from(mask) << 16))
+        }
+    }
+
+    /// Configures `mask` pins as push pull outputs
+    pub fn make_output(&mut self, mask: u16) {
+        unsafe {
+            let gpio = &*gpiox::<P>();
+            gpio.otyper()
+                .modify(|r, w| w.bits(r.bits() & !u32::from(mask)));
+            gpio.moder()
+                .modify(|r, w| w.bits((r.bits() & !double_mask(mask)) | fields(mask, 0b01)));
+        }
+    }
+
+    /// Configures `mask` pins as open drain outputs
+    pub fn make_open_drain_output(&mut self, mask: u16) {
+        unsafe {
+            let gpio = &*gpiox::<P>();
+            gpio.otyper()
+                .modify(|r, w| w.bits(r.bits() | u32::from(mask)));
+            gpio.moder()
+                .modify(|r, w| w.bits((r.bits() & !double_mask(mask)) | fields(mask, 0b01)));
+        }
+    }
+
+    /// Configures `mask` pins as inputs
+    pub fn make_input(&mut self, mask: u16) {
+        unsafe {
+            (*gpiox::<P>())
+                .moder()
+                .modify(|r, w| w.bits(r.bits() & !double_mask(mask)));
+        }
+    }
+
+    /// Sets the internal pull-up and pull-down resistor of `mask` pins
+    pub fn set_internal_resistor(&mut self, mask: u16, resistor: Pull) {
+        unsafe {
+            (*gpiox::<P>()).pupdr().modify(|r, w| {
+                w.bits((r.bits() & !double_mask(mask)) | fields(mask, resistor as u32))
+            });
+        }
+    }
+
+    /// Sets speed of `mask` pins
+    pub fn set_speed(&mut self, mask: u16, speed: Speed) {
+        unsafe {
+            (*gpiox::<P>()).ospeedr().modify(|r, w| {
+                w.bits((r.bits() & !double_mask(mask)) | fields(mask, speed as u32))
+            });
+        }
+    }
+}
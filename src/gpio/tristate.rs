@@ -0,0 +1,71 @@
+use super::*;
+
+/// Pin which is either driven high or low, or released (floating input)
+///
+/// Useful for shared lines and strapping pins, which must be left undriven most of the time.
+/// Pin starts released.
+///
+/// - `P` is port name: `A` for GPIOA, `B` for GPIOB, etc.
+/// - `N` is pin number: from `0` to `15`.
+pub struct TriState<const P: char, const N: u8> {
+    pin: DynamicPin<P, N>,
+}
+
+impl<const P: char, const N: u8> TriState<P, N> {
+    /// Makes tri-state pin of `pin` and releases it
+    pub fn new<MODE: PinMode>(pin: Pin<P, N, MODE>) -> Self {
+        Self {
+            pin: pin.into_dynamic(),
+        }
+    }
+
+    /// Drives the pin high
+    #[inline]
+    pub fn drive_high(&mut self) {
+        self.drive(PinState::High)
+    }
+
+    /// Drives the pin low
+    #[inline]
+    pub fn drive_low(&mut self) {
+        self.drive(PinState::Low)
+    }
+
+    /// Drives the pin high or low depending on the provided value
+    ///
+    /// Output level is set before the pin becomes output, so there is no glitch.
+    #[inline]
+    pub fn drive(&mut self, state: PinState) {
+        self.pin.make_push_pull_output_in_state(state);
+    }
+
+    /// Stops driving the pin, it becomes floating input
+    #[inline]
+    pub fn release(&mut self) {
+        self.pin.make_floating_input();
+    }
+
+    /// Is the pin driven?
+    #[inline]
+    pub fn is_driven(&self) -> bool {
+        self.pin.mode().is_output()
+    }
+
+    /// Is the pin high? Reads actual level in both driven and released states
+    #[inline(always)]
+    pub fn is_high(&self) -> bool {
+        !self.is_low()
+    }
+
+    /// Is the pin low? Reads actual level in both driven and released states
+    #[inline(always)]
+    pub fn is_low(&self) -> bool {
+        Pin::<P, N, Input>::new()._is_low()
+    }
+
+    /// Releases the pin and converts it to input
+    pub fn into_input(mut self) -> Pin<P, N, Input> {
+        self.release();
+        Pin::new()
+    }
+}
@@ -1,7 +1,7 @@
 use core::ops::Deref;
 
 use crate::pac::{self, i2c1};
-use crate::rcc::{Enable, Reset};
+use crate::rcc::{Enable, PeripheralClock, Reset};
 
 use crate::gpio;
 
@@ -104,7 +104,12 @@ impl Error {
 }
 
 pub trait Instance:
-    crate::Sealed + Deref<Target = i2c1::RegisterBlock> + Enable + Reset + gpio::alt::I2cCommon
+    crate::Sealed
+    + Deref<Target = i2c1::RegisterBlock>
+    + Enable
+    + Reset
+    + PeripheralClock
+    + gpio::alt::I2cCommon
 {
     #[doc(hidden)]
     fn ptr() -> *const i2c1::RegisterBlock;
@@ -120,6 +125,12 @@ macro_rules! i2c {
                 <$I2C>::ptr() as *const _
             }
         }
+
+        impl PeripheralClock for $I2C {
+            fn kernel_clock(clocks: &Clocks) -> Hertz {
+                <$I2C as crate::rcc::BusClock>::clock(clocks)
+            }
+        }
     };
 }
 
@@ -168,7 +179,7 @@ where
         let pins = (pins.0.into(), pins.1.into());
 
         let i2c = I2c { i2c, pins };
-        i2c.i2c_init(mode, clocks.pclk1());
+        i2c.i2c_init(mode, I2C::kernel_clock(clocks));
         i2c
     }
 
@@ -96,6 +96,8 @@ pub mod fmpi2c;
 pub mod gpio;
 pub mod i2c;
 pub mod i2s;
+#[cfg(feature = "lptim1")]
+pub mod lptim;
 #[cfg(all(feature = "usb_fs", feature = "otg-fs"))]
 pub mod otg_fs;
 #[cfg(all(any(feature = "usb_hs", docsrs), feature = "otg-hs"))]
@@ -105,6 +107,8 @@ pub mod otg_hs;
 pub mod rng;
 
 pub mod dma;
+#[cfg(feature = "dma2d")]
+pub mod dma2d;
 pub mod dwt;
 pub mod flash;
 #[cfg(all(feature = "fsmc_lcd", any(feature = "fmc", feature = "fsmc")))]
@@ -112,6 +116,7 @@ pub mod fsmc_lcd;
 #[cfg(all(feature = "dma2d", feature = "ltdc"))]
 pub mod ltdc;
 pub mod prelude;
+pub mod pwr;
 pub mod qei;
 #[cfg(feature = "quadspi")]
 pub mod qspi;
@@ -127,6 +132,8 @@ pub mod time;
 pub mod timer;
 #[cfg(feature = "uart4")]
 pub mod uart;
+#[cfg(feature = "async")]
+pub mod waker;
 pub mod watchdog;
 
 mod sealed {
@@ -146,6 +153,12 @@ pub trait ReadFlags {
 
     /// Get all interrupts flags a once.
     fn flags(&self) -> BitFlags<Self::Flag>;
+
+    /// Returns `true` if all of `flags` are set
+    #[inline(always)]
+    fn is_pending(&self, flags: impl Into<BitFlags<Self::Flag>>) -> bool {
+        self.flags().contains(flags.into())
+    }
 }
 
 pub trait ClearFlags {
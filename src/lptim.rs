@@ -0,0 +1,369 @@
+//! Low-power timer (LPTIM1)
+//!
+//! 16-bit timer which can be clocked from LSE or LSI and keeps running in Stop mode,
+//! so it can wake up the MCU periodically. It also supports PWM output and quadrature
+//! encoder mode.
+//!
+//! ```rust,ignore
+//! // LSE must be running, for example enabled by `Rtc::new`
+//! let mut lptim = LpTimer::new(dp.LPTIM1, ClockSource::Lse, Prescaler::Div32);
+//! lptim.listen(Event::AutoReloadMatch);
+//! lptim.enable_wakeup(&mut dp.EXTI);
+//! // Wake up each second
+//! lptim.start_periodic(1023);
+//! ```
+
+use crate::gpio::alt::lptim1 as alt;
+use crate::pac::{EXTI, LPTIM1, RCC};
+use crate::rcc::{Enable, Reset};
+use core::ops::{Deref, DerefMut};
+use enumflags2::BitFlags;
+
+/// Kernel clock of the timer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum ClockSource {
+    /// APB1 clock, stopped in Stop mode
+    Apb1 = 0,
+    Hsi = 1,
+    Lsi = 2,
+    Lse = 3,
+}
+
+/// Kernel clock prescaler
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Prescaler {
+    Div1 = 0,
+    Div2 = 1,
+    Div4 = 2,
+    Div8 = 3,
+    Div16 = 4,
+    Div32 = 5,
+    Div64 = 6,
+    Div128 = 7,
+}
+
+/// LPTIM interrupt events
+#[enumflags2::bitflags]
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// Counter reached compare value
+    CompareMatch = 1 << 0,
+    /// Counter reached auto-reload value
+    AutoReloadMatch = 1 << 1,
+    /// Valid edge on external trigger
+    ExternalTrigger = 1 << 2,
+    /// Compare register update finished
+    CompareUpdateOk = 1 << 3,
+    /// Auto-reload register update finished
+    AutoReloadUpdateOk = 1 << 4,
+    /// Encoder direction changed to up
+    Up = 1 << 5,
+    /// Encoder direction changed to down
+    Down = 1 << 6,
+}
+
+/// LPTIM status flags, same as events
+pub type Flag = Event;
+
+// EXTI line connected to LPTIM1 wakeup event
+const EXTI_LINE: u8 = 23;
+
+/// Counting mode started by the last `start_*` call
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Periodic,
+    Once,
+}
+
+/// Low-power timer
+pub struct LpTimer {
+    tim: LPTIM1,
+    mode: Option<Mode>,
+}
+
+impl LpTimer {
+    /// Enables timer clocked from `source` divided by `prescaler`
+    ///
+    /// LSE or LSI must be already running if selected.
+    pub fn new(tim: LPTIM1, source: ClockSource, prescaler: Prescaler) -> Self {
+        unsafe {
+            let rcc = &(*RCC::ptr());
+            LPTIM1::enable(rcc);
+            LPTIM1::reset(rcc);
+            rcc.dckcfgr2()
+                .modify(|_, w| w.lptim1sel().set(source as u8));
+        }
+        tim.cfgr()
+            .write(|w| w.presc().set(prescaler as u8).preload().set_bit());
+        Self { tim, mode: None }
+    }
+
+    fn enable(&mut self) {
+        self.tim.cr().modify(|_, w| w.enable().set_bit());
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.tim.cr().read().enable().bit_is_set()
+    }
+
+    /// Starts counting in `mode`, timer must be enabled
+    fn start(&mut self, mode: Mode) {
+        self.tim.cr().modify(|_, w| match mode {
+            Mode::Periodic => w.cntstrt().set_bit(),
+            Mode::Once => w.sngstrt().set_bit(),
+        });
+        self.mode = Some(mode);
+    }
+
+    /// Writes auto-reload value and waits until it is taken by the timer, timer must be enabled
+    fn write_auto_reload(&mut self, arr: u16) {
+        self.tim.arr().write(|w| w.arr().set(arr));
+        while self.tim.isr().read().arrok().bit_is_clear() {}
+        self.tim.icr().write(|w| w.arrokcf().set_bit());
+    }
+
+    /// Writes compare value and waits until it is taken by the timer, timer must be enabled
+    fn write_compare(&mut self, cmp: u16) {
+        self.tim.cmp().write(|w| w.cmp().set(cmp));
+        while self.tim.isr().read().cmpok().bit_is_clear() {}
+        self.tim.icr().write(|w| w.cmpokcf().set_bit());
+    }
+
+    /// Starts counting from 0 to `arr` repeatedly
+    pub fn start_periodic(&mut self, arr: u16) {
+        self.enable();
+        self.write_auto_reload(arr);
+        self.start(Mode::Periodic);
+    }
+
+    /// Counts from 0 to `arr` once
+    pub fn start_once(&mut self, arr: u16) {
+        self.enable();
+        self.write_auto_reload(arr);
+        self.start(Mode::Once);
+    }
+
+    /// Stops and disables the timer, counter is reset
+    pub fn stop(&mut self) {
+        self.tim.cr().reset();
+        self.mode = None;
+    }
+
+    /// Writes interrupt enable register, which is only writable while the timer is disabled
+    ///
+    /// Running timer is disabled for the write and enabled back. Periodic counting
+    /// restarts from 0, single count in progress is cancelled.
+    fn modify_ier(&mut self, f: impl FnOnce(u32) -> u32) {
+        let enabled = self.is_enabled();
+        if enabled {
+            self.tim.cr().reset();
+        }
+        self.tim.ier().modify(|r, w| unsafe { w.bits(f(r.bits())) });
+        if enabled {
+            self.enable();
+            if self.mode == Some(Mode::Periodic) {
+                self.start(Mode::Periodic);
+            } else {
+                self.mode = None;
+            }
+        }
+    }
+
+    /// Current counter value
+    pub fn count(&self) -> u16 {
+        // Counter is clocked asynchronously, two equal consecutive reads are reliable
+        loop {
+            let a = self.tim.cnt().read().bits();
+            if a == self.tim.cnt().read().bits() {
+                return a as u16;
+            }
+        }
+    }
+
+    /// Routes timer events to EXTI line 23, so listened events wake up the MCU from Stop mode
+    pub fn enable_wakeup(&mut self, exti: &mut EXTI) {
+        let bit = 1 << EXTI_LINE;
+        exti.imr().modify(|r, w| unsafe { w.bits(r.bits() | bit) });
+        exti.rtsr().modify(|r, w| unsafe { w.bits(r.bits() | bit) });
+    }
+
+    /// Disconnects timer events from EXTI line 23
+    pub fn disable_wakeup(&mut self, exti: &mut EXTI) {
+        let bit = 1 << EXTI_LINE;
+        exti.imr().modify(|r, w| unsafe { w.bits(r.bits() & !bit) });
+        exti.rtsr()
+            .modify(|r, w| unsafe { w.bits(r.bits() & !bit) });
+    }
+
+    /// Clears pending EXTI line 23, must be done in interrupt handler together with timer flags
+    pub fn clear_wakeup_pending(&mut self, exti: &mut EXTI) {
+        exti.pr().write(|w| unsafe { w.bits(1 << EXTI_LINE) });
+    }
+
+    /// Generates PWM with `period` counter ticks on output pin
+    ///
+    /// # Panics
+    ///
+    /// If `period` is 0.
+    pub fn pwm(mut self, pin: impl Into<alt::Out>, period: u16, inverted: bool) -> LpPwm {
+        assert!(period > 0, "PWM period must not be 0");
+        let pin = pin.into();
+        self.stop();
+        self.tim.cfgr().modify(|_, w| w.wavpol().bit(inverted));
+        self.enable();
+        self.write_auto_reload(period - 1);
+        // Zero duty
+        self.write_compare(period - 1);
+        self.start(Mode::Periodic);
+        LpPwm { timer: self, pin }
+    }
+
+    /// Counts quadrature encoder edges on IN1 and IN2 inputs between 0 and `arr`
+    ///
+    /// Kernel clock must be internal, prescaler is set to 1.
+    pub fn encoder(
+        mut self,
+        in1: impl Into<alt::In1>,
+        in2: impl Into<alt::In2>,
+        arr: u16,
+    ) -> LpEncoder {
+        let pins = (in1.into(), in2.into());
+        self.stop();
+        self.tim.cfgr().modify(|_, w| {
+            w.presc().set(0);
+            // Both edges
+            w.ckpol().set(0b10);
+            w.enc().set_bit()
+        });
+        self.start_periodic(arr);
+        LpEncoder { timer: self, pins }
+    }
+
+    /// Disables the timer and returns the peripheral
+    pub fn release(mut self) -> LPTIM1 {
+        self.stop();
+        self.tim
+    }
+}
+
+/// Interrupt enable register can be written only while the timer is disabled, so running timer
+/// is briefly disabled: periodic counting (also PWM and encoder) restarts from 0, single count
+/// in progress is cancelled.
+impl crate::Listen for LpTimer {
+    type Event = Event;
+    fn listen(&mut self, event: impl Into<BitFlags<Event>>) {
+        let event = event.into().bits();
+        self.modify_ier(|ier| ier | event);
+    }
+    fn listen_only(&mut self, event: impl Into<BitFlags<Event>>) {
+        let all = BitFlags::<Event>::ALL.bits();
+        let event = event.into().bits();
+        self.modify_ier(|ier| (ier & !all) | event);
+    }
+    fn unlisten(&mut self, event: impl Into<BitFlags<Event>>) {
+        let event = event.into().bits();
+        self.modify_ier(|ier| ier & !event);
+    }
+}
+
+impl crate::ReadFlags for LpTimer {
+    type Flag = Flag;
+    fn flags(&self) -> BitFlags<Flag> {
+        BitFlags::from_bits_truncate(self.tim.isr().read().bits())
+    }
+}
+
+impl crate::ClearFlags for LpTimer {
+    type Flag = Flag;
+    fn clear_flags(&mut self, flags: impl Into<BitFlags<Flag>>) {
+        self.tim
+            .icr()
+            .write(|w| unsafe { w.bits(flags.into().bits()) });
+    }
+}
+
+/// PWM on LPTIM1 output
+pub struct LpPwm {
+    timer: LpTimer,
+    pin: alt::Out,
+}
+
+impl Deref for LpPwm {
+    type Target = LpTimer;
+    fn deref(&self) -> &Self::Target {
+        &self.timer
+    }
+}
+
+impl DerefMut for LpPwm {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.timer
+    }
+}
+
+impl LpPwm {
+    /// Sets pulse width in counter ticks
+    pub fn set_duty(&mut self, duty: u16) {
+        // Output is active from compare match to auto-reload match
+        let max = self.get_max_duty();
+        self.timer.write_compare(max - duty.min(max));
+    }
+
+    /// Current pulse width
+    pub fn get_duty(&self) -> u16 {
+        self.get_max_duty() - self.timer.tim.cmp().read().bits() as u16
+    }
+
+    /// Maximal pulse width, one tick less than period
+    pub fn get_max_duty(&self) -> u16 {
+        self.timer.tim.arr().read().bits() as u16
+    }
+
+    /// Stops PWM and returns the timer and pin
+    pub fn release(mut self) -> (LpTimer, alt::Out) {
+        self.timer.stop();
+        (self.timer, self.pin)
+    }
+}
+
+/// LPTIM1 in quadrature encoder mode
+pub struct LpEncoder {
+    timer: LpTimer,
+    pins: (alt::In1, alt::In2),
+}
+
+impl Deref for LpEncoder {
+    type Target = LpTimer;
+    fn deref(&self) -> &Self::Target {
+        &self.timer
+    }
+}
+
+impl DerefMut for LpEncoder {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.timer
+    }
+}
+
+impl LpEncoder {
+    /// Current position
+    pub fn count(&self) -> u16 {
+        self.timer.count()
+    }
+
+    /// Stops counting and returns the timer and input pins
+    pub fn release(mut self) -> (LpTimer, (alt::In1, alt::In2)) {
+        self.timer.stop();
+        self.timer
+            .tim
+            .cfgr()
+            .modify(|_, w| w.enc().clear_bit().ckpol().set(0));
+        (self.timer, self.pins)
+    }
+}
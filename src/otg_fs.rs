@@ -6,7 +6,7 @@
 use crate::pac;
 
 use crate::gpio::alt::otg_fs as alt;
-use crate::rcc::{Clocks, Enable, Reset};
+use crate::rcc::{Clocks, Enable, PeripheralClock, Reset};
 use fugit::HertzU32 as Hertz;
 
 pub use synopsys_usb_otg::UsbBus;
@@ -22,11 +22,20 @@ pub struct USB {
 }
 
 impl USB {
+    /// Creates USB peripheral with internal full-speed PHY
+    ///
+    /// # Panics
+    ///
+    /// If 48 MHz clock is not accurate enough for USB, see [`Clocks::is_48mhz_accurate`].
     pub fn new(
         periphs: (pac::OTG_FS_GLOBAL, pac::OTG_FS_DEVICE, pac::OTG_FS_PWRCLK),
         pins: (impl Into<alt::Dm>, impl Into<alt::Dp>),
         clocks: &Clocks,
     ) -> Self {
+        assert!(
+            clocks.is_48mhz_accurate(),
+            "USB requires accurate 48 MHz clock"
+        );
         Self {
             usb_global: periphs.0,
             usb_device: periphs.1,
@@ -40,6 +49,12 @@ impl USB {
 
 unsafe impl Sync for USB {}
 
+impl PeripheralClock for pac::OTG_FS_GLOBAL {
+    fn kernel_clock(clocks: &Clocks) -> Hertz {
+        clocks.pll48clk().unwrap_or(Hertz::from_raw(0))
+    }
+}
+
 unsafe impl UsbPeripheral for USB {
     const REGISTERS: *const () = pac::OTG_FS_GLOBAL::ptr() as *const ();
 
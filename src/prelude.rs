@@ -51,11 +51,13 @@ pub use fugit::RateExtU32 as _fugit_RateExtU32;
 pub use crate::can::CanExt as _stm32f4xx_hal_can_CanExt;
 #[cfg(feature = "dac")]
 pub use crate::dac::DacExt as _stm32f4xx_hal_dac_DacExt;
+pub use crate::dma::route::DmaRouteExt as _;
 pub use crate::dma::traits::DmaEventExt as _;
 pub use crate::dma::traits::DmaFlagExt as _;
 pub use crate::dma::traits::Stream as _;
 pub use crate::dma::traits::StreamISR as _;
 pub use crate::gpio::outport::OutPort as _;
+pub use crate::gpio::ExtiExt as _stm32f4xx_hal_gpio_ExtiExt;
 pub use crate::gpio::ExtiPin as _stm32f4xx_hal_gpio_ExtiPin;
 pub use crate::gpio::GpioExt as _stm32f4xx_hal_gpio_GpioExt;
 pub use crate::i2c::dma::I2CMasterHandleIT as _stm32f4xx_hal_i2c_dma_I2CMasterHandleIT;
@@ -74,6 +76,8 @@ pub use crate::serial::dma::SerialWriteDMA as _stm32f4xx_hal_serial_dma_SerialWr
 pub use crate::serial::RxISR as _stm32f4xx_hal_serial_RxISR;
 pub use crate::serial::RxListen as _stm32f4xx_hal_serial_RxListen;
 pub use crate::serial::SerialExt as _stm32f4xx_hal_serial_SerialExt;
+pub use crate::serial::SmartcardExt as _stm32f4xx_hal_serial_SmartcardExt;
+pub use crate::serial::SyncSerialExt as _stm32f4xx_hal_serial_SyncSerialExt;
 pub use crate::serial::TxISR as _stm32f4xx_hal_serial_TxISR;
 pub use crate::serial::TxListen as _stm32f4xx_hal_serial_TxListen;
 pub use crate::spi::SpiExt as _stm32f4xx_hal_spi_SpiExt;
@@ -88,13 +92,16 @@ pub use crate::timer::MonoTimerExt as _;
     feature = "rtic-tim2",
     feature = "rtic-tim3",
     feature = "rtic-tim4",
-    feature = "rtic-tim5"
+    feature = "rtic-tim5",
+    feature = "rtic-systick"
 ))]
 pub use crate::timer::MonoTimerExt as _;
 pub use crate::timer::PwmExt as _stm32f4xx_hal_timer_PwmExt;
 #[cfg(feature = "rtic1")]
 pub use crate::timer::SysMonoTimerExt as _stm32f4xx_hal_timer_SysMonoTimerExt;
 pub use crate::timer::SysTimerExt as _stm32f4xx_hal_timer_SysCounterExt;
+#[cfg(feature = "systick-uptime")]
+pub use crate::timer::SysUptimeExt as _stm32f4xx_hal_timer_SysUptimeExt;
 pub use crate::timer::TimerExt as _stm32f4xx_hal_timer_TimerExt;
 
 pub use crate::ClearFlags as _;
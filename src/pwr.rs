@@ -0,0 +1,428 @@
+//! Power control and low power modes
+//!
+//! In Stop mode all clocks of the core domain are stopped, SRAM and registers keep their
+//! content. Any EXTI line configured for interrupt or event wakes the MCU up, which then runs
+//! from HSI. [`enter_stop`] restores oscillators, PLLs and system clock frozen before, so
+//! `Clocks` stay valid after it returns.
+//!
+//! ```rust,ignore
+//! let clocks = rcc.cfgr.use_hse(8.MHz()).sysclk(168.MHz()).freeze();
+//! button.make_interrupt_source(&mut syscfg);
+//! button.trigger_on_edge(&mut dp.EXTI, Edge::Falling);
+//! button.enable_interrupt(&mut dp.EXTI);
+//!
+//! let config = StopConfig::default().low_power_regulator(true);
+//! let wakeup = pwr::enter_stop(&mut dp.PWR, &mut cp.SCB, config);
+//! if wakeup.is_pin(button.pin_id()) {
+//!     // ...
+//! }
+//! ```
+//!
+//! Debugger connection is lost in Stop mode unless `DBG_STOP` bit of `DBGMCU_CR` is set.
+//!
+//! In Standby mode the core domain is powered off, only backup domain, backup SRAM (if its
+//! regulator is on) and wakeup logic are kept. Wakeup by a WKUP pin rising edge, RTC alarm,
+//! wakeup timer, tamper or time stamp event, `NRST` or independent watchdog starts the
+//! program from reset, [`reset_reason`] tells it was a wakeup from Standby.
+//!
+//! ```rust,ignore
+//! if pwr::reset_reason(&dp.PWR) == ResetReason::Standby {
+//!     // restore state from backup registers
+//! }
+//! pwr::clear_reset_flags(&mut dp.PWR);
+//!
+//! pwr::enable_wakeup_pin(&mut dp.PWR, WakeupPin::Wkup1);
+//! pwr::enter_standby(&mut dp.PWR, &mut cp.SCB);
+//! ```
+//!
+//! In Sleep mode only the core clock is stopped, peripherals keep running and any interrupt
+//! wakes the core up. Peripheral clocks can be gated in Sleep mode with
+//! [`LPEnable`](crate::rcc::LPEnable). Interrupt driven applications can sleep whenever
+//! there is nothing to do:
+//!
+//! ```rust,ignore
+//! pwr::set_sleep_on_exit(&mut cp.SCB, true);
+//! loop {
+//!     // returns here only if an interrupt handler clears SLEEPONEXIT
+//!     pwr::wfi_idle(&mut cp.SCB);
+//! }
+//! ```
+
+use crate::gpio::exti::Line;
+use crate::pac::{self, EXTI, PWR, RCC};
+use crate::rcc::{ClockState, Enable};
+use cortex_m::peripheral::SCB;
+
+// SEVONPEND bit of SCB_SCR, cortex-m has no accessor for it unlike SLEEPDEEP and SLEEPONEXIT
+const SEVONPEND: u32 = 1 << 4;
+
+/// Instruction used to enter low power mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WaitFor {
+    /// `WFI`, woken up by EXTI interrupt
+    Interrupt,
+    /// `WFE`, woken up by EXTI event, or by interrupt if `SEVONPEND` is set
+    Event,
+}
+
+/// Stop mode configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StopConfig {
+    low_power_regulator: bool,
+    flash_power_down: bool,
+    // Under-drive on F427, F446 and F469, low voltage on F401, F410, F411, F412 and F413
+    #[cfg(not(feature = "gpio-f417"))]
+    low_voltage: bool,
+    wait_for: WaitFor,
+}
+
+impl StopConfig {
+    /// Voltage regulator in low power mode, lower consumption but longer wakeup time
+    pub fn low_power_regulator(mut self, low_power_regulator: bool) -> Self {
+        self.low_power_regulator = low_power_regulator;
+        self
+    }
+
+    /// Flash memory powered down, lower consumption but longer wakeup time
+    pub fn flash_power_down(mut self, flash_power_down: bool) -> Self {
+        self.flash_power_down = flash_power_down;
+        self
+    }
+
+    /// Regulator in under-drive mode, lowest consumption but longest wakeup time
+    #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+    pub fn under_drive(mut self, under_drive: bool) -> Self {
+        self.low_voltage = under_drive;
+        self
+    }
+
+    /// Regulator at low voltage, lower consumption but longer wakeup time
+    #[cfg(any(
+        feature = "gpio-f401",
+        feature = "gpio-f410",
+        feature = "gpio-f411",
+        feature = "gpio-f412",
+        feature = "gpio-f413"
+    ))]
+    pub fn low_voltage(mut self, low_voltage: bool) -> Self {
+        self.low_voltage = low_voltage;
+        self
+    }
+
+    /// Instruction used to enter Stop mode
+    pub fn wait_for(mut self, wait_for: WaitFor) -> Self {
+        self.wait_for = wait_for;
+        self
+    }
+
+    // Sets PWR_CR regulator voltage bits of Stop mode
+    //
+    // Under-drive and low voltage bits apply to the regulator selected by LPDS, so only one
+    // of them is set.
+    #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+    fn set_low_voltage<'a>(&self, w: &'a mut pac::pwr::cr::W) -> &'a mut pac::pwr::cr::W {
+        let lp = self.low_power_regulator;
+        unsafe { w.uden().bits(if self.low_voltage { 0b11 } else { 0b00 }) };
+        w.lpuds().bit(self.low_voltage && lp);
+        w.mruds().bit(self.low_voltage && !lp)
+    }
+
+    #[cfg(not(any(
+        feature = "gpio-f417",
+        feature = "gpio-f427",
+        feature = "gpio-f446",
+        feature = "gpio-f469"
+    )))]
+    fn set_low_voltage<'a>(&self, w: &'a mut pac::pwr::cr::W) -> &'a mut pac::pwr::cr::W {
+        let lp = self.low_power_regulator;
+        w.lplvds().bit(self.low_voltage && lp);
+        w.mrlvds().bit(self.low_voltage && !lp)
+    }
+
+    #[cfg(feature = "gpio-f417")]
+    fn set_low_voltage<'a>(&self, w: &'a mut pac::pwr::cr::W) -> &'a mut pac::pwr::cr::W {
+        w
+    }
+}
+
+impl Default for StopConfig {
+    /// Main regulator at normal voltage, flash on, woken up by interrupt
+    fn default() -> Self {
+        Self {
+            low_power_regulator: false,
+            flash_power_down: false,
+            #[cfg(not(feature = "gpio-f417"))]
+            low_voltage: false,
+            wait_for: WaitFor::Interrupt,
+        }
+    }
+}
+
+/// EXTI lines pending after wakeup from Stop mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Wakeup {
+    pending: u32,
+}
+
+impl Wakeup {
+    /// Pending EXTI lines, bit `n` for line `n`
+    ///
+    /// Lines configured only for events are not latched, then no bit is set.
+    pub fn pending(&self) -> u32 {
+        self.pending
+    }
+
+    /// Woken up by GPIO line `pin` (0..=15)
+    pub fn is_pin(&self, pin: u8) -> bool {
+        pin < 16 && self.pending & (1 << pin) != 0
+    }
+
+    /// Woken up by EXTI `line` of another peripheral
+    pub fn is_line(&self, line: Line) -> bool {
+        self.pending & (1 << line as u8) != 0
+    }
+}
+
+/// Enters Stop mode and returns after wakeup, with clocks restored
+///
+/// Runs with interrupts disabled, so interrupt handler of the wakeup source runs after this
+/// function returns, at full speed. HSE, PLLs and system clock switch are restored as they
+/// were before Stop mode, bus prescalers and PLL settings are kept by hardware. Pending bits
+/// of EXTI lines are not cleared.
+///
+/// A pending EXTI interrupt prevents entering Stop mode, it returns at once.
+pub fn enter_stop(pwr: &mut PWR, scb: &mut SCB, config: StopConfig) -> Wakeup {
+    let rcc = unsafe { &*RCC::ptr() };
+    PWR::enable(rcc);
+
+    cortex_m::interrupt::free(|_| {
+        let clocks = ClockState::save();
+
+        pwr.cr().modify(|_, w| {
+            w.lpds().bit(config.low_power_regulator);
+            w.pdds().clear_bit();
+            w.fpds().bit(config.flash_power_down);
+            config.set_low_voltage(w)
+        });
+
+        scb.set_sleepdeep();
+        match config.wait_for {
+            WaitFor::Interrupt => {
+                cortex_m::asm::dsb();
+                cortex_m::asm::wfi();
+            }
+            WaitFor::Event => {
+                // Clear event register, which may be set by an earlier event
+                cortex_m::asm::sev();
+                cortex_m::asm::wfe();
+                cortex_m::asm::wfe();
+            }
+        }
+        scb.clear_sleepdeep();
+
+        #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+        if config.low_voltage {
+            // Under-drive is disabled and its ready flag cleared before clocks are restored
+            pwr.cr().modify(|_, w| unsafe { w.uden().bits(0b00) });
+            pwr.csr().modify(|_, w| unsafe { w.udrdy().bits(0b11) });
+        }
+
+        clocks.restore();
+
+        let exti = unsafe { &*EXTI::ptr() };
+        Wakeup {
+            pending: exti.pr().read().bits(),
+        }
+    })
+}
+
+/// Pin which wakes the MCU up from Standby mode on rising edge
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WakeupPin {
+    /// PA0
+    Wkup1,
+    /// PC13
+    #[cfg(feature = "gpio-f446")]
+    Wkup2,
+    /// PC0
+    #[cfg(any(feature = "gpio-f410", feature = "gpio-f412", feature = "gpio-f413"))]
+    Wkup2,
+    /// PC1
+    #[cfg(any(feature = "gpio-f410", feature = "gpio-f412", feature = "gpio-f413"))]
+    Wkup3,
+}
+
+impl WakeupPin {
+    // EWUPx bit of PWR_CSR
+    fn ewup(self) -> u32 {
+        match self {
+            Self::Wkup1 => 1 << 8,
+            #[cfg(any(
+                feature = "gpio-f410",
+                feature = "gpio-f412",
+                feature = "gpio-f413",
+                feature = "gpio-f446"
+            ))]
+            Self::Wkup2 => 1 << 7,
+            #[cfg(any(feature = "gpio-f410", feature = "gpio-f412", feature = "gpio-f413"))]
+            Self::Wkup3 => 1 << 6,
+        }
+    }
+}
+
+/// Makes `pin` a wakeup pin, it is forced to input with pull-down
+///
+/// Wakeup flag is set at once if the pin is high when it is enabled.
+pub fn enable_wakeup_pin(pwr: &mut PWR, pin: WakeupPin) {
+    let rcc = unsafe { &*RCC::ptr() };
+    PWR::enable(rcc);
+    pwr.csr()
+        .modify(|r, w| unsafe { w.bits(r.bits() | pin.ewup()) });
+}
+
+/// Returns `pin` to general purpose use
+pub fn disable_wakeup_pin(pwr: &mut PWR, pin: WakeupPin) {
+    pwr.csr()
+        .modify(|r, w| unsafe { w.bits(r.bits() & !pin.ewup()) });
+}
+
+/// Has a wakeup event occurred?
+///
+/// Set by WKUP pins and RTC wakeup events, it has to be cleared before entering Standby mode.
+pub fn is_wakeup_flag_set(pwr: &PWR) -> bool {
+    pwr.csr().read().wuf().bit_is_set()
+}
+
+/// Clears wakeup flag
+pub fn clear_wakeup_flag(pwr: &mut PWR) {
+    pwr.cr().modify(|_, w| w.cwuf().set_bit());
+}
+
+/// Has the MCU been in Standby mode since the flag was cleared?
+pub fn is_standby_flag_set(pwr: &PWR) -> bool {
+    pwr.csr().read().sbf().bit_is_set()
+}
+
+/// Clears standby flag
+pub fn clear_standby_flag(pwr: &mut PWR) {
+    pwr.cr().modify(|_, w| w.csbf().set_bit());
+}
+
+/// Enters Standby mode, the MCU starts from reset on wakeup
+///
+/// Wakeup flag is cleared first, RTC flags of enabled RTC wakeup sources must be cleared by
+/// the caller, otherwise the MCU wakes up at once.
+pub fn enter_standby(pwr: &mut PWR, scb: &mut SCB) -> ! {
+    let rcc = unsafe { &*RCC::ptr() };
+    PWR::enable(rcc);
+
+    cortex_m::interrupt::disable();
+    pwr.cr().modify(|_, w| w.pdds().set_bit().cwuf().set_bit());
+    scb.set_sleepdeep();
+    loop {
+        cortex_m::asm::dsb();
+        cortex_m::asm::wfi();
+    }
+}
+
+/// Cause of the last reset
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ResetReason {
+    /// Wakeup from Standby mode
+    Standby,
+    /// Power-on or power-down reset
+    PowerOn,
+    /// Supply voltage dropped below brownout threshold
+    Brownout,
+    /// `NRST` pin
+    Pin,
+    /// Software reset by `SYSRESETREQ`
+    Software,
+    /// Independent watchdog
+    IndependentWatchdog,
+    /// Window watchdog
+    WindowWatchdog,
+    /// Entering Stop or Standby mode prohibited by option bytes
+    LowPower,
+    /// No reset flag is set, flags were cleared
+    Unknown,
+}
+
+/// Returns cause of the last reset
+///
+/// Reset flags are kept through resets until cleared with [`clear_reset_flags`]. Internal
+/// resets also drive `NRST` pin, so the most specific flag is reported.
+pub fn reset_reason(pwr: &PWR) -> ResetReason {
+    let rcc = unsafe { &*RCC::ptr() };
+    PWR::enable(rcc);
+    let csr = rcc.csr().read();
+    if is_standby_flag_set(pwr) {
+        ResetReason::Standby
+    } else if csr.wdgrstf().bit_is_set() {
+        ResetReason::IndependentWatchdog
+    } else if csr.wwdgrstf().bit_is_set() {
+        ResetReason::WindowWatchdog
+    } else if csr.lpwrrstf().bit_is_set() {
+        ResetReason::LowPower
+    } else if csr.sftrstf().bit_is_set() {
+        ResetReason::Software
+    } else if csr.porrstf().bit_is_set() {
+        // Brownout flag is set at power-on too
+        ResetReason::PowerOn
+    } else if csr.borrstf().bit_is_set() {
+        ResetReason::Brownout
+    } else if csr.padrstf().bit_is_set() {
+        ResetReason::Pin
+    } else {
+        ResetReason::Unknown
+    }
+}
+
+/// Clears reset flags and standby and wakeup flags
+pub fn clear_reset_flags(pwr: &mut PWR) {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.csr().modify(|_, w| w.rmvf().set_bit());
+    pwr.cr().modify(|_, w| w.csbf().set_bit().cwuf().set_bit());
+}
+
+/// Core enters Sleep mode again after returning from the last interrupt handler
+///
+/// Main thread is not resumed until SLEEPONEXIT is cleared, which saves the time and energy
+/// of returning to it when all work is done in interrupt handlers.
+pub fn set_sleep_on_exit(scb: &mut SCB, sleep_on_exit: bool) {
+    if sleep_on_exit {
+        scb.set_sleeponexit();
+    } else {
+        scb.clear_sleeponexit();
+    }
+}
+
+/// Pending interrupts wake the core up from `WFE`, including disabled ones
+///
+/// Used with [`WaitFor::Event`] to wake up by interrupts which are only polled.
+pub fn set_sev_on_pend(scb: &mut SCB, sev_on_pend: bool) {
+    unsafe {
+        scb.scr.modify(|scr| {
+            if sev_on_pend {
+                scr | SEVONPEND
+            } else {
+                scr & !SEVONPEND
+            }
+        })
+    }
+}
+
+/// Waits for interrupt in Sleep mode
+///
+/// Clears SLEEPDEEP, so clocks keep running and `Clocks` stay valid. Returns after the
+/// interrupt handler, unless sleep on exit is enabled.
+pub fn wfi_idle(scb: &mut SCB) {
+    scb.clear_sleepdeep();
+    cortex_m::asm::dsb();
+    cortex_m::asm::wfi();
+}
@@ -0,0 +1,220 @@
+use super::Enable;
+use crate::pac::{PWR, RCC, RTC};
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Number of 32-bit RTC backup registers
+pub const BACKUP_REGISTERS: usize = 20;
+
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Write access to the backup domain
+///
+/// LSE, RTC clock selection, RTC registers and backup registers are write protected until
+/// DBP bit of `PWR_CR` is set. `BackupDomain` enables the access, and functions which
+/// change the backup domain take it by reference. Only one instance exists at a time.
+///
+/// Write access is never disabled again, because `Rtc` and backup registers need it while
+/// they are used.
+///
+/// ```rust,ignore
+/// let mut bd = BackupDomain::take(&mut dp.PWR).unwrap();
+/// lsclk::enable_lse(&mut bd, LSEClockMode::Oscillator);
+/// lsclk::select_rtc_clock(&mut bd, RtcClock::Lse);
+/// bd.enable_rtc_clock();
+/// ```
+///
+/// It also gives access to the 20 RTC backup registers, which keep their content while VBAT is
+/// powered, through system reset, Standby mode and loss of VDD. Backup domain reset and RTC
+/// tamper detection clear them.
+pub struct BackupDomain {
+    taken: bool,
+}
+
+impl BackupDomain {
+    /// Enables PWR clock and write access to the backup domain, returns `None` if it is
+    /// already taken
+    ///
+    /// Dropped instance can be taken again, write access stays enabled.
+    pub fn take(pwr: &mut PWR) -> Option<Self> {
+        if TAKEN.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        Self::enable_access(pwr);
+        Some(Self { taken: true })
+    }
+
+    // Enables write access for drivers configuring the backup domain while the user may
+    // hold the instance
+    pub(crate) fn unchecked(pwr: &mut PWR) -> Self {
+        Self::enable_access(pwr);
+        Self { taken: false }
+    }
+
+    fn enable_access(pwr: &mut PWR) {
+        let rcc = unsafe { &*RCC::ptr() };
+        PWR::enable(rcc);
+        pwr.cr().modify(|_, w| w.dbp().set_bit());
+    }
+
+    /// Resets the backup domain
+    ///
+    /// LSE is stopped, RTC clock selection, RTC registers and backup registers are reset.
+    /// Backup SRAM keeps its content.
+    pub fn reset(&mut self) {
+        let rcc = unsafe { &*RCC::ptr() };
+        rcc.bdcr().modify(|_, w| w.bdrst().set_bit());
+        rcc.bdcr().modify(|_, w| w.bdrst().clear_bit());
+    }
+
+    /// Enables RTC clock, RTC clock source must be selected
+    pub fn enable_rtc_clock(&mut self) {
+        let rcc = unsafe { &*RCC::ptr() };
+        rcc.bdcr().modify(|_, w| w.rtcen().set_bit());
+    }
+
+    /// Disables RTC clock
+    pub fn disable_rtc_clock(&mut self) {
+        let rcc = unsafe { &*RCC::ptr() };
+        rcc.bdcr().modify(|_, w| w.rtcen().clear_bit());
+    }
+
+    /// Is RTC clock enabled?
+    pub fn is_rtc_clock_enabled(&self) -> bool {
+        let rcc = unsafe { &*RCC::ptr() };
+        rcc.bdcr().read().rtcen().bit_is_set()
+    }
+
+    /// Reads backup register `index`
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not below [`BACKUP_REGISTERS`].
+    pub fn read_backup_register(&self, index: usize) -> u32 {
+        let rtc = unsafe { &*RTC::ptr() };
+        rtc.bkpr(index).read().bits()
+    }
+
+    /// Writes backup register `index`
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not below [`BACKUP_REGISTERS`].
+    pub fn write_backup_register(&mut self, index: usize, value: u32) {
+        let rtc = unsafe { &*RTC::ptr() };
+        rtc.bkpr(index).write(|w| unsafe { w.bits(value) });
+    }
+
+    /// Reads typed value of `reg`
+    pub fn get<T: BackupValue>(&self, reg: BackupRegister<T>) -> T {
+        T::from_bits(self.read_backup_register(reg.index))
+    }
+
+    /// Writes typed value of `reg`
+    pub fn set<T: BackupValue>(&mut self, reg: BackupRegister<T>, value: T) {
+        self.write_backup_register(reg.index, value.into_bits())
+    }
+}
+
+impl Drop for BackupDomain {
+    fn drop(&mut self) {
+        if self.taken {
+            TAKEN.store(false, Ordering::Release);
+        }
+    }
+}
+
+/// Value which fits into a 32-bit backup register
+pub trait BackupValue: Copy {
+    fn from_bits(bits: u32) -> Self;
+    fn into_bits(self) -> u32;
+}
+
+macro_rules! backup_value {
+    ($($t:ty),*) => {
+        $(
+            impl BackupValue for $t {
+                fn from_bits(bits: u32) -> Self {
+                    bits as $t
+                }
+                fn into_bits(self) -> u32 {
+                    self as u32
+                }
+            }
+        )*
+    };
+}
+
+backup_value!(u8, u16, i8, i16, i32);
+
+impl BackupValue for u32 {
+    fn from_bits(bits: u32) -> Self {
+        bits
+    }
+    fn into_bits(self) -> u32 {
+        self
+    }
+}
+
+impl BackupValue for bool {
+    fn from_bits(bits: u32) -> Self {
+        bits != 0
+    }
+    fn into_bits(self) -> u32 {
+        self.into()
+    }
+}
+
+impl BackupValue for f32 {
+    fn from_bits(bits: u32) -> Self {
+        f32::from_bits(bits)
+    }
+    fn into_bits(self) -> u32 {
+        self.to_bits()
+    }
+}
+
+/// Backup register holding a value of type `T`
+///
+/// Gives names and types to register indices, so the layout of backup registers is declared
+/// in one place. Registers are zero after backup domain reset, which is
+/// also the initial value read.
+///
+/// ```rust,ignore
+/// const BOOT_COUNT: BackupRegister<u32> = BackupRegister::new(0);
+/// const CRASHED: BackupRegister<bool> = BackupRegister::new(1);
+///
+/// let boots = bd.get(BOOT_COUNT);
+/// bd.set(BOOT_COUNT, boots.wrapping_add(1));
+/// if bd.get(CRASHED) {
+///     bd.set(CRASHED, false);
+/// }
+/// ```
+pub struct BackupRegister<T> {
+    index: usize,
+    _value: PhantomData<T>,
+}
+
+impl<T> BackupRegister<T> {
+    /// Backup register `index`, fails the build in `const` context if it is out of range
+    pub const fn new(index: usize) -> Self {
+        assert!(index < BACKUP_REGISTERS, "backup register out of range");
+        Self {
+            index,
+            _value: PhantomData,
+        }
+    }
+
+    /// Index of the register
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T> Clone for BackupRegister<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for BackupRegister<T> {}
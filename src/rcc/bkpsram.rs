@@ -0,0 +1,80 @@
+use super::BackupDomain;
+use crate::pac::{PWR, RCC};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+const BKPSRAM_BASE: usize = 0x4002_4000;
+
+/// Size of backup SRAM in bytes
+pub const BACKUP_SRAM_SIZE: usize = 4096;
+
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// 4 KiB of backup SRAM
+///
+/// Backup SRAM is in the backup domain, so it is written only while [`BackupDomain`] access
+/// is enabled. With the backup regulator on it keeps its content in Standby mode and while
+/// VBAT is powered. Unlike backup registers it is not cleared by backup domain reset.
+///
+/// ```rust,ignore
+/// let mut bd = BackupDomain::take(&mut dp.PWR).unwrap();
+/// let mut sram = BackupSram::take(&mut bd).unwrap();
+/// sram.enable_retention(&mut bd);
+/// let calibration = &mut sram.as_mut()[..64];
+/// ```
+pub struct BackupSram {
+    _private: (),
+}
+
+impl BackupSram {
+    /// Enables backup SRAM clock, returns `None` if backup SRAM is already taken
+    pub fn take(_bd: &mut BackupDomain) -> Option<Self> {
+        if TAKEN.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        let rcc = unsafe { &*RCC::ptr() };
+        rcc.ahb1enr().modify(|_, w| w.bkpsramen().set_bit());
+        // Delay after an RCC peripheral clock enabling
+        cortex_m::asm::dsb();
+        Some(Self { _private: () })
+    }
+
+    /// Enables backup regulator and waits until it is ready
+    ///
+    /// Without it backup SRAM loses its content in Standby mode and when VDD is off.
+    pub fn enable_retention(&mut self, _bd: &mut BackupDomain) {
+        let pwr = unsafe { &*PWR::ptr() };
+        pwr.csr().modify(|_, w| w.bre().set_bit());
+        while !self.is_retained() {}
+    }
+
+    /// Disables backup regulator
+    pub fn disable_retention(&mut self, _bd: &mut BackupDomain) {
+        let pwr = unsafe { &*PWR::ptr() };
+        pwr.csr().modify(|_, w| w.bre().clear_bit());
+    }
+
+    /// Is backup regulator on and ready?
+    pub fn is_retained(&self) -> bool {
+        let pwr = unsafe { &*PWR::ptr() };
+        pwr.csr().read().brr().bit_is_set()
+    }
+
+    /// Disables backup SRAM clock, so it can be taken again
+    pub fn release(self) {
+        let rcc = unsafe { &*RCC::ptr() };
+        rcc.ahb1enr().modify(|_, w| w.bkpsramen().clear_bit());
+        TAKEN.store(false, Ordering::Release);
+    }
+}
+
+impl AsRef<[u8; BACKUP_SRAM_SIZE]> for BackupSram {
+    fn as_ref(&self) -> &[u8; BACKUP_SRAM_SIZE] {
+        unsafe { &*(BKPSRAM_BASE as *const [u8; BACKUP_SRAM_SIZE]) }
+    }
+}
+
+impl AsMut<[u8; BACKUP_SRAM_SIZE]> for BackupSram {
+    fn as_mut(&mut self) -> &mut [u8; BACKUP_SRAM_SIZE] {
+        unsafe { &mut *(BKPSRAM_BASE as *mut [u8; BACKUP_SRAM_SIZE]) }
+    }
+}
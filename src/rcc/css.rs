@@ -0,0 +1,106 @@
+//! Clock security system (CSS)
+//!
+//! When HSE fails while CSS is enabled, hardware switches system clock to HSI, stops HSE and
+//! PLLs clocked from it and raises non-maskable interrupt. Bus prescalers stay unchanged, so
+//! all clocks become slower. [`css_nmi!`](crate::css_nmi) defines NMI handler which clears the
+//! failure flag, records the failure and calls a function. Usable clocks are restored with
+//! [`fallback`] outside of NMI handler, because NMI can preempt code accessing RCC registers.
+//!
+//! ```rust,ignore
+//! let clocks = rcc.cfgr.use_hse(8.MHz()).sysclk(168.MHz()).freeze();
+//! css::enable();
+//!
+//! stm32f4xx_hal::css_nmi!(|| {});
+//!
+//! loop {
+//!     if css::take_hse_failure() {
+//!         let clocks = css::fallback(|cfgr| cfgr.sysclk(168.MHz()));
+//!         // reconfigure peripherals with `clocks`
+//!     }
+//! }
+//! ```
+
+use super::{Clocks, RccExt, CFGR};
+use crate::pac::RCC;
+use core::sync::atomic::{AtomicBool, Ordering};
+use cortex_m::peripheral::scb::{Exception, VectActive};
+use cortex_m::peripheral::SCB;
+
+static HSE_FAILURE: AtomicBool = AtomicBool::new(false);
+
+/// Enables clock security system, HSE must be already running
+pub fn enable() {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.cr().modify(|_, w| w.csson().set_bit());
+}
+
+/// Disables clock security system
+pub fn disable() {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.cr().modify(|_, w| w.csson().clear_bit());
+}
+
+/// Has HSE failure been detected?
+///
+/// The flag is cleared by [`on_nmi`], use [`take_hse_failure`] after that.
+pub fn is_hse_failed() -> bool {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.cir().read().cssf().bit_is_set()
+}
+
+/// Returns `true` once after HSE failure was handled by [`on_nmi`]
+pub fn take_hse_failure() -> bool {
+    HSE_FAILURE.swap(false, Ordering::Relaxed)
+}
+
+/// Clears HSE failure flag if it is set, call from NMI handler
+///
+/// Returns `true` if HSE failure caused the interrupt. NMI is raised again at once
+/// while the flag is set.
+pub fn on_nmi() -> bool {
+    if !is_hse_failed() {
+        return false;
+    }
+    let rcc = unsafe { &*RCC::ptr() };
+    // Ready interrupt enables are read back and kept, flags are read only
+    rcc.cir().modify(|_, w| w.cssc().set_bit());
+    HSE_FAILURE.store(true, Ordering::Relaxed);
+    true
+}
+
+/// Switches clocks to configuration made from HSI, returning new Clocks
+///
+/// `f` gets clock builder in its initial state and must not enable HSE. See [`CFGR::reconfigure`].
+///
+/// # Panics
+///
+/// If called from NMI handler, where RCC registers may be in the middle of modification
+/// by preempted code.
+pub fn fallback(f: impl FnOnce(CFGR) -> CFGR) -> Clocks {
+    assert!(
+        SCB::vect_active() != VectActive::Exception(Exception::NonMaskableInt),
+        "Clocks can't be reconfigured in NMI handler"
+    );
+    let cfgr = unsafe { RCC::steal() }.constrain().cfgr;
+    f(cfgr).reconfigure()
+}
+
+/// Defines NMI handler which calls function on HSE failure
+///
+/// The function is called after the failure flag is cleared. NMI from other sources
+/// is ignored. Clocks are restored with [`fallback`](crate::rcc::css::fallback) outside
+/// of the handler, for example after [`take_hse_failure`](crate::rcc::css::take_hse_failure)
+/// returns `true`.
+#[macro_export]
+macro_rules! css_nmi {
+    ($callback:expr) => {
+        #[no_mangle]
+        #[allow(non_snake_case)]
+        unsafe extern "C" fn NonMaskableInt() {
+            if $crate::rcc::css::on_nmi() {
+                let callback: fn() = $callback;
+                callback();
+            }
+        }
+    };
+}
@@ -0,0 +1,263 @@
+use super::*;
+
+/// Clock configuration calculated and checked at compile time
+///
+/// Unlike [`CFGR`], PLL dividers are given explicitly, and all frequencies are calculated by
+/// `const fn`s, which panic on invalid configuration. Used in `const` items invalid
+/// configuration fails the build, and the resulting [`Clocks`] are available in static
+/// contexts.
+///
+/// ```rust,ignore
+/// // 8 MHz HSE, 168 MHz system clock, 48 MHz USB clock
+/// const CONFIG: ClockConfig = ClockConfig::hse(Hertz::MHz(8))
+///     .pll(8, 336, 2, 7)
+///     .prescalers(1, 4, 2);
+/// const CLOCKS: Clocks = CONFIG.clocks();
+///
+/// let clocks = dp.RCC.constrain().cfgr.freeze_config(&CONFIG);
+/// ```
+///
+/// Only the main PLL is configured, I2S and SAI clocks are not available.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ClockConfig {
+    hse: Option<u32>,
+    hse_bypass: bool,
+    pll: Option<(u8, u16, u8, u8)>,
+    hpre: u32,
+    ppre1: u32,
+    ppre2: u32,
+}
+
+impl ClockConfig {
+    /// System clock from HSI, no PLL, no bus prescalers
+    pub const fn hsi() -> Self {
+        Self {
+            hse: None,
+            hse_bypass: false,
+            pll: None,
+            hpre: 1,
+            ppre1: 1,
+            ppre2: 1,
+        }
+    }
+
+    /// System clock from HSE crystal oscillator from 4 to 26 MHz
+    pub const fn hse(freq: Hertz) -> Self {
+        let freq = freq.raw();
+        assert!(freq >= 4_000_000 && freq <= 26_000_000, "HSE out of range");
+        Self {
+            hse: Some(freq),
+            ..Self::hsi()
+        }
+    }
+
+    /// System clock from external clock up to 50 MHz on OSC_IN
+    pub const fn hse_bypass(freq: Hertz) -> Self {
+        let freq = freq.raw();
+        assert!(freq >= 1_000_000 && freq <= 50_000_000, "HSE out of range");
+        Self {
+            hse: Some(freq),
+            hse_bypass: true,
+            ..Self::hsi()
+        }
+    }
+
+    /// System clock from main PLL with input divider `m`, multiplier `n`, system clock
+    /// divider `p` and 48 MHz clock divider `q`
+    pub const fn pll(self, m: u8, n: u16, p: u8, q: u8) -> Self {
+        let src = self.pll_source();
+        assert!(m >= 2 && m <= 63, "PLLM out of range");
+        let vco_in = src / m as u32;
+        assert!(
+            vco_in >= 1_000_000 && vco_in <= 2_000_000,
+            "PLL input out of range"
+        );
+        assert!(n >= 50 && n <= 432, "PLLN out of range");
+        let vco_out = vco_in * n as u32;
+        assert!(
+            vco_out >= 100_000_000 && vco_out <= 432_000_000,
+            "PLL VCO out of range"
+        );
+        assert!(
+            p == 2 || p == 4 || p == 6 || p == 8,
+            "PLLP must be 2, 4, 6 or 8"
+        );
+        assert!(q >= 2 && q <= 15, "PLLQ out of range");
+        let sysclk = vco_out / p as u32;
+        assert!(
+            sysclk >= SYSCLK_MIN && sysclk <= SYSCLK_MAX,
+            "SYSCLK out of range"
+        );
+        Self {
+            pll: Some((m, n, p, q)),
+            ..self
+        }
+    }
+
+    /// Sets AHB prescaler (1, 2, 4, 8, 16, 64, 128, 256 or 512) and APB1 and APB2 prescalers
+    /// (1, 2, 4, 8 or 16)
+    pub const fn prescalers(self, hpre: u32, ppre1: u32, ppre2: u32) -> Self {
+        hpre_bits(hpre);
+        ppre_bits(ppre1);
+        ppre_bits(ppre2);
+        Self {
+            hpre,
+            ppre1,
+            ppre2,
+            ..self
+        }
+    }
+
+    const fn pll_source(&self) -> u32 {
+        match self.hse {
+            Some(hse) => hse,
+            None => HSI,
+        }
+    }
+
+    const fn sysclk(&self) -> u32 {
+        match self.pll {
+            Some((m, n, p, _)) => self.pll_source() / m as u32 * n as u32 / p as u32,
+            None => self.pll_source(),
+        }
+    }
+
+    /// Returns clock frequencies of the configuration
+    ///
+    /// Panics if APB clocks are out of range.
+    pub const fn clocks(&self) -> Clocks {
+        let sysclk = self.sysclk();
+        let hclk = sysclk / self.hpre;
+        let pclk1 = hclk / self.ppre1;
+        assert!(pclk1 <= PCLK1_MAX, "PCLK1 out of range");
+        let pclk2 = hclk / self.ppre2;
+        assert!(pclk2 <= PCLK2_MAX, "PCLK2 out of range");
+        let timclk1 = if self.ppre1 == 1 { pclk1 } else { pclk1 * 2 };
+        let timclk2 = if self.ppre2 == 1 { pclk2 } else { pclk2 * 2 };
+        let pll48clk = match self.pll {
+            Some((m, n, _, q)) => Some(Hertz::from_raw(
+                self.pll_source() / m as u32 * n as u32 / q as u32,
+            )),
+            None => None,
+        };
+        Clocks {
+            hclk: Hertz::from_raw(hclk),
+            pclk1: Hertz::from_raw(pclk1),
+            pclk2: Hertz::from_raw(pclk2),
+            timclk1: Hertz::from_raw(timclk1),
+            timclk2: Hertz::from_raw(timclk2),
+            sysclk: Hertz::from_raw(sysclk),
+            pll48clk,
+            pll: match self.pll {
+                Some((m, n, p, q)) => Some(PllConfig { m, n, p, q }),
+                None => None,
+            },
+            exact: true,
+
+            #[cfg(not(any(feature = "gpio-f412", feature = "gpio-f413", feature = "gpio-f446")))]
+            i2s_clk: None,
+            #[cfg(any(feature = "gpio-f412", feature = "gpio-f413", feature = "gpio-f446"))]
+            i2s_apb1_clk: None,
+            #[cfg(any(feature = "gpio-f412", feature = "gpio-f413", feature = "gpio-f446"))]
+            i2s_apb2_clk: None,
+
+            #[cfg(any(feature = "gpio-f413", feature = "gpio-f427", feature = "gpio-f469"))]
+            saia_clk: None,
+            #[cfg(any(feature = "gpio-f413", feature = "gpio-f427", feature = "gpio-f469"))]
+            saib_clk: None,
+            #[cfg(feature = "sai2")]
+            sai1_clk: None,
+            #[cfg(feature = "sai2")]
+            sai2_clk: None,
+        }
+    }
+}
+
+const fn hpre_bits(div: u32) -> u8 {
+    match div {
+        1 => 0b0000,
+        2 => 0b1000,
+        4 => 0b1001,
+        8 => 0b1010,
+        16 => 0b1011,
+        64 => 0b1100,
+        128 => 0b1101,
+        256 => 0b1110,
+        512 => 0b1111,
+        _ => panic!("invalid AHB prescaler"),
+    }
+}
+
+const fn ppre_bits(div: u32) -> u8 {
+    match div {
+        1 => 0b000,
+        2 => 0b100,
+        4 => 0b101,
+        8 => 0b110,
+        16 => 0b111,
+        _ => panic!("invalid APB prescaler"),
+    }
+}
+
+impl CFGR {
+    /// Initialises the hardware according to compile time `config` returning its Clocks
+    ///
+    /// Settings of the builder are ignored. Can also be used to change clocks at runtime,
+    /// like [`reconfigure`](Self::reconfigure).
+    pub fn freeze_config(self, config: &ClockConfig) -> Clocks {
+        let rcc = unsafe { &*RCC::ptr() };
+        let clocks = config.clocks();
+
+        Self::switch_to_hsi();
+        Self::flash_setup(clocks.sysclk.raw());
+
+        if config.hse.is_some() {
+            rcc.cr().modify(|_, w| {
+                w.hsebyp().bit(config.hse_bypass);
+                w.hseon().set_bit()
+            });
+            while rcc.cr().read().hserdy().bit_is_clear() {}
+        }
+
+        if let Some((m, n, p, q)) = config.pll {
+            // PLLR of some MCUs is kept
+            rcc.pllcfgr().modify(|_, w| unsafe {
+                w.pllm().bits(m);
+                w.plln().bits(n);
+                w.pllp().bits(p / 2 - 1);
+                w.pllq().bits(q);
+                w.pllsrc().bit(config.hse.is_some())
+            });
+            rcc.sscgr().reset();
+
+            #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+            Self::voltage_scale_setup(clocks.hclk.raw());
+
+            rcc.cr().modify(|_, w| w.pllon().set_bit());
+            while rcc.cr().read().pllrdy().bit_is_clear() {}
+
+            #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+            Self::overdrive_setup(clocks.hclk.raw());
+        }
+
+        rcc.cfgr().modify(|_, w| unsafe {
+            w.ppre2().bits(ppre_bits(config.ppre2));
+            w.ppre1().bits(ppre_bits(config.ppre1));
+            w.hpre().bits(hpre_bits(config.hpre))
+        });
+        cortex_m::asm::delay(16);
+
+        rcc.cfgr().modify(|_, w| {
+            w.sw().variant(if config.pll.is_some() {
+                SW::Pll
+            } else if config.hse.is_some() {
+                SW::Hse
+            } else {
+                SW::Hsi
+            })
+        });
+
+        clocks
+    }
+}
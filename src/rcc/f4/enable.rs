@@ -199,6 +199,11 @@ bus! {
     FMPI2C1 => (APB1, 24),
 }
 
+#[cfg(feature = "lptim1")]
+bus! {
+    LPTIM1 => (APB1, 9),
+}
+
 bus! {
     USART1 => (APB2, 4),
     USART2 => (APB1, 17),
@@ -296,6 +301,15 @@ bus! {
     SDIO => (APB2, 11),
 }
 
+bus! {
+    WWDG => (APB1, 11),
+}
+
+#[cfg(feature = "dcmi")]
+bus! {
+    DCMI => (AHB2, 0),
+}
+
 bus! {
     TIM1 => (APB2, 0),
     TIM5 => (APB1, 3),
@@ -15,7 +15,10 @@ use pll::SaiPll;
 mod pll;
 
 mod enable;
+
+mod config;
 use crate::pac::rcc::RegisterBlock as RccRB;
+pub use config::ClockConfig;
 
 /// Enable/disable peripheral
 #[allow(clippy::missing_safety_doc)]
@@ -221,6 +224,7 @@ impl RccExt for RCC {
                 pclk2: None,
                 sysclk: None,
                 pll48clk: false,
+                spread_spectrum: None,
                 i2s_ckin: None,
                 #[cfg(any(
                     feature = "gpio-f401",
@@ -313,6 +317,29 @@ pub const PCLK2_MAX: u32 = SYSCLK_MAX / 2;
 /// Maximum APB1 peripheral clock frequency
 pub const PCLK1_MAX: u32 = PCLK2_MAX / 2;
 
+/// Maximum clock frequency returned by [`audio_clk`]
+pub const AUDIO_CLK_MAX: u32 = 100_000_000;
+
+/// Returns I2S or SAI clock frequency which can be divided exactly to `sample_rate`
+///
+/// The frequency is the biggest multiple of 512 × `sample_rate` not above [`AUDIO_CLK_MAX`], so
+/// both I2S and SAI dividers can produce 256 × `sample_rate` master clock. Pass it to I2S or SAI
+/// clock methods of [`CFGR`]. PLL can't generate every frequency, so the resulting clock in
+/// [`Clocks`] must still be checked if exact rate is required.
+///
+/// ```rust,ignore
+/// let clocks = rcc.cfgr.use_hse(8.MHz()).i2s_clk(audio_clk(48.kHz())).freeze();
+/// ```
+pub const fn audio_clk(sample_rate: Hertz) -> Hertz {
+    let step = sample_rate.raw() * 512;
+    Hertz::from_raw(AUDIO_CLK_MAX / step * step)
+}
+
+/// Clock configuration builder
+///
+/// Can be cloned to keep several clock profiles and switch between them with
+/// [`reconfigure`](Self::reconfigure).
+#[derive(Clone)]
 pub struct CFGR {
     hse: Option<u32>,
     hse_bypass: bool,
@@ -321,6 +348,7 @@ pub struct CFGR {
     pclk2: Option<u32>,
     sysclk: Option<u32>,
     pll48clk: bool,
+    spread_spectrum: Option<SpreadSpectrum>,
 
     i2s_ckin: Option<u32>,
     #[cfg(any(
@@ -357,6 +385,11 @@ impl CFGR {
     /// frequency specified in the call to use_hse(), and the OSC_OUT pin should not be connected.
     ///
     /// This function has no effect unless use_hse() is also called.
+    ///
+    /// ```rust,ignore
+    /// // 26 MHz TCXO connected to OSC_IN
+    /// let clocks = rcc.cfgr.use_hse(26.MHz()).bypass_hse_oscillator().sysclk(84.MHz()).freeze();
+    /// ```
     pub fn bypass_hse_oscillator(self) -> Self {
         Self {
             hse_bypass: true,
@@ -389,6 +422,28 @@ impl CFGR {
         self
     }
 
+    /// Enables spread spectrum modulation of the main PLL to reduce electromagnetic emission
+    ///
+    /// `modulation` frequency must not exceed 10 kHz, peak modulation depth is set in per mille
+    /// of PLL frequency, up to 20 ‰. Has no effect if system clock does not come from PLL.
+    /// USB, I2S and SAI clocks from the main PLL are modulated too.
+    ///
+    /// `freeze` panics if `modulation` is 0 or the depth is too big for the modulation frequency
+    /// and PLL multiplier.
+    pub fn spread_spectrum(
+        mut self,
+        modulation: Hertz,
+        depth_permille: u16,
+        spread: Spread,
+    ) -> Self {
+        self.spread_spectrum = Some(SpreadSpectrum {
+            modulation: modulation.raw(),
+            depth_permille,
+            spread,
+        });
+        self
+    }
+
     /// Declares that the selected frequency is available at the I2S clock input pin (I2S_CKIN).
     ///
     /// If this frequency matches the requested SAI or I2S frequencies, the external I2S clock is
@@ -680,6 +735,46 @@ impl CFGR {
         }
     }
 
+    // Selects the lowest regulator voltage scale sufficient for `hclk`, PLL must be disabled
+    //
+    // Scale 3 allows up to 120 MHz, scale 2 up to 144 MHz (168 MHz with overdrive) and scale 1
+    // up to 168 MHz (180 MHz with overdrive).
+    #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+    fn voltage_scale_setup(hclk: u32) {
+        let rcc = unsafe { &*RCC::ptr() };
+        // Enable clock for PWR peripheral
+        rcc.apb1enr().modify(|_, w| w.pwren().set_bit());
+
+        // Stall the pipeline to work around erratum 2.1.13 (DM00037591)
+        cortex_m::asm::dsb();
+
+        // VOS value is the scale number counted from 3
+        let vos = if hclk <= 120_000_000 {
+            0b01
+        } else if hclk <= 144_000_000 {
+            0b10
+        } else {
+            0b11
+        };
+        let pwr = unsafe { &*crate::pac::PWR::ptr() };
+        pwr.cr().modify(|_, w| unsafe { w.vos().bits(vos) });
+    }
+
+    // Waits until voltage scale is applied and enables voltage regulator overdrive if `hclk`
+    // is above the limit, PLL must be ready and PWR clocked
+    #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+    fn overdrive_setup(hclk: u32) {
+        let pwr = unsafe { &*crate::pac::PWR::ptr() };
+        // Voltage scale selected while PLL was disabled is applied when PLL is on
+        while pwr.csr().read().vosrdy().bit_is_clear() {}
+        if hclk > 168_000_000 {
+            pwr.cr().modify(|_, w| w.oden().set_bit());
+            while pwr.csr().read().odrdy().bit_is_clear() {}
+            pwr.cr().modify(|_, w| w.odswen().set_bit());
+            while pwr.csr().read().odswrdy().bit_is_clear() {}
+        }
+    }
+
     /// Initialises the hardware according to CFGR state returning a Clocks instance.
     /// Panics if overclocking is attempted.
     pub fn freeze(self) -> Clocks {
@@ -697,6 +792,80 @@ impl CFGR {
         self.freeze_internal(true)
     }
 
+    /// Changes clock configuration at runtime, after clocks were frozen, returning new Clocks.
+    /// Panics if overclocking is attempted.
+    ///
+    /// System clock is temporarily switched to HSI, so PLLs can be reprogrammed and flash wait
+    /// states, voltage regulator overdrive and bus prescalers are changed only while the core
+    /// runs at 16 MHz. Peripherals configured with previous `Clocks` (serial baud rates, timers,
+    /// etc.) must be configured again.
+    ///
+    /// ```rust,ignore
+    /// let fast = rcc.cfgr.use_hse(8.MHz()).sysclk(168.MHz());
+    /// let slow = fast.clone().sysclk(16.MHz());
+    /// let mut clocks = fast.clone().freeze();
+    /// // ...
+    /// clocks = slow.clone().reconfigure();
+    /// ```
+    pub fn reconfigure(self) -> Clocks {
+        Self::switch_to_hsi();
+        self.freeze_internal(false)
+    }
+
+    // Selects HSI as system clock and stops everything `freeze_internal` configures
+    fn switch_to_hsi() {
+        let rcc = unsafe { &*RCC::ptr() };
+
+        rcc.cr().modify(|_, w| w.hsion().set_bit());
+        while rcc.cr().read().hsirdy().bit_is_clear() {}
+
+        rcc.cfgr().modify(|_, w| w.sw().variant(SW::Hsi));
+        while rcc.cfgr().read().sws().bits() != 0b00 {}
+
+        // Any prescaler fits 16 MHz, so dividers are reset before they are set for the new clocks
+        rcc.cfgr().modify(|_, w| unsafe {
+            w.ppre2().bits(0b000);
+            w.ppre1().bits(0b000);
+            w.hpre().variant(HPRE::Div1)
+        });
+        cortex_m::asm::delay(16);
+
+        // PLL configuration can only be written while PLLs are disabled
+        rcc.cr().modify(|_, w| w.pllon().clear_bit());
+        while rcc.cr().read().pllrdy().bit_is_set() {}
+        #[cfg(not(feature = "gpio-f410"))]
+        {
+            rcc.cr().modify(|_, w| w.plli2son().clear_bit());
+            while rcc.cr().read().plli2srdy().bit_is_set() {}
+        }
+        #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+        {
+            rcc.cr().modify(|_, w| w.pllsaion().clear_bit());
+            while rcc.cr().read().pllsairdy().bit_is_set() {}
+        }
+
+        // HSE bypass can only be changed while HSE is disabled
+        rcc.cr().modify(|_, w| w.hseon().clear_bit());
+        while rcc.cr().read().hserdy().bit_is_set() {}
+        rcc.cr().modify(|_, w| w.hsebyp().not_bypassed());
+
+        // Overdrive is enabled again by `freeze_internal` if the new HCLK needs it
+        #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+        if rcc.apb1enr().read().pwren().bit_is_set() {
+            let pwr = unsafe { &*crate::pac::PWR::ptr() };
+            if pwr.cr().read().oden().bit_is_set() {
+                pwr.cr().modify(|_, w| {
+                    w.odswen().clear_bit();
+                    w.oden().clear_bit()
+                });
+                while pwr.csr().read().odswrdy().bit_is_set() {}
+            }
+        }
+
+        // HSI runs with no wait states
+        Self::flash_setup(HSI);
+    }
+
     fn freeze_internal(self, unchecked: bool) -> Clocks {
         let rcc = unsafe { &*RCC::ptr() };
 
@@ -768,38 +937,39 @@ impl CFGR {
         Self::flash_setup(sysclk);
 
         if self.hse.is_some() {
+            // HSE bypass can only be changed while HSE is disabled, for example when bootloader
+            // left HSE running in another mode
+            if rcc.cr().read().hsebyp().bit_is_set() != self.hse_bypass {
+                rcc.cr().modify(|_, w| w.hseon().clear_bit());
+                while rcc.cr().read().hserdy().bit_is_set() {}
+            }
             // enable HSE and wait for it to be ready
             rcc.cr().modify(|_, w| {
-                if self.hse_bypass {
-                    w.hsebyp().bypassed();
-                }
+                w.hsebyp().bit(self.hse_bypass);
                 w.hseon().set_bit()
             });
             while rcc.cr().read().hserdy().bit_is_clear() {}
         }
 
         if plls.use_pll {
-            // Enable PLL
-            rcc.cr().modify(|_, w| w.pllon().set_bit());
+            // Spread spectrum must be configured before PLL is enabled
+            match self.spread_spectrum {
+                Some(ss) => ss.write_sscgr(pllsrcclk, unchecked),
+                None => rcc.sscgr().reset(),
+            }
 
-            // Enable voltage regulator overdrive if HCLK is above the limit
+            // Voltage scale can only be changed while PLL is disabled
             #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
-            if hclk > 168_000_000 {
-                // Enable clock for PWR peripheral
-                rcc.apb1enr().modify(|_, w| w.pwren().set_bit());
-
-                // Stall the pipeline to work around erratum 2.1.13 (DM00037591)
-                cortex_m::asm::dsb();
-
-                let pwr = unsafe { &*crate::pac::PWR::ptr() };
-                pwr.cr().modify(|_, w| w.oden().set_bit());
-                while pwr.csr().read().odrdy().bit_is_clear() {}
-                pwr.cr().modify(|_, w| w.odswen().set_bit());
-                while pwr.csr().read().odswrdy().bit_is_clear() {}
-            }
+            Self::voltage_scale_setup(hclk);
+
+            // Enable PLL
+            rcc.cr().modify(|_, w| w.pllon().set_bit());
 
             // Wait for PLL to stabilise
             while rcc.cr().read().pllrdy().bit_is_clear() {}
+
+            #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+            Self::overdrive_setup(hclk);
         }
 
         #[cfg(not(feature = "gpio-f410"))]
@@ -853,6 +1023,13 @@ impl CFGR {
         let pclk_mul = if ppre2 == 1 { 1 } else { 2 };
         let timclk2 = Hertz::from_raw(pclk2 * pclk_mul);
 
+        let requested = |req: Option<u32>, real: u32| req.map_or(true, |req| req == real);
+        let exact = requested(self.sysclk, sysclk)
+            && requested(self.hclk, hclk)
+            && requested(self.pclk1, pclk1)
+            && requested(self.pclk2, pclk2)
+            && (!self.pll48clk || plls.pll48clk == Some(48_000_000));
+
         let clocks = Clocks {
             hclk: hclk.Hz(),
             pclk1: pclk1.Hz(),
@@ -861,6 +1038,8 @@ impl CFGR {
             timclk2,
             sysclk: sysclk.Hz(),
             pll48clk: plls.pll48clk.map(Hertz::from_raw),
+            pll: plls.use_pll.then(PllConfig::read),
+            exact,
 
             #[cfg(not(any(feature = "gpio-f412", feature = "gpio-f413", feature = "gpio-f446")))]
             i2s_clk: plls.i2s.i2s_clk.map(Hertz::from_raw),
@@ -880,13 +1059,149 @@ impl CFGR {
         };
 
         if self.pll48clk {
-            assert!(clocks.is_pll48clk_valid());
+            assert!(clocks.is_48mhz_accurate());
         }
 
         clocks
     }
 }
 
+/// Oscillators and system clock switch, saved before Stop mode
+///
+/// Stop mode stops HSE and all PLLs and wakes up on HSI. PLL configuration, bus prescalers
+/// and flash wait states are kept, so enabling oscillators again and switching system clock
+/// back restores the frozen `Clocks`.
+pub(crate) struct ClockState {
+    hse: bool,
+    pll: bool,
+    #[cfg(not(feature = "gpio-f410"))]
+    plli2s: bool,
+    #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+    pllsai: bool,
+    sw: u8,
+    #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+    overdrive: bool,
+}
+
+impl ClockState {
+    pub(crate) fn save() -> Self {
+        let rcc = unsafe { &*RCC::ptr() };
+        let cr = rcc.cr().read();
+        Self {
+            hse: cr.hseon().bit_is_set(),
+            pll: cr.pllon().bit_is_set(),
+            #[cfg(not(feature = "gpio-f410"))]
+            plli2s: cr.plli2son().bit_is_set(),
+            #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+            pllsai: cr.pllsaion().bit_is_set(),
+            sw: rcc.cfgr().read().sw().bits(),
+            #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+            overdrive: rcc.apb1enr().read().pwren().bit_is_set()
+                && unsafe { &*crate::pac::PWR::ptr() }
+                    .cr()
+                    .read()
+                    .oden()
+                    .bit_is_set(),
+        }
+    }
+
+    pub(crate) fn restore(&self) {
+        let rcc = unsafe { &*RCC::ptr() };
+
+        if self.hse {
+            // HSE bypass is kept
+            rcc.cr().modify(|_, w| w.hseon().set_bit());
+            while rcc.cr().read().hserdy().bit_is_clear() {}
+        }
+
+        rcc.cr().modify(|_, w| {
+            #[cfg(not(feature = "gpio-f410"))]
+            if self.plli2s {
+                w.plli2son().set_bit();
+            }
+            #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+            if self.pllsai {
+                w.pllsaion().set_bit();
+            }
+            if self.pll {
+                w.pllon().set_bit();
+            }
+            w
+        });
+
+        // Overdrive is disabled by hardware in Stop mode
+        #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+        if self.overdrive {
+            let pwr = unsafe { &*crate::pac::PWR::ptr() };
+            pwr.cr().modify(|_, w| w.oden().set_bit());
+            while pwr.csr().read().odrdy().bit_is_clear() {}
+            pwr.cr().modify(|_, w| w.odswen().set_bit());
+            while pwr.csr().read().odswrdy().bit_is_clear() {}
+        }
+
+        if self.pll {
+            while rcc.cr().read().pllrdy().bit_is_clear() {}
+        }
+        #[cfg(not(feature = "gpio-f410"))]
+        if self.plli2s {
+            while rcc.cr().read().plli2srdy().bit_is_clear() {}
+        }
+        #[cfg(any(feature = "gpio-f427", feature = "gpio-f446", feature = "gpio-f469"))]
+        if self.pllsai {
+            while rcc.cr().read().pllsairdy().bit_is_clear() {}
+        }
+
+        rcc.cfgr().modify(|_, w| unsafe { w.sw().bits(self.sw) });
+        while rcc.cfgr().read().sws().bits() != self.sw {}
+    }
+}
+
+/// Spread spectrum modulation profile
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Spread {
+    /// Frequency is modulated around PLL frequency
+    Center,
+    /// Frequency is modulated below PLL frequency
+    Down,
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct SpreadSpectrum {
+    modulation: u32,
+    depth_permille: u16,
+    spread: Spread,
+}
+
+impl SpreadSpectrum {
+    // Writes SSCGR, PLL input divider and multiplier must be already set
+    fn write_sscgr(&self, pllsrcclk: u32, unchecked: bool) {
+        assert!(self.modulation > 0, "modulation frequency must not be 0");
+        assert!(unchecked || (self.modulation <= 10_000 && self.depth_permille <= 20));
+        let rcc = unsafe { &*RCC::ptr() };
+        let pllcfgr = rcc.pllcfgr().read();
+        let pllm = u32::from(pllcfgr.pllm().bits());
+        let plln = u64::from(pllcfgr.plln().bits());
+
+        // MODPER = round(f_PLL_IN / (4 × f_Mod))
+        let modper = (pllsrcclk / pllm + 2 * self.modulation) / (4 * self.modulation);
+        assert!((1..=0x1fff).contains(&modper));
+        // INCSTEP = round((2^15 − 1) × md × PLLN / (100 × 5 × MODPER)), md in %
+        let divisor = 5000 * u64::from(modper);
+        let incstep =
+            ((0x7fff * u64::from(self.depth_permille) * plln + divisor / 2) / divisor) as u32;
+        assert!(modper * incstep <= 0x7fff);
+
+        rcc.sscgr().write(|w| unsafe {
+            w.modper().bits(modper as u16);
+            w.incstep().bits(incstep as u16);
+            w.spreadsel().bit(self.spread == Spread::Down);
+            w.sscgen().set_bit()
+        });
+    }
+}
+
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 struct PllSetup {
@@ -1085,9 +1400,36 @@ impl RealSaiClocks {
     }
 }
 
+/// Dividers of the main PLL
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PllConfig {
+    /// Input divider
+    pub m: u8,
+    /// VCO multiplier
+    pub n: u16,
+    /// System clock divider
+    pub p: u8,
+    /// 48 MHz clock divider
+    pub q: u8,
+}
+
+impl PllConfig {
+    fn read() -> Self {
+        let pllcfgr = unsafe { &*RCC::ptr() }.pllcfgr().read();
+        Self {
+            m: pllcfgr.pllm().bits(),
+            n: pllcfgr.plln().bits(),
+            p: (pllcfgr.pllp().bits() + 1) * 2,
+            q: pllcfgr.pllq().bits(),
+        }
+    }
+}
+
 /// Frozen clock frequencies
 ///
-/// The existence of this value indicates that the clock configuration can no longer be changed
+/// The existence of this value indicates that the clock configuration can no longer be changed,
+/// except with [`CFGR::reconfigure`]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Clocks {
@@ -1098,6 +1440,8 @@ pub struct Clocks {
     timclk2: Hertz,
     sysclk: Hertz,
     pll48clk: Option<Hertz>,
+    pll: Option<PllConfig>,
+    exact: bool,
 
     #[cfg(not(any(feature = "gpio-f412", feature = "gpio-f413", feature = "gpio-f446")))]
     i2s_clk: Option<Hertz>,
@@ -1154,13 +1498,32 @@ impl Clocks {
 
     /// Returns true if the PLL48 clock is within USB
     /// specifications. It is required to use the USB functionality.
+    #[deprecated(note = "use `is_48mhz_accurate`")]
     pub fn is_pll48clk_valid(&self) -> bool {
-        // USB specification allows +-0.25%
+        self.is_48mhz_accurate()
+    }
+
+    /// Returns true if the 48 MHz clock of USB, SDIO and RNG deviates by no more than 0.25 %,
+    /// as required by USB specification
+    pub fn is_48mhz_accurate(&self) -> bool {
         self.pll48clk
             .map(|freq| 48_000_000_u32.abs_diff(freq.raw()) <= 120_000)
             .unwrap_or_default()
     }
 
+    /// Returns dividers of the main PLL if it is enabled
+    pub fn main_pll(&self) -> Option<PllConfig> {
+        self.pll
+    }
+
+    /// Returns true if requested system, AHB and APB clocks and 48 MHz clock were reached
+    /// exactly
+    ///
+    /// I2S and SAI clocks are not compared, they can be checked with their own methods.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
     /// Returns the frequency of the I2S clock.
     #[cfg(not(any(feature = "gpio-f412", feature = "gpio-f413", feature = "gpio-f446")))]
     pub fn i2s_clk(&self) -> Option<Hertz> {
@@ -0,0 +1,169 @@
+//! Low speed clocks (LSE and LSI) and RTC clock source
+//!
+//! LSE, its bypass and drive level and RTC clock selection live in the backup domain, so
+//! they need [`BackupDomain`] access. LSI also clocks the independent watchdog, which starts
+//! it by itself.
+//!
+//! ```rust,ignore
+//! let mut bd = BackupDomain::take(&mut dp.PWR).unwrap();
+//! lsclk::enable_lse(&mut bd, LSEClockMode::Oscillator);
+//! lsclk::select_rtc_clock(&mut bd, RtcClock::Lse);
+//! ```
+
+use super::BackupDomain;
+use crate::pac::RCC;
+
+/// LSE clock mode.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LSEClockMode {
+    /// Enable LSE oscillator to use external crystal or ceramic resonator.
+    Oscillator,
+    /// Bypass LSE oscillator to use external clock source.
+    /// Use this if an external oscillator is used which is not connected to `OSC32_IN` such as a MEMS resonator.
+    Bypass,
+}
+
+/// LSE oscillator drive level
+#[cfg(any(
+    feature = "gpio-f410",
+    feature = "gpio-f411",
+    feature = "gpio-f412",
+    feature = "gpio-f413",
+    feature = "gpio-f446",
+))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LseDrive {
+    /// Low power mode, default
+    Low,
+    /// High drive mode, for crystals with higher load capacitance
+    High,
+}
+
+/// RTC clock source
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RtcClock {
+    /// No clock, state after backup domain reset
+    None,
+    Lse,
+    Lsi,
+    /// HSE divided by a prescaler from 2 to 31, result must be 1 MHz
+    Hse(u8),
+}
+
+/// Starts LSE in `mode` and waits until it is ready
+///
+/// Bypass mode can only be changed while LSE is disabled, it is disabled first when needed.
+pub fn enable_lse(_bd: &mut BackupDomain, mode: LSEClockMode) {
+    let rcc = unsafe { &*RCC::ptr() };
+    let bypass = mode == LSEClockMode::Bypass;
+    if is_lse_ready() && rcc.bdcr().read().lsebyp().bit() == bypass {
+        return;
+    }
+    rcc.bdcr().modify(|_, w| w.lseon().clear_bit());
+    while is_lse_ready() {}
+    rcc.bdcr().modify(|_, w| w.lsebyp().bit(bypass));
+    rcc.bdcr().modify(|_, w| w.lseon().set_bit());
+    while !is_lse_ready() {}
+}
+
+/// Sets drive level of LSE oscillator
+///
+/// Should be set before LSE is enabled.
+#[cfg(any(
+    feature = "gpio-f410",
+    feature = "gpio-f411",
+    feature = "gpio-f412",
+    feature = "gpio-f413",
+    feature = "gpio-f446",
+))]
+pub fn set_lse_drive(_bd: &mut BackupDomain, drive: LseDrive) {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.bdcr()
+        .modify(|_, w| w.lsemod().bit(drive == LseDrive::High));
+}
+
+/// Stops LSE
+pub fn disable_lse(_bd: &mut BackupDomain) {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.bdcr().modify(|_, w| w.lseon().clear_bit());
+}
+
+/// Is LSE running and stable?
+pub fn is_lse_ready() -> bool {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.bdcr().read().lserdy().bit_is_set()
+}
+
+/// Starts LSI and waits until it is ready
+pub fn enable_lsi() {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.csr().modify(|_, w| w.lsion().on());
+    while !is_lsi_ready() {}
+}
+
+/// Stops LSI
+///
+/// LSI stays running while independent watchdog is started.
+pub fn disable_lsi() {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.csr().modify(|_, w| w.lsion().off());
+}
+
+/// Is LSI running and stable?
+pub fn is_lsi_ready() -> bool {
+    let rcc = unsafe { &*RCC::ptr() };
+    rcc.csr().read().lsirdy().is_ready()
+}
+
+/// Selects RTC clock source, which must be already running
+///
+/// RTC clock source can be selected only once, after that only backup domain reset
+/// returns it to [`RtcClock::None`]. Returns `false` if another source is already selected.
+///
+/// # Panics
+///
+/// If HSE prescaler is out of range.
+pub fn select_rtc_clock(_bd: &mut BackupDomain, clock: RtcClock) -> bool {
+    let rcc = unsafe { &*RCC::ptr() };
+    let current = rtc_clock();
+    match clock {
+        RtcClock::None => return current == RtcClock::None,
+        RtcClock::Hse(prescaler) => assert!((2..=31).contains(&prescaler)),
+        _ => {}
+    }
+    match (current, clock) {
+        (RtcClock::None, _) => {}
+        // HSE prescaler is not in the backup domain, so it can still be changed
+        (RtcClock::Hse(_), RtcClock::Hse(_)) => {}
+        (current, clock) => return current == clock,
+    }
+    if let RtcClock::Hse(prescaler) = clock {
+        rcc.cfgr().modify(|_, w| w.rtcpre().set(prescaler));
+    }
+    if current == RtcClock::None {
+        rcc.bdcr().modify(|_, w| match clock {
+            RtcClock::Lse => w.rtcsel().lse(),
+            RtcClock::Lsi => w.rtcsel().lsi(),
+            _ => w.rtcsel().hse(),
+        });
+    }
+    true
+}
+
+/// Returns selected RTC clock source
+pub fn rtc_clock() -> RtcClock {
+    let rcc = unsafe { &*RCC::ptr() };
+    let rtcsel = rcc.bdcr().read().rtcsel();
+    if rtcsel.is_no_clock() {
+        RtcClock::None
+    } else if rtcsel.is_lse() {
+        RtcClock::Lse
+    } else if rtcsel.is_lsi() {
+        RtcClock::Lsi
+    } else {
+        RtcClock::Hse(rcc.cfgr().read().rtcpre().bits())
+    }
+}
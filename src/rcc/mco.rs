@@ -0,0 +1,127 @@
+//! Microcontroller clock outputs (MCO1 on PA8 and MCO2 on PC9)
+//!
+//! Routes one of internal clocks, divided by [`McoPrescaler`], to a pin. Can be used to clock
+//! external PHYs and codecs or to check the clock tree with an oscilloscope.
+//!
+//! ```rust,ignore
+//! // 25 MHz for Ethernet PHY from 8 MHz HSE clocked PLL
+//! let mco1 = Mco1::new(gpioa.pa8, Mco1Source::Hse, McoPrescaler::Div1);
+//! let mco2 = Mco2::new(gpioc.pc9, Mco2Source::Sysclk, McoPrescaler::Div4);
+//! ```
+//!
+//! Output frequency must not exceed 100 MHz.
+
+use crate::gpio::alt::rcc as alt;
+use crate::gpio::{PinSpeed, Speed};
+use crate::pac::RCC;
+
+/// Clock source of MCO1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Mco1Source {
+    Hsi = 0b00,
+    Lse = 0b01,
+    Hse = 0b10,
+    Pll = 0b11,
+}
+
+/// Clock source of MCO2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Mco2Source {
+    Sysclk = 0b00,
+    #[cfg(not(feature = "gpio-f410"))]
+    Plli2s = 0b01,
+    Hse = 0b10,
+    Pll = 0b11,
+}
+
+/// Division factor of clock output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum McoPrescaler {
+    Div1 = 0b000,
+    Div2 = 0b100,
+    Div3 = 0b101,
+    Div4 = 0b110,
+    Div5 = 0b111,
+}
+
+fn configure_mco1(source: Mco1Source, prescaler: McoPrescaler) {
+    let rcc = unsafe { &*RCC::ptr() };
+    cortex_m::interrupt::free(|_| {
+        rcc.cfgr().modify(|_, w| unsafe {
+            w.mco1().bits(source as u8);
+            w.mco1pre().bits(prescaler as u8)
+        })
+    });
+}
+
+fn configure_mco2(source: Mco2Source, prescaler: McoPrescaler) {
+    let rcc = unsafe { &*RCC::ptr() };
+    cortex_m::interrupt::free(|_| {
+        rcc.cfgr().modify(|_, w| unsafe {
+            w.mco2().bits(source as u8);
+            w.mco2pre().bits(prescaler as u8)
+        })
+    });
+}
+
+/// Clock output 1 on PA8
+pub struct Mco1 {
+    pin: alt::Mco1,
+}
+
+impl Mco1 {
+    /// Outputs `source` divided by `prescaler` on `pin`
+    pub fn new(pin: impl Into<alt::Mco1>, source: Mco1Source, prescaler: McoPrescaler) -> Self {
+        let mut pin = pin.into();
+        pin.set_speed(Speed::VeryHigh);
+        configure_mco1(source, prescaler);
+        Self { pin }
+    }
+
+    /// Changes clock source and prescaler
+    ///
+    /// Output may glitch, ST recommends to configure MCO before enabling external oscillators
+    /// and PLL.
+    pub fn set_source(&mut self, source: Mco1Source, prescaler: McoPrescaler) {
+        configure_mco1(source, prescaler);
+    }
+
+    /// Returns the pin, clock output stays configured
+    pub fn release(self) -> alt::Mco1 {
+        self.pin
+    }
+}
+
+/// Clock output 2 on PC9
+pub struct Mco2 {
+    pin: alt::Mco2,
+}
+
+impl Mco2 {
+    /// Outputs `source` divided by `prescaler` on `pin`
+    pub fn new(pin: impl Into<alt::Mco2>, source: Mco2Source, prescaler: McoPrescaler) -> Self {
+        let mut pin = pin.into();
+        pin.set_speed(Speed::VeryHigh);
+        configure_mco2(source, prescaler);
+        Self { pin }
+    }
+
+    /// Changes clock source and prescaler
+    ///
+    /// Output may glitch, ST recommends to configure MCO before enabling external oscillators
+    /// and PLL.
+    pub fn set_source(&mut self, source: Mco2Source, prescaler: McoPrescaler) {
+        configure_mco2(source, prescaler);
+    }
+
+    /// Returns the pin, clock output stays configured
+    pub fn release(self) -> alt::Mco2 {
+        self.pin
+    }
+}
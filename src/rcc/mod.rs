@@ -39,12 +39,53 @@
 //! though I2S or SAI are available. On the STM32F410, the I2S clock is generated by the main PLL,
 //! and on the STM32F413/423 SAI clocks are generated by the I2S PLL. On these MCUs, the actual
 //! frequencies may substantially deviate from the requested frequencies.
+//!
+//! # Peripheral clocks
+//!
+//! PAC peripherals implement [`Enable`], [`LPEnable`] and [`Reset`], so drivers outside of
+//! this crate can switch their clocks and reset them without touching RCC registers.
+//! [`BusClock`] and [`BusTimerClock`] give the frequency of the bus the peripheral is
+//! connected to.
+//!
+//! ```rust,ignore
+//! use stm32f4xx_hal::rcc::{BusClock, Enable, Reset};
+//!
+//! unsafe {
+//!     pac::DCMI::enable_unchecked();
+//!     pac::DCMI::reset_unchecked();
+//! }
+//! let freq = pac::DCMI::clock(&clocks);
+//! ```
 mod f4;
 pub use f4::*;
 
+mod backup;
+pub use backup::{BackupDomain, BackupRegister, BackupValue, BACKUP_REGISTERS};
+#[cfg(any(
+    feature = "gpio-f417",
+    feature = "gpio-f427",
+    feature = "gpio-f446",
+    feature = "gpio-f469"
+))]
+mod bkpsram;
+#[cfg(any(
+    feature = "gpio-f417",
+    feature = "gpio-f427",
+    feature = "gpio-f446",
+    feature = "gpio-f469"
+))]
+pub use bkpsram::{BackupSram, BACKUP_SRAM_SIZE};
+
+pub mod css;
+pub mod lsclk;
+pub mod mco;
+
 use fugit::HertzU32 as Hertz;
 
 /// Bus associated to peripheral
+///
+/// Implemented for PAC peripherals, it is sealed, so new peripherals can't be added outside
+/// of this crate.
 pub trait RccBus: crate::Sealed {
     /// Bus type;
     type Bus;
@@ -62,6 +103,18 @@ pub trait BusTimerClock {
     fn timer_clock(clocks: &Clocks) -> Hertz;
 }
 
+/// Frequency of the kernel clock of peripheral, used for baud rate and timing calculations
+///
+/// Accounts for timer clock doubling on APB buses with prescaler and for peripherals
+/// clocked from the 48 MHz domain. Kernel clock of SPI in I2S mode is given by
+/// [`I2sFreq`](crate::i2s::I2sFreq) instead.
+pub trait PeripheralClock {
+    /// Calculates frequency of the kernel clock depending on `Clock` state
+    ///
+    /// Returns zero if the clock is not enabled.
+    fn kernel_clock(clocks: &Clocks) -> Hertz;
+}
+
 impl<T> BusClock for T
 where
     T: RccBus,
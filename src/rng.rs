@@ -24,11 +24,11 @@ use core::cmp;
 use core::mem;
 
 use crate::pac::RNG;
-use crate::rcc::{Clocks, Enable, Reset};
+use crate::rcc::{Clocks, Enable, PeripheralClock, Reset};
 use core::num::NonZeroU32;
 use core::ops::Shl;
 use embedded_hal_02::blocking::rng;
-use fugit::RateExtU32;
+use fugit::{HertzU32 as Hertz, RateExtU32};
 use rand_core::RngCore;
 
 /// Random number generator specific errors
@@ -75,10 +75,17 @@ pub trait RngExt {
     ///
     /// # Panics
     ///
-    /// This function will panic if `PLL48_CLK < 1/16 HCLK`.
+    /// This function will panic if `PLL48_CLK < 1/16 HCLK` or `PLL48_CLK` is not accurate
+    /// 48 MHz, see [`Clocks::is_48mhz_accurate`].
     fn constrain(self, clocks: &Clocks) -> Rng;
 }
 
+impl PeripheralClock for RNG {
+    fn kernel_clock(clocks: &Clocks) -> Hertz {
+        clocks.pll48clk().unwrap_or_else(|| 0.Hz())
+    }
+}
+
 impl RngExt for RNG {
     fn constrain(self, clocks: &Clocks) -> Rng {
         cortex_m::interrupt::free(|_| {
@@ -90,8 +97,9 @@ impl RngExt for RNG {
 
             // verify the clock configuration is valid
             let hclk = clocks.hclk();
-            let rng_clk = clocks.pll48clk().unwrap_or_else(|| 0.Hz());
+            let rng_clk = RNG::kernel_clock(clocks);
             assert!(rng_clk >= (hclk / 16));
+            assert!(clocks.is_48mhz_accurate());
 
             // enable the RNG peripheral
             self.cr().modify(|_, w| w.rngen().set_bit());
@@ -4,8 +4,9 @@
 
 use crate::bb;
 use crate::pac::rtc::{dr, tr};
-use crate::pac::{self, rcc::RegisterBlock, PWR, RCC, RTC};
-use crate::rcc::Enable;
+use crate::pac::{self, PWR, RTC};
+use crate::rcc::lsclk::{self, RtcClock};
+use crate::rcc::BackupDomain;
 use core::fmt;
 use core::marker::PhantomData;
 use fugit::RateExtU32;
@@ -66,6 +67,8 @@ impl From<Weekday> for AlarmDay {
 pub struct Lse;
 /// RTC clock source LSI oscillator clock (type state)
 pub struct Lsi;
+/// RTC clock source HSE divided to 1 MHz (type state)
+pub struct Hse;
 
 pub trait FrequencySource {
     fn frequency() -> fugit::Hertz<u32>;
@@ -83,6 +86,34 @@ impl FrequencySource for Lsi {
     }
 }
 
+impl FrequencySource for Hse {
+    fn frequency() -> fugit::Hertz<u32> {
+        1u32.MHz()
+    }
+}
+
+/// Calculates `(prediv_s, prediv_a)` which divide `rtc_clk` to 1 Hz calendar clock
+///
+/// Asynchronous prescaler is made as large as possible to save power, giving
+/// `(255, 127)` for LSE, `(249, 127)` for LSI and `(7999, 124)` for HSE.
+///
+/// # Panics
+///
+/// If `rtc_clk` can't be divided to exactly 1 Hz.
+pub const fn calendar_prescalers(rtc_clk: fugit::Hertz<u32>) -> (u16, u8) {
+    let freq = rtc_clk.raw();
+    let mut div_a = 128;
+    while div_a > 1 && freq % div_a != 0 {
+        div_a -= 1;
+    }
+    let div_s = freq / div_a;
+    assert!(
+        freq % div_a == 0 && div_s >= 1 && div_s <= 0x8000,
+        "RTC clock can't be divided to 1 Hz"
+    );
+    ((div_s - 1) as u16, (div_a - 1) as u8)
+}
+
 /// Real Time Clock peripheral
 pub struct Rtc<CS: FrequencySource = Lse> {
     /// RTC Peripheral register
@@ -103,27 +134,25 @@ impl fmt::Debug for Rtc {
     }
 }
 
-/// LSE clock mode.
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum LSEClockMode {
-    /// Enable LSE oscillator to use external crystal or ceramic resonator.
-    Oscillator,
-    /// Bypass LSE oscillator to use external clock source.
-    /// Use this if an external oscillator is used which is not connected to `OSC32_IN` such as a MEMS resonator.
-    Bypass,
-}
+pub use crate::rcc::lsclk::LSEClockMode;
 
 impl Rtc<Lse> {
     /// Create and enable a new RTC with external crystal or ceramic resonator and default prescalers.
+    ///
+    /// Backup domain is reset if another RTC clock source was selected, which also clears
+    /// backup registers.
     pub fn new(regs: RTC, pwr: &mut PWR) -> Self {
-        Self::with_config(regs, pwr, LSEClockMode::Oscillator, 255, 127)
+        let (prediv_s, prediv_a) = calendar_prescalers(Lse::frequency());
+        Self::with_config(regs, pwr, LSEClockMode::Oscillator, prediv_s, prediv_a)
     }
 
     /// Create and enable a new RTC, and configure its clock source and prescalers.
     ///
     /// From AN3371, Table 3, when using the LSE,
     /// set `prediv_s` to 255, and `prediv_a` to 127 to get a calendar clock of 1Hz.
+    ///
+    /// Backup domain is reset if another RTC clock source was selected, which also clears
+    /// backup registers.
     pub fn with_config(
         regs: RTC,
         pwr: &mut PWR,
@@ -131,107 +160,95 @@ impl Rtc<Lse> {
         prediv_s: u16,
         prediv_a: u8,
     ) -> Self {
-        let mut result = Self {
-            regs,
-            _clock_source: PhantomData,
-        };
-
-        // Steps:
-        // Enable PWR and DBP
-        // Enable LSE (if needed)
-        // Enable RTC Clock
-        // Disable Write Protect
-        // Enter Init
-        // Configure 24 hour format
-        // Set prescalers
-        // Exit Init
-        // Enable write protect
-
-        unsafe {
-            let rcc = &(*RCC::ptr());
-            // As per the sample code, unlock comes first. (Enable PWR and DBP)
-            result.unlock(rcc, pwr);
-            // If necessary, enable the LSE.
-            if rcc.bdcr().read().lserdy().bit_is_clear() {
-                result.enable_lse(rcc, mode);
-            }
-            // Set clock source to LSE.
-            rcc.bdcr().modify(|_, w| w.rtcsel().lse());
-            result.enable(rcc);
-        }
-
-        result.modify(true, |regs| {
-            // Set 24 Hour
-            regs.cr().modify(|_, w| w.fmt().clear_bit());
-            // Set prescalers
-            regs.prer().modify(|_, w| {
-                w.prediv_s().set(prediv_s);
-                w.prediv_a().set(prediv_a)
-            })
-        });
-
-        result
-    }
-
-    /// Enable the low frequency external oscillator. This is the only mode currently
-    /// supported, to avoid exposing the `CR` and `CRS` registers.
-    fn enable_lse(&mut self, rcc: &RegisterBlock, mode: LSEClockMode) {
-        unsafe {
-            // Force a reset of the backup domain.
-            self.backup_reset(rcc);
-            // Enable the LSE.
-            // Set BDCR - Bit 0 (LSEON)
-            bb::set(rcc.bdcr(), 0);
-            match mode {
-                // Set BDCR - Bit 2 (LSEBYP)
-                LSEClockMode::Bypass => bb::set(rcc.bdcr(), 2),
-                // Clear BDCR - Bit 2 (LSEBYP)
-                LSEClockMode::Oscillator => bb::clear(rcc.bdcr(), 2),
-            }
-            while rcc.bdcr().read().lserdy().bit_is_clear() {}
-        }
+        Self::init(regs, pwr, RtcClock::Lse, prediv_s, prediv_a, |bd| {
+            lsclk::enable_lse(bd, mode)
+        })
     }
 }
 
 impl Rtc<Lsi> {
     /// Create and enable a new RTC with internal crystal and default prescalers.
+    ///
+    /// Backup domain is reset if another RTC clock source was selected, which also clears
+    /// backup registers.
     pub fn new_lsi(regs: RTC, pwr: &mut PWR) -> Self {
-        Self::lsi_with_config(regs, pwr, 249, 127)
+        let (prediv_s, prediv_a) = calendar_prescalers(Lsi::frequency());
+        Self::lsi_with_config(regs, pwr, prediv_s, prediv_a)
     }
 
     /// Create and enable a new RTC, and configure its clock source and prescalers.
     ///
     /// From AN3371, Table 3, when using the LSI,
     /// set `prediv_s` to 249, and `prediv_a` to 127 to get a calendar clock of 1Hz.
+    ///
+    /// Backup domain is reset if another RTC clock source was selected, which also clears
+    /// backup registers.
     pub fn lsi_with_config(regs: RTC, pwr: &mut PWR, prediv_s: u16, prediv_a: u8) -> Self {
+        Self::init(regs, pwr, RtcClock::Lsi, prediv_s, prediv_a, |_| {
+            lsclk::enable_lsi()
+        })
+    }
+}
+
+impl Rtc<Hse> {
+    /// Create and enable a new RTC clocked from HSE, which must be already running.
+    ///
+    /// HSE is divided by RTCPRE to 1 MHz, prescalers are set by [`calendar_prescalers`].
+    /// HSE is stopped in Stop and Standby modes, so the calendar only counts while the MCU
+    /// runs and is lost when VDD is off.
+    ///
+    /// Backup domain is reset if another RTC clock source was selected, which also clears
+    /// backup registers.
+    ///
+    /// # Panics
+    ///
+    /// If `hse` is not a multiple of 1 MHz from 2 to 31 MHz.
+    pub fn new_hse(regs: RTC, pwr: &mut PWR, hse: fugit::Hertz<u32>) -> Self {
+        let rtcpre = hse.raw() / 1_000_000;
+        assert!(hse.raw() % 1_000_000 == 0 && (2..=31).contains(&rtcpre));
+        let (prediv_s, prediv_a) = calendar_prescalers(Hse::frequency());
+        Self::init(
+            regs,
+            pwr,
+            RtcClock::Hse(rtcpre as u8),
+            prediv_s,
+            prediv_a,
+            |_| {},
+        )
+    }
+}
+
+impl<CS: FrequencySource> Rtc<CS> {
+    // Selects RTC `clock` started by `enable_clock`, enables RTC and sets 24 hour format
+    // and prescalers
+    fn init(
+        regs: RTC,
+        pwr: &mut PWR,
+        clock: RtcClock,
+        prediv_s: u16,
+        prediv_a: u8,
+        enable_clock: impl FnOnce(&mut BackupDomain),
+    ) -> Self {
         let mut result = Self {
             regs,
             _clock_source: PhantomData,
         };
 
-        // Steps:
-        // Enable PWR and DBP
-        // Enable LSI (if needed)
-        // Enable RTC Clock
-        // Disable Write Protect
-        // Enter Init
-        // Configure 24 hour format
-        // Set prescalers
-        // Exit Init
-        // Enable write protect
-
-        unsafe {
-            let rcc = &(*RCC::ptr());
-            // As per the sample code, unlock comes first. (Enable PWR and DBP)
-            result.unlock(rcc, pwr);
-            // If necessary, enable the LSE.
-            if rcc.csr().read().lsirdy().bit_is_clear() {
-                result.enable_lsi(rcc);
-            }
-            // Set clock source to LSI.
-            rcc.bdcr().modify(|_, w| w.rtcsel().lsi());
-            result.enable(rcc);
+        let mut bd = BackupDomain::unchecked(pwr);
+        // RTC clock source can only be changed by reset of the backup domain, HSE prescaler
+        // is outside of it
+        let current = lsclk::rtc_clock();
+        let same_source = match (current, clock) {
+            (RtcClock::Hse(_), RtcClock::Hse(_)) => true,
+            (current, clock) => current == clock,
+        };
+        if current != RtcClock::None && !same_source {
+            bd.reset();
         }
+        // Reset stops LSE, so the clock is started after it
+        enable_clock(&mut bd);
+        lsclk::select_rtc_clock(&mut bd, clock);
+        bd.enable_rtc_clock();
 
         result.modify(true, |regs| {
             // Set 24 Hour
@@ -246,42 +263,6 @@ impl Rtc<Lsi> {
         result
     }
 
-    fn enable_lsi(&mut self, rcc: &RegisterBlock) {
-        // Force a reset of the backup domain.
-        self.backup_reset(rcc);
-        // Enable the LSI.
-        rcc.csr().modify(|_, w| w.lsion().on());
-        while rcc.csr().read().lsirdy().is_not_ready() {}
-    }
-}
-
-impl<CS: FrequencySource> Rtc<CS> {
-    fn unlock(&mut self, rcc: &RegisterBlock, pwr: &mut PWR) {
-        // Enable the backup interface
-        // Set APB1 - Bit 28 (PWREN)
-        PWR::enable(rcc);
-
-        // Enable access to the backup registers
-        pwr.cr().modify(|_, w| w.dbp().set_bit());
-    }
-
-    fn backup_reset(&mut self, rcc: &RegisterBlock) {
-        unsafe {
-            // Set BDCR - Bit 16 (BDRST)
-            bb::set(rcc.bdcr(), 16);
-            // Clear BDCR - Bit 16 (BDRST)
-            bb::clear(rcc.bdcr(), 16);
-        }
-    }
-
-    fn enable(&mut self, rcc: &RegisterBlock) {
-        // Start the actual RTC.
-        // Set BDCR - Bit 15 (RTCEN)
-        unsafe {
-            bb::set(rcc.bdcr(), 15);
-        }
-    }
-
     pub fn set_prescalers(&mut self, prediv_s: u16, prediv_a: u8) {
         self.modify(true, |regs| {
             // Set prescalers
@@ -509,6 +490,16 @@ impl<CS: FrequencySource> Rtc<CS> {
         Ok(())
     }
 
+    /// Waits until calendar shadow registers are updated from the counters
+    ///
+    /// Shadow registers are not updated in Stop and Standby modes, so this must be called
+    /// after wakeup before the calendar is read, as it still holds the time of entering the
+    /// low power mode.
+    pub fn synchronize(&mut self) {
+        self.regs.isr().modify(|_, w| w.rsf().clear_bit());
+        while self.regs.isr().read().rsf().bit_is_clear() {}
+    }
+
     pub fn get_datetime(&mut self) -> PrimitiveDateTime {
         // Wait for Registers synchronization flag,  to ensure consistency between the RTC_SSR, RTC_TR and RTC_DR shadow registers.
         while self.regs.isr().read().rsf().bit_is_clear() {}
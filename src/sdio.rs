@@ -2,7 +2,7 @@
 
 use crate::gpio::alt::sdio as alt;
 use crate::pac::{self, SDIO};
-use crate::rcc::{Clocks, Enable, Reset};
+use crate::rcc::{Clocks, Enable, PeripheralClock, Reset};
 #[allow(unused_imports)]
 use fugit::HertzU32 as Hertz;
 pub use sdio_host::{
@@ -165,9 +165,21 @@ pub struct Emmc {
     pub csd: CSD<EMMC>,
 }
 
+impl PeripheralClock for SDIO {
+    /// SDIOCLK comes from the 48 MHz domain
+    fn kernel_clock(clocks: &Clocks) -> Hertz {
+        clocks.pll48clk().unwrap_or(Hertz::from_raw(0))
+    }
+}
+
 impl<P: SdioPeripheral> Sdio<P> {
     /// Create and enable the Sdio device
+    ///
+    /// # Panics
+    ///
+    /// If SDIOCLK is not an accurate 48 MHz clock.
     pub fn new<PINS: Pins>(sdio: SDIO, pins: PINS, clocks: &Clocks) -> Self {
+        assert!(clocks.is_48mhz_accurate(), "SDIO requires 48 MHz clock");
         unsafe {
             // Enable and reset the sdio peripheral, it's the same bit position for both registers
             SDIO::enable_unchecked();
@@ -13,15 +13,24 @@
 //! In this mode, the `Serial<_, u16>`, `Rx<_, u16>`, and `Tx<_, u16>` structs instead implement
 //! the embedded-hal read and write traits with `u16` as the word type. You can use these
 //! implementations for 9-bit words.
+//!
+//! # Multiprocessor communication
+//!
+//! Receivers of nodes not being addressed can be put into mute mode with `mute()`.
+//! Depending on `Config` `wakeup()` setting, the receiver leaves mute mode when
+//! the line becomes idle or when an address mark with the node address is received.
+//! Address marks are sent with `write_address()`, the most significant data bit marks
+//! the word as an address, so with 9-bit words whole bytes can be sent as data.
 
 use core::marker::PhantomData;
 
 mod hal_02;
 mod hal_1;
+mod io;
 
 pub(crate) mod uart_impls;
-pub use uart_impls::Instance;
 use uart_impls::RegisterBlockImpl;
+pub use uart_impls::{baud_error_ppm, Instance};
 
 use crate::gpio::{self, PushPull};
 
@@ -29,8 +38,23 @@ use crate::pac;
 
 use crate::gpio::NoPin;
 use crate::rcc::Clocks;
+use crate::time::Bps;
 
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod buffered;
+pub use buffered::BufferedSerial;
 pub mod dma;
+pub mod modbus;
+pub use modbus::ModbusRtu;
+pub mod rs485;
+pub use rs485::Rs485;
+pub mod smartcard;
+pub mod stats;
+pub use smartcard::{Smartcard, SmartcardExt};
+pub use stats::ErrorCounts;
+pub mod synchronous;
+pub use synchronous::{SyncSerial, SyncSerialExt};
 
 /// Serial error kind
 ///
@@ -111,6 +135,8 @@ pub enum CFlag {
     TransmissionComplete = 1 << 6,
     /// LIN break detection flag
     LinBreak = 1 << 8,
+    /// CTS flag
+    Cts = 1 << 9,
 }
 
 pub mod config;
@@ -123,6 +149,7 @@ pub use gpio::NoPin as NoTx;
 pub use gpio::NoPin as NoRx;
 
 pub use gpio::alt::SerialAsync as CommonPins;
+pub use gpio::alt::SerialRs232 as FlowControlPins;
 
 /// Trait for [`Rx`] interrupt handling.
 pub trait RxISR {
@@ -176,22 +203,24 @@ pub trait TxListen {
 }
 
 /// Serial abstraction
-pub struct Serial<USART: CommonPins, WORD = u8> {
+pub struct Serial<USART: CommonPins + FlowControlPins, WORD = u8> {
     tx: Tx<USART, WORD>,
     rx: Rx<USART, WORD>,
 }
 
-/// Serial receiver containing RX pin
-pub struct Rx<USART: CommonPins, WORD = u8> {
+/// Serial receiver containing RX pin and RTS pin if hardware flow control is used
+pub struct Rx<USART: CommonPins + FlowControlPins, WORD = u8> {
     _word: PhantomData<(USART, WORD)>,
     pin: USART::Rx<PushPull>,
+    rts: Option<USART::Rts>,
 }
 
-/// Serial transmitter containing TX pin
-pub struct Tx<USART: CommonPins, WORD = u8> {
+/// Serial transmitter containing TX pin and CTS pin if hardware flow control is used
+pub struct Tx<USART: CommonPins + FlowControlPins, WORD = u8> {
     _word: PhantomData<WORD>,
     usart: USART,
     pin: USART::Tx<PushPull>,
+    cts: Option<USART::Cts>,
 }
 
 pub trait SerialExt: Sized + Instance {
@@ -238,14 +267,291 @@ impl<USART: Instance, WORD> Serial<USART, WORD> {
     }
 }
 
-impl<UART: CommonPins, WORD> Serial<UART, WORD> {
+impl<UART: CommonPins + FlowControlPins, WORD> Serial<UART, WORD> {
     pub fn split(self) -> (Tx<UART, WORD>, Rx<UART, WORD>) {
         (self.tx, self.rx)
     }
 
+    /// Returns the peripheral, TX and RX pins and RTS and CTS pins if they were set
     #[allow(clippy::type_complexity)]
-    pub fn release(self) -> (UART, (UART::Tx<PushPull>, UART::Rx<PushPull>)) {
-        (self.tx.usart, (self.tx.pin, self.rx.pin))
+    pub fn release(
+        self,
+    ) -> (
+        UART,
+        (UART::Tx<PushPull>, UART::Rx<PushPull>),
+        (Option<UART::Rts>, Option<UART::Cts>),
+    ) {
+        (
+            self.tx.usart,
+            (self.tx.pin, self.rx.pin),
+            (self.rx.rts, self.tx.cts),
+        )
+    }
+}
+
+impl<UART: Instance + FlowControlPins, WORD> Serial<UART, WORD> {
+    /// Enables RTS hardware flow control using `rts` pin
+    ///
+    /// The pin is switched to alternate function mode and is kept by the receiver, it is
+    /// returned by [`release`](Self::release).
+    pub fn with_rts(mut self, rts: impl Into<UART::Rts>) -> Self {
+        self.rx.rts = Some(rts.into());
+        unsafe { (*UART::ptr()).enable_rts(true) };
+        self
+    }
+
+    /// Enables CTS hardware flow control using `cts` pin
+    ///
+    /// The pin is switched to alternate function mode and is kept by the transmitter, it is
+    /// returned by [`release`](Self::release).
+    pub fn with_cts(mut self, cts: impl Into<UART::Cts>) -> Self {
+        self.tx.cts = Some(cts.into());
+        unsafe { (*UART::ptr()).enable_cts(true) };
+        self
+    }
+
+    /// Enables RTS and CTS hardware flow control using `rts` and `cts` pins
+    pub fn with_rts_cts(self, rts: impl Into<UART::Rts>, cts: impl Into<UART::Cts>) -> Self {
+        self.with_rts(rts).with_cts(cts)
+    }
+
+    /// Start listening for a CTS change interrupt event
+    ///
+    /// Note, you will also have to enable the corresponding interrupt
+    /// in the NVIC to start receiving events.
+    pub fn listen_cts(&mut self) {
+        unsafe { (*UART::ptr()).enable_cts_interrupt(true) }
+    }
+
+    /// Stop listening for the CTS change interrupt event
+    pub fn unlisten_cts(&mut self) {
+        unsafe { (*UART::ptr()).enable_cts_interrupt(false) }
+    }
+
+    /// Return true if CTS input status changed. Cleared with [`CFlag::Cts`]
+    pub fn is_cts_changed(&self) -> bool {
+        unsafe { (*UART::ptr()).flags().contains(Flag::Cts) }
+    }
+}
+
+impl<UART: Instance, WORD> Serial<UART, WORD> {
+    /// Returns baud rate actually achieved with peripheral clock divider
+    ///
+    /// Use [`baud_error_ppm`] to get deviation from requested baud rate.
+    pub fn baudrate(&self, clocks: &Clocks) -> Bps {
+        Bps(unsafe { (*UART::ptr()).baudrate(UART::kernel_clock(clocks).raw()) })
+    }
+
+    /// Changes baud rate, oversampling, word length, parity, stop bits and wakeup method
+    ///
+    /// Waits for transmission complete, then transmitter and receiver are disabled during the change.
+    /// Other settings of `config` are ignored. `WORD` type must still match the word length.
+    pub fn reconfigure(
+        &mut self,
+        config: impl Into<config::Config>,
+        clocks: &Clocks,
+    ) -> Result<(), config::InvalidConfig> {
+        let config = config.into();
+        let usart = unsafe { &*UART::ptr() };
+        let (over8, div) =
+            uart_impls::calculate_config_brr(UART::kernel_clock(clocks).raw(), &config)?;
+        let _ = usart.bflush();
+        usart.set_frame(over8, div, &config);
+        self.tx.usart.set_stopbits(config.stopbits);
+        usart.set_wakeup(config.wakeup);
+        Ok(())
+    }
+}
+
+impl<UART: Instance, WORD> Serial<UART, WORD> {
+    /// Puts receiver into mute mode, see [`Rx::mute`]
+    pub fn mute(&mut self) {
+        self.rx.mute()
+    }
+
+    /// Returns `true` while receiver is in mute mode
+    pub fn is_muted(&self) -> bool {
+        self.rx.is_muted()
+    }
+}
+
+impl<UART: Instance, WORD> Serial<UART, WORD> {
+    /// Counts and clears receive errors, see [`Rx::handle_error_interrupt`]
+    pub fn handle_error_interrupt(&mut self) -> Result<(), Error> {
+        self.rx.handle_error_interrupt()
+    }
+
+    /// Returns error counters and resets them
+    pub fn error_counts(&mut self) -> ErrorCounts {
+        self.rx.error_counts()
+    }
+}
+
+impl<UART: Instance, WORD> Rx<UART, WORD> {
+    /// Start listening for receive error interrupt events
+    ///
+    /// Overrun is also signaled with RXNE interrupt, framing error and noise only in DMA mode.
+    /// Note, you will also have to enable the corresponding interrupt
+    /// in the NVIC to start receiving events.
+    pub fn listen_errors(&mut self) {
+        let usart = unsafe { &*UART::ptr() };
+        usart.enable_error_interrupt_generation();
+        usart.listen_event(None, Some(Event::ParityError.into()));
+    }
+
+    /// Stop listening for receive error interrupt events
+    pub fn unlisten_errors(&mut self) {
+        let usart = unsafe { &*UART::ptr() };
+        usart.disable_error_interrupt_generation();
+        usart.listen_event(Some(Event::ParityError.into()), None);
+    }
+
+    /// Counts receive errors and clears error flags. Call from USART interrupt
+    ///
+    /// Returns the most severe error if any. Note, received word is discarded.
+    pub fn handle_error_interrupt(&mut self) -> Result<(), Error> {
+        let usart = unsafe { &*UART::ptr() };
+        UART::error_stats().record(usart.flags());
+        usart.check_and_clear_error_flags()
+    }
+
+    /// Returns error counters and resets them
+    pub fn error_counts(&mut self) -> ErrorCounts {
+        UART::error_stats().take()
+    }
+}
+
+impl<UART: Instance, WORD> Rx<UART, WORD> {
+    /// Puts receiver into mute mode
+    ///
+    /// Received words are ignored until wakeup condition set with [`Config::wakeup`]
+    /// occurs: idle line or address mark containing own node address.
+    pub fn mute(&mut self) {
+        unsafe { (*UART::ptr()).set_mute(true) }
+    }
+
+    /// Returns `true` while receiver is in mute mode
+    pub fn is_muted(&self) -> bool {
+        unsafe { (*UART::ptr()).is_muted() }
+    }
+}
+
+impl<UART: Instance, WORD> Serial<UART, WORD> {
+    /// Sends break character, see [`Tx::send_break`]
+    pub fn send_break(&mut self) {
+        self.tx.send_break()
+    }
+
+    /// Enables break detection, see [`Rx::enable_break_detection`]
+    pub fn enable_break_detection(&mut self, length: config::BreakLength) {
+        self.rx.enable_break_detection(length)
+    }
+
+    /// Disables break detection
+    pub fn disable_break_detection(&mut self) {
+        self.rx.disable_break_detection()
+    }
+
+    /// Start listening for a break detection interrupt event
+    ///
+    /// Note, you will also have to enable the corresponding interrupt
+    /// in the NVIC to start receiving events.
+    pub fn listen_break(&mut self) {
+        self.rx.listen_break()
+    }
+
+    /// Stop listening for the break detection interrupt event
+    pub fn unlisten_break(&mut self) {
+        self.rx.unlisten_break()
+    }
+
+    /// Return true if break was detected. Cleared with [`CFlag::LinBreak`]
+    pub fn is_break_detected(&self) -> bool {
+        self.rx.is_break_detected()
+    }
+}
+
+impl<UART: Instance, WORD> Tx<UART, WORD> {
+    /// Sends break character (all zeros frame) after current word
+    ///
+    /// The request is cleared by hardware at the stop bit of break character.
+    pub fn send_break(&mut self) {
+        unsafe { (*UART::ptr()).send_break() }
+    }
+}
+
+impl<UART: Instance, WORD> Rx<UART, WORD> {
+    /// Enables break detection of at least `length` bits
+    ///
+    /// This switches USART to LIN mode, so only 1 stop bit is allowed and synchronous,
+    /// Smartcard, IrDA and half-duplex modes must be disabled.
+    pub fn enable_break_detection(&mut self, length: config::BreakLength) {
+        unsafe { (*UART::ptr()).enable_break_detection(Some(length)) }
+    }
+
+    /// Disables break detection
+    pub fn disable_break_detection(&mut self) {
+        unsafe { (*UART::ptr()).enable_break_detection(None) }
+    }
+
+    /// Start listening for a break detection interrupt event
+    ///
+    /// Note, you will also have to enable the corresponding interrupt
+    /// in the NVIC to start receiving events.
+    pub fn listen_break(&mut self) {
+        unsafe { (*UART::ptr()).listen_break(true) }
+    }
+
+    /// Stop listening for the break detection interrupt event
+    pub fn unlisten_break(&mut self) {
+        unsafe { (*UART::ptr()).listen_break(false) }
+    }
+
+    /// Return true if break was detected. Cleared with [`CFlag::LinBreak`]
+    pub fn is_break_detected(&self) -> bool {
+        unsafe { (*UART::ptr()).flags().contains(Flag::LinBreak) }
+    }
+}
+
+impl<UART: Instance> Serial<UART, u8> {
+    /// Sends address mark for node `address`, see [`Tx::write_address`]
+    pub fn write_address(&mut self, address: u8) -> nb::Result<(), Error> {
+        self.tx.write_address(address)
+    }
+}
+
+impl<UART: Instance> Serial<UART, u16> {
+    /// Sends address mark for node `address`, see [`Tx::write_address`]
+    pub fn write_address(&mut self, address: u8) -> nb::Result<(), Error> {
+        self.tx.write_address(address)
+    }
+}
+
+impl<UART: Instance> Tx<UART, u8> {
+    /// Sends address mark (word with bit 7 set) for node `address` in multiprocessor mode
+    ///
+    /// Parity must be disabled, otherwise bit 7 is the parity bit.
+    ///
+    /// # Panics
+    ///
+    /// If `address` is above 0x0f, receivers compare only 4 bits of the address.
+    pub fn write_address(&mut self, address: u8) -> nb::Result<(), Error> {
+        assert!(address <= 0x0f, "node address out of range");
+        unsafe { (*UART::ptr()).write_u8(0x80 | address) }
+    }
+}
+
+impl<UART: Instance> Tx<UART, u16> {
+    /// Sends address mark (word with bit 8 set) for node `address` in multiprocessor mode
+    ///
+    /// Parity must be disabled, otherwise bit 8 is the parity bit.
+    ///
+    /// # Panics
+    ///
+    /// If `address` is above 0x0f, receivers compare only 4 bits of the address.
+    pub fn write_address(&mut self, address: u8) -> nb::Result<(), Error> {
+        assert!(address <= 0x0f, "node address out of range");
+        unsafe { (*UART::ptr()).write_u16(0x100 | u16::from(address)) }
     }
 }
 
@@ -255,6 +561,12 @@ macro_rules! halUsart {
         pub type $Tx<WORD = u8> = Tx<$USART, WORD>;
         pub type $Rx<WORD = u8> = Rx<$USART, WORD>;
 
+        impl crate::rcc::PeripheralClock for $USART {
+            fn kernel_clock(clocks: &crate::rcc::Clocks) -> crate::time::Hertz {
+                <$USART as crate::rcc::BusClock>::clock(clocks)
+            }
+        }
+
         impl Instance for $USART {
             type RegisterBlock = crate::serial::uart_impls::RegisterBlockUsart;
 
@@ -266,7 +578,7 @@ macro_rules! halUsart {
                 use crate::pac::usart1::cr2::STOP;
                 use config::StopBits;
 
-                self.cr2().write(|w| {
+                self.cr2().modify(|_, w| {
                     w.stop().variant(match bits {
                         StopBits::STOP0P5 => STOP::Stop0p5,
                         StopBits::STOP1 => STOP::Stop1,
@@ -279,6 +591,18 @@ macro_rules! halUsart {
             fn peri_address() -> u32 {
                 unsafe { (*(<$USART>::ptr() as *const Self::RegisterBlock)).peri_address() }
             }
+
+            fn error_stats() -> &'static crate::serial::stats::ErrorStats {
+                static STATS: crate::serial::stats::ErrorStats =
+                    crate::serial::stats::ErrorStats::new();
+                &STATS
+            }
+
+            #[cfg(feature = "async")]
+            fn wakers() -> &'static crate::serial::asynch::Wakers {
+                static WAKERS: crate::serial::asynch::Wakers = crate::serial::asynch::Wakers::new();
+                &WAKERS
+            }
         }
     };
 }
@@ -291,35 +615,54 @@ halUsart! { pac::USART6, Serial6, Rx6, Tx6 }
 #[cfg(feature = "usart3")]
 halUsart! { pac::USART3, Serial3, Rx3, Tx3 }
 
-impl<UART: CommonPins> Rx<UART, u8> {
+impl<UART: CommonPins + FlowControlPins> Rx<UART, u8> {
     pub(crate) fn with_u16_data(self) -> Rx<UART, u16> {
-        Rx::new(self.pin)
+        Rx {
+            _word: PhantomData,
+            pin: self.pin,
+            rts: self.rts,
+        }
     }
 }
 
-impl<UART: CommonPins> Rx<UART, u16> {
+impl<UART: CommonPins + FlowControlPins> Rx<UART, u16> {
     pub(crate) fn with_u8_data(self) -> Rx<UART, u8> {
-        Rx::new(self.pin)
+        Rx {
+            _word: PhantomData,
+            pin: self.pin,
+            rts: self.rts,
+        }
     }
 }
 
-impl<UART: CommonPins> Tx<UART, u8> {
+impl<UART: CommonPins + FlowControlPins> Tx<UART, u8> {
     pub(crate) fn with_u16_data(self) -> Tx<UART, u16> {
-        Tx::new(self.usart, self.pin)
+        Tx {
+            _word: PhantomData,
+            usart: self.usart,
+            pin: self.pin,
+            cts: self.cts,
+        }
     }
 }
 
-impl<UART: CommonPins> Tx<UART, u16> {
+impl<UART: CommonPins + FlowControlPins> Tx<UART, u16> {
     pub(crate) fn with_u8_data(self) -> Tx<UART, u8> {
-        Tx::new(self.usart, self.pin)
+        Tx {
+            _word: PhantomData,
+            usart: self.usart,
+            pin: self.pin,
+            cts: self.cts,
+        }
     }
 }
 
-impl<UART: CommonPins, WORD> Rx<UART, WORD> {
+impl<UART: CommonPins + FlowControlPins, WORD> Rx<UART, WORD> {
     pub(crate) fn new(pin: UART::Rx<PushPull>) -> Self {
         Self {
             _word: PhantomData,
             pin,
+            rts: None,
         }
     }
 
@@ -328,12 +671,13 @@ impl<UART: CommonPins, WORD> Rx<UART, WORD> {
     }
 }
 
-impl<UART: CommonPins, WORD> Tx<UART, WORD> {
+impl<UART: CommonPins + FlowControlPins, WORD> Tx<UART, WORD> {
     pub(crate) fn new(usart: UART, pin: UART::Tx<PushPull>) -> Self {
         Self {
             _word: PhantomData,
             usart,
             pin,
+            cts: None,
         }
     }
 
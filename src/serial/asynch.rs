@@ -0,0 +1,157 @@
+//! Interrupt driven async serial
+//!
+//! [`Rx`] and [`Tx`] with `u8` words implement [`embedded_io_async::Read`] and
+//! [`embedded_io_async::Write`]. Futures enable RXNE, TXE or TC interrupts while waiting,
+//! so [`on_interrupt`] must be called from the USART interrupt handler, and the interrupt
+//! must be unmasked in the NVIC.
+//!
+//! The handler disables these interrupts after waking the task, so they can't be used
+//! for other purposes at the same time.
+
+use core::future::poll_fn;
+use core::task::Poll;
+
+use super::{Error, Event, Flag, Instance, RegisterBlockImpl, Rx, Serial, Tx};
+use crate::waker::WakerRegistration;
+
+/// Receiver and transmitter wakers of USART instance
+#[doc(hidden)]
+pub struct Wakers {
+    rx: WakerRegistration,
+    tx: WakerRegistration,
+}
+
+impl Wakers {
+    pub const fn new() -> Self {
+        Self {
+            rx: WakerRegistration::new(),
+            tx: WakerRegistration::new(),
+        }
+    }
+}
+
+/// Wakes tasks waiting on `USART`, call from USART interrupt handler
+pub fn on_interrupt<USART: Instance>() {
+    let usart = unsafe { &*USART::ptr() };
+    let flags = usart.flags();
+    let wakers = USART::wakers();
+    if flags.intersects(
+        Flag::RxNotEmpty | Flag::Overrun | Flag::Noise | Flag::FramingError | Flag::ParityError,
+    ) {
+        usart.unlisten_rxne();
+        wakers.rx.wake();
+    }
+    if flags.intersects(Flag::TxEmpty | Flag::TransmissionComplete) {
+        usart.listen_event(Some(Event::TxEmpty | Event::TransmissionComplete), None);
+        wakers.tx.wake();
+    }
+}
+
+impl<USART: Instance> Rx<USART, u8> {
+    async fn read_async(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let Some((first, rest)) = buf.split_first_mut() else {
+            return Ok(0);
+        };
+        let usart = unsafe { &*USART::ptr() };
+        *first = poll_fn(|cx| {
+            USART::wakers().rx.register(cx.waker());
+            match usart.read_u8() {
+                Ok(byte) => Poll::Ready(Ok(byte)),
+                Err(nb::Error::WouldBlock) => {
+                    usart.listen_rxne();
+                    Poll::Pending
+                }
+                Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+            }
+        })
+        .await?;
+        let mut count = 1;
+        for b in rest {
+            match usart.read_u8() {
+                Ok(byte) => *b = byte,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+impl<USART: Instance> Tx<USART, u8> {
+    async fn write_async(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let Some((&first, rest)) = buf.split_first() else {
+            return Ok(0);
+        };
+        let usart = unsafe { &*USART::ptr() };
+        poll_fn(|cx| {
+            USART::wakers().tx.register(cx.waker());
+            match usart.write_u8(first) {
+                Ok(()) => Poll::Ready(Ok(())),
+                Err(nb::Error::WouldBlock) => {
+                    usart.listen_txe();
+                    Poll::Pending
+                }
+                Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+            }
+        })
+        .await?;
+        let mut count = 1;
+        for &b in rest {
+            match usart.write_u8(b) {
+                Ok(()) => count += 1,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+        }
+        Ok(count)
+    }
+
+    async fn flush_async(&mut self) -> Result<(), Error> {
+        let usart = unsafe { &*USART::ptr() };
+        poll_fn(|cx| {
+            USART::wakers().tx.register(cx.waker());
+            match usart.flush() {
+                Ok(()) => Poll::Ready(Ok(())),
+                Err(nb::Error::WouldBlock) => {
+                    usart.listen_event(None, Some(Event::TransmissionComplete.into()));
+                    Poll::Pending
+                }
+                Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+            }
+        })
+        .await
+    }
+}
+
+impl<USART: Instance> embedded_io_async::Read for Rx<USART, u8> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.read_async(buf).await
+    }
+}
+
+impl<USART: Instance> embedded_io_async::Write for Tx<USART, u8> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.write_async(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.flush_async().await
+    }
+}
+
+impl<USART: Instance> embedded_io_async::Read for Serial<USART, u8> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.rx.read_async(buf).await
+    }
+}
+
+impl<USART: Instance> embedded_io_async::Write for Serial<USART, u8> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.tx.write_async(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.tx.flush_async().await
+    }
+}
@@ -0,0 +1,168 @@
+//! Interrupt driven serial with receive and transmit ring buffers
+//!
+//! [`BufferedSerial`] is usually placed in a `static` (e.g. inside `Mutex` or as RTIC shared
+//! resource) as it is accessed both from application and from USART interrupt handler which
+//! must call [`BufferedSerial::handle_interrupt`].
+
+use super::{Error, Instance, RegisterBlockImpl, Serial};
+
+struct RingBuffer<const N: usize> {
+    buf: [u8; N],
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> RingBuffer<N> {
+    const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> bool {
+        if self.len == N {
+            return false;
+        }
+        self.buf[(self.head + self.len) % N] = byte;
+        self.len += 1;
+        true
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let byte = self.buf[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(byte)
+    }
+
+    fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+/// Serial with `N` bytes receive and transmit buffers serviced from USART interrupt
+pub struct BufferedSerial<USART: Instance, const N: usize> {
+    serial: Serial<USART, u8>,
+    rx: RingBuffer<N>,
+    tx: RingBuffer<N>,
+    rx_overflows: u32,
+    last_error: Option<Error>,
+}
+
+impl<USART: Instance> Serial<USART, u8> {
+    /// Converts serial to interrupt driven one with `N` bytes ring buffers
+    ///
+    /// RXNE interrupt is enabled, the USART interrupt must be unmasked in the NVIC
+    /// and call [`BufferedSerial::handle_interrupt`].
+    pub fn buffered<const N: usize>(self) -> BufferedSerial<USART, N> {
+        unsafe { (*USART::ptr()).listen_rxne() };
+        BufferedSerial {
+            serial: self,
+            rx: RingBuffer::new(),
+            tx: RingBuffer::new(),
+            rx_overflows: 0,
+            last_error: None,
+        }
+    }
+}
+
+impl<USART: Instance, const N: usize> BufferedSerial<USART, N> {
+    /// Moves received byte to receive buffer and next byte from transmit buffer
+    /// to USART. Call from USART interrupt handler.
+    pub fn handle_interrupt(&mut self) {
+        let usart = unsafe { &*USART::ptr() };
+        match usart.read_u8() {
+            Ok(byte) => {
+                if !self.rx.push(byte) {
+                    self.rx_overflows = self.rx_overflows.wrapping_add(1);
+                }
+            }
+            Err(nb::Error::Other(e)) => {
+                if e == Error::Overrun {
+                    self.rx_overflows = self.rx_overflows.wrapping_add(1);
+                }
+                self.last_error = Some(e);
+            }
+            Err(nb::Error::WouldBlock) => {}
+        }
+        if usart.is_tx_empty() {
+            match self.tx.pop() {
+                Some(byte) => {
+                    let _ = usart.write_u8(byte);
+                }
+                None => usart.unlisten_txe(),
+            }
+        }
+    }
+
+    /// Moves received bytes to `buf`, returns number of bytes read
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let mut count = 0;
+        for b in buf {
+            match self.rx.pop() {
+                Some(byte) => *b = byte,
+                None => break,
+            }
+            count += 1;
+        }
+        count
+    }
+
+    /// Puts `bytes` to transmit buffer, returns number of bytes accepted
+    pub fn write(&mut self, bytes: &[u8]) -> usize {
+        let count = bytes.iter().take_while(|&&b| self.tx.push(b)).count();
+        if count != 0 {
+            unsafe { (*USART::ptr()).listen_txe() };
+        }
+        count
+    }
+
+    /// Number of received bytes in buffer
+    pub fn len(&self) -> usize {
+        self.rx.len
+    }
+
+    /// Returns `true` if no received bytes are available
+    pub fn is_empty(&self) -> bool {
+        self.rx.len == 0
+    }
+
+    /// Number of bytes waiting for transmission
+    pub fn tx_len(&self) -> usize {
+        self.tx.len
+    }
+
+    /// Free space in transmit buffer
+    pub fn tx_free(&self) -> usize {
+        N - self.tx.len
+    }
+
+    /// Number of received bytes lost because of full buffer or hardware overrun
+    pub fn rx_overflows(&self) -> u32 {
+        self.rx_overflows
+    }
+
+    /// Returns and clears last receive error
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.last_error.take()
+    }
+
+    /// Drops buffered received bytes
+    pub fn clear_rx(&mut self) {
+        self.rx.clear();
+    }
+
+    /// Disables interrupts and returns serial. Unsent bytes are dropped
+    pub fn release(self) -> Serial<USART, u8> {
+        let usart = unsafe { &*USART::ptr() };
+        usart.unlisten_rxne();
+        usart.unlisten_txe();
+        self.serial
+    }
+}
@@ -1,5 +1,7 @@
+use crate::spi::{Mode, Phase, Polarity};
 use crate::time::Bps;
 use crate::time::U32Ext;
+use fugit::{HertzU32 as Hertz, MicrosDurationU32, RateExtU32};
 
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,6 +62,62 @@ pub enum IrdaMode {
     LowPower,
 }
 
+/// Receiver oversampling
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Oversampling {
+    /// Oversampling by 16 if baud rate allows, otherwise by 8
+    Auto,
+    /// Oversampling by 16, better clock deviation tolerance
+    By16,
+    /// Oversampling by 8, higher maximal baud rate and finer baud rate resolution
+    By8,
+}
+
+/// Hardware flow control (RS232)
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlowControl {
+    #[doc = "No hardware flow control"]
+    None,
+    #[doc = "RTS output is asserted while receiver can accept data"]
+    Rts,
+    #[doc = "Transmitter waits for CTS input to be asserted"]
+    Cts,
+    #[doc = "Both RTS and CTS flow control"]
+    RtsCts,
+}
+
+impl FlowControl {
+    pub(crate) fn rts(self) -> bool {
+        matches!(self, Self::Rts | Self::RtsCts)
+    }
+
+    pub(crate) fn cts(self) -> bool {
+        matches!(self, Self::Cts | Self::RtsCts)
+    }
+}
+
+/// Minimal length of detected break
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakLength {
+    /// 10 bit break detection
+    Bits10,
+    /// 11 bit break detection
+    Bits11,
+}
+
+/// Multiprocessor communication wakeup method of muted receiver
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wakeup {
+    /// Receiver wakes up when idle line is detected
+    IdleLine,
+    /// Receiver wakes up on address mark (word with MSB set) with node address in 4 LSBs
+    AddressMark(u8),
+}
+
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Config {
@@ -69,6 +127,14 @@ pub struct Config {
     pub stopbits: StopBits,
     pub dma: DmaConfig,
     pub irda: IrdaMode,
+    /// Prescaler of IrDA low-power frequency. Calculated from peripheral clock if `None`
+    pub irda_prescaler: Option<u8>,
+    pub flow_control: FlowControl,
+    /// Multiprocessor mode wakeup method
+    pub wakeup: Wakeup,
+    pub oversampling: Oversampling,
+    /// Maximal allowed deviation of achieved baud rate in ppm
+    pub max_baud_error: Option<u32>,
 }
 
 impl Config {
@@ -116,6 +182,182 @@ impl Config {
         self.irda = irda;
         self
     }
+
+    /// Sets IrDA low-power mode prescaler. `pclk / prescaler` should be about 1.8432 MHz
+    pub fn irda_prescaler(mut self, prescaler: u8) -> Self {
+        self.irda_prescaler = Some(prescaler);
+        self
+    }
+
+    pub fn flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    pub fn wakeup(mut self, wakeup: Wakeup) -> Self {
+        self.wakeup = wakeup;
+        self
+    }
+
+    pub fn oversampling(mut self, oversampling: Oversampling) -> Self {
+        self.oversampling = oversampling;
+        self
+    }
+
+    /// Fail if achieved baud rate differs from requested one by more than `ppm`
+    pub fn max_baud_error_ppm(mut self, ppm: u32) -> Self {
+        self.max_baud_error = Some(ppm);
+        self
+    }
+}
+
+/// RS-485 driver enable (DE) timing
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rs485Config {
+    /// Delay between DE assertion and start of transmission
+    pub assertion_time: MicrosDurationU32,
+    /// Delay between transmission complete and DE deassertion
+    ///
+    /// In DMA mode it is busy waited in the USART interrupt handler.
+    pub deassertion_time: MicrosDurationU32,
+}
+
+impl Rs485Config {
+    pub fn assertion_time(mut self, time: MicrosDurationU32) -> Self {
+        self.assertion_time = time;
+        self
+    }
+
+    pub fn deassertion_time(mut self, time: MicrosDurationU32) -> Self {
+        self.deassertion_time = time;
+        self
+    }
+}
+
+impl Default for Rs485Config {
+    fn default() -> Self {
+        Self {
+            assertion_time: MicrosDurationU32::from_ticks(0),
+            deassertion_time: MicrosDurationU32::from_ticks(0),
+        }
+    }
+}
+
+/// Smartcard (ISO 7816-3) mode configuration
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmartcardConfig {
+    /// Card clock frequency on CK pin
+    pub clock: Hertz,
+    /// Baud rate. `None` means default ISO 7816 rate: card clock / 372
+    pub baudrate: Option<Bps>,
+    /// Guard time in baud clock periods
+    pub guard_time: u8,
+    /// Send NACK on parity error
+    pub nack: bool,
+    pub parity: Parity,
+}
+
+impl SmartcardConfig {
+    pub fn clock(mut self, clock: Hertz) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn baudrate(mut self, baudrate: Bps) -> Self {
+        self.baudrate = Some(baudrate);
+        self
+    }
+
+    pub fn guard_time(mut self, guard_time: u8) -> Self {
+        self.guard_time = guard_time;
+        self
+    }
+
+    pub fn nack(mut self, nack: bool) -> Self {
+        self.nack = nack;
+        self
+    }
+
+    pub fn parity_even(mut self) -> Self {
+        self.parity = Parity::ParityEven;
+        self
+    }
+
+    pub fn parity_odd(mut self) -> Self {
+        self.parity = Parity::ParityOdd;
+        self
+    }
+}
+
+impl Default for SmartcardConfig {
+    fn default() -> Self {
+        Self {
+            clock: 3_571_200.Hz(),
+            baudrate: None,
+            guard_time: 2,
+            nack: true,
+            parity: Parity::ParityEven,
+        }
+    }
+}
+
+/// Synchronous (clocked) mode configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncConfig {
+    pub baudrate: Bps,
+    /// Clock polarity and phase
+    pub mode: Mode,
+    /// Output clock pulse for the last data bit
+    pub last_bit_clock: bool,
+    /// Transfer most significant bit first (bits are swapped in software)
+    pub msb_first: bool,
+}
+
+impl SyncConfig {
+    pub fn baudrate(mut self, baudrate: Bps) -> Self {
+        self.baudrate = baudrate;
+        self
+    }
+
+    pub fn mode(mut self, mode: impl Into<Mode>) -> Self {
+        self.mode = mode.into();
+        self
+    }
+
+    pub fn last_bit_clock(mut self, last_bit_clock: bool) -> Self {
+        self.last_bit_clock = last_bit_clock;
+        self
+    }
+
+    pub fn msb_first(mut self, msb_first: bool) -> Self {
+        self.msb_first = msb_first;
+        self
+    }
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            baudrate: 1_000_000_u32.bps(),
+            mode: Mode {
+                polarity: Polarity::IdleLow,
+                phase: Phase::CaptureOnFirstTransition,
+            },
+            last_bit_clock: true,
+            msb_first: false,
+        }
+    }
+}
+
+impl<T: Into<Bps>> From<T> for SyncConfig {
+    fn from(b: T) -> SyncConfig {
+        SyncConfig {
+            baudrate: b.into(),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -131,6 +373,11 @@ impl Default for Config {
             stopbits: StopBits::STOP1,
             dma: DmaConfig::None,
             irda: IrdaMode::None,
+            irda_prescaler: None,
+            flow_control: FlowControl::None,
+            wakeup: Wakeup::IdleLine,
+            oversampling: Oversampling::Auto,
+            max_baud_error: None,
         }
     }
 }
@@ -4,9 +4,10 @@ use super::{Instance, RegisterBlockImpl, Serial};
 use crate::dma::{
     config::DmaConfig,
     traits::{Channel, DMASet, DmaFlagExt, PeriAddress, Stream, StreamISR},
-    ChannelX, MemoryToPeripheral, PeripheralToMemory, Transfer,
+    ChannelX, DMAError, MemoryToPeripheral, PeripheralToMemory, Transfer,
 };
 use crate::ReadFlags;
+use embedded_dma::{ReadBuffer, WriteBuffer};
 
 use nb;
 
@@ -23,6 +24,9 @@ pub struct NoDMA;
 /// Callback type to notify user code of completion serial transfers
 pub type SerialCompleteCallback = fn(Result<(), Error>);
 
+/// Callback type to pass filled receive buffer to user code
+pub type SerialRxBufferCallback = fn(&[u8]);
+
 pub trait SerialWriteDMA {
     /// Writes `bytes` to the serial interface in non-blocking mode
     ///
@@ -120,6 +124,73 @@ where
         }
     }
 
+    /// Converts blocking [Serial] to non-blocking [SerialTxQueue] that use `tx_stream` to send
+    /// up to `N` queued `&'static [u8]` buffers one after another
+    pub fn use_dma_tx_queue<TX_STREAM, const TX_CH: u8, const N: usize>(
+        self,
+        tx_stream: TX_STREAM,
+    ) -> SerialTxQueue<Serial_, TX_STREAM, TX_CH, N>
+    where
+        TX_STREAM: Stream,
+        ChannelX<TX_CH>: Channel,
+        Tx<Serial_>: DMASet<TX_STREAM, TX_CH, MemoryToPeripheral>,
+    {
+        self.use_dma_tx_buffer_queue(tx_stream)
+    }
+
+    /// Converts blocking [Serial] to non-blocking [SerialTxQueue] that use `tx_stream` to send
+    /// up to `N` queued owned buffers of type `BUF` one after another
+    pub fn use_dma_tx_buffer_queue<TX_STREAM, const TX_CH: u8, const N: usize, BUF>(
+        self,
+        tx_stream: TX_STREAM,
+    ) -> SerialTxQueue<Serial_, TX_STREAM, TX_CH, N, BUF>
+    where
+        TX_STREAM: Stream,
+        ChannelX<TX_CH>: Channel,
+        Tx<Serial_>: DMASet<TX_STREAM, TX_CH, MemoryToPeripheral>,
+        BUF: ReadBuffer<Word = u8>,
+    {
+        SerialTxQueue {
+            hal_serial: self,
+            tx: TxDMA::new(tx_stream),
+            queue: [(); N].map(|_| None),
+            head: 0,
+            len: 0,
+            error: None,
+        }
+    }
+
+    /// Converts blocking [Serial] to [FrameReader] that receives idle line delimited frames
+    /// to `buffers` using `rx_stream`
+    pub fn use_dma_frame_reader<RX_STREAM, const RX_CH: u8, const N: usize>(
+        self,
+        rx_stream: RX_STREAM,
+        buffers: [&'static mut [u8; N]; 2],
+    ) -> FrameReader<Serial_, RX_STREAM, RX_CH, N>
+    where
+        RX_STREAM: Stream,
+        ChannelX<RX_CH>: Channel,
+        Rx<Serial_>: DMASet<RX_STREAM, RX_CH, PeripheralToMemory>,
+    {
+        FrameReader::new(self, rx_stream, buffers)
+    }
+
+    /// Converts blocking [Serial] to [SerialRxDoubleBuffer] that continuously receives to
+    /// `buffers` using `rx_stream` in double buffer mode
+    pub fn use_dma_rx_double_buffer<RX_STREAM, const RX_CH: u8, const N: usize>(
+        self,
+        rx_stream: RX_STREAM,
+        buffers: [&'static mut [u8; N]; 3],
+        callback: SerialRxBufferCallback,
+    ) -> SerialRxDoubleBuffer<Serial_, RX_STREAM, RX_CH, N>
+    where
+        RX_STREAM: Stream,
+        ChannelX<RX_CH>: Channel,
+        Rx<Serial_>: DMASet<RX_STREAM, RX_CH, PeripheralToMemory>,
+    {
+        SerialRxDoubleBuffer::new(self, rx_stream, buffers, callback)
+    }
+
     /// Converts blocking [Serial] to non-blocking [SerialDma] that use `rx_stream` to only receive data
     pub fn use_dma_rx<RX_STREAM, const RX_CH: u8>(
         self,
@@ -201,17 +272,17 @@ impl DMATransfer<&'static mut [u8]> for NoDMA {
 }
 
 /// DMA Transfer holder for Tx operations
-pub struct TxDMA<Serial_, TX_STREAM, const TX_CH: u8>
+pub struct TxDMA<Serial_, TX_STREAM, const TX_CH: u8, BUF = &'static [u8]>
 where
     Serial_: Instance,
     TX_STREAM: Stream,
 {
     tx: Option<Tx<Serial_>>,
     tx_stream: Option<TX_STREAM>,
-    tx_transfer: Option<Transfer<TX_STREAM, TX_CH, Tx<Serial_>, MemoryToPeripheral, &'static [u8]>>,
+    tx_transfer: Option<Transfer<TX_STREAM, TX_CH, Tx<Serial_>, MemoryToPeripheral, BUF>>,
 }
 
-impl<Serial_, TX_STREAM, const TX_CH: u8> TxDMA<Serial_, TX_STREAM, TX_CH>
+impl<Serial_, TX_STREAM, const TX_CH: u8, BUF> TxDMA<Serial_, TX_STREAM, TX_CH, BUF>
 where
     Serial_: Instance,
     TX_STREAM: Stream,
@@ -229,15 +300,16 @@ where
     }
 }
 
-impl<Serial_, TX_STREAM, const TX_CH: u8> DMATransfer<&'static [u8]>
-    for TxDMA<Serial_, TX_STREAM, TX_CH>
+impl<Serial_, TX_STREAM, const TX_CH: u8, BUF> DMATransfer<BUF>
+    for TxDMA<Serial_, TX_STREAM, TX_CH, BUF>
 where
     Serial_: Instance,
     TX_STREAM: Stream,
     ChannelX<TX_CH>: Channel,
     Tx<Serial_>: DMASet<TX_STREAM, TX_CH, MemoryToPeripheral>,
+    BUF: ReadBuffer<Word = u8>,
 {
-    fn create_transfer(&mut self, buf: &'static [u8]) {
+    fn create_transfer(&mut self, buf: BUF) {
         assert!(self.tx.is_some());
         assert!(self.tx_stream.is_some());
 
@@ -269,18 +341,17 @@ where
 }
 
 /// DMA Transfer holder for Rx operations
-pub struct RxDMA<Serial_, RX_STREAM, const RX_CH: u8>
+pub struct RxDMA<Serial_, RX_STREAM, const RX_CH: u8, BUF = &'static mut [u8]>
 where
     Serial_: Instance,
     RX_STREAM: Stream,
 {
     rx: Option<Rx<Serial_>>,
     rx_stream: Option<RX_STREAM>,
-    rx_transfer:
-        Option<Transfer<RX_STREAM, RX_CH, Rx<Serial_>, PeripheralToMemory, &'static mut [u8]>>,
+    rx_transfer: Option<Transfer<RX_STREAM, RX_CH, Rx<Serial_>, PeripheralToMemory, BUF>>,
 }
 
-impl<Serial_, RX_STREAM, const RX_CH: u8> RxDMA<Serial_, RX_STREAM, RX_CH>
+impl<Serial_, RX_STREAM, const RX_CH: u8, BUF> RxDMA<Serial_, RX_STREAM, RX_CH, BUF>
 where
     Serial_: Instance,
     RX_STREAM: Stream,
@@ -298,15 +369,16 @@ where
     }
 }
 
-impl<Serial_, RX_STREAM, const RX_CH: u8> DMATransfer<&'static mut [u8]>
-    for RxDMA<Serial_, RX_STREAM, RX_CH>
+impl<Serial_, RX_STREAM, const RX_CH: u8, BUF> DMATransfer<BUF>
+    for RxDMA<Serial_, RX_STREAM, RX_CH, BUF>
 where
     Serial_: Instance,
     RX_STREAM: Stream,
     ChannelX<RX_CH>: Channel,
     Rx<Serial_>: DMASet<RX_STREAM, RX_CH, PeripheralToMemory>,
+    BUF: WriteBuffer<Word = u8>,
 {
-    fn create_transfer(&mut self, buf: &'static mut [u8]) {
+    fn create_transfer(&mut self, buf: BUF) {
         assert!(self.rx.is_some());
         assert!(self.rx_stream.is_some());
 
@@ -611,6 +683,503 @@ where
     }
 }
 
+/// Serial transmitter that queues buffers and sends them one after another using DMA
+///
+/// [`write_all`](Self::write_all) returns immediately. Queued buffers are chained
+/// in [`handle_dma_interrupt`](SerialHandleIT::handle_dma_interrupt) on transfer complete.
+/// A client must follow these requirements to use that feature:
+/// * Configure serial with [`DmaConfig::Tx`](super::config::DmaConfig::Tx) or [`DmaConfig::TxRx`](super::config::DmaConfig::TxRx).
+/// * Enable interrupt DMAx_STREAMy used for transmit and call [`handle_dma_interrupt`](SerialHandleIT::handle_dma_interrupt) in it.
+/// * Enable interrupt USARTx or UARTx for handling errors and call [`handle_error_interrupt`](SerialHandleIT::handle_error_interrupt) in it.
+///
+/// Buffers can be any [`ReadBuffer`] (like `&'static [u8]` or a pool box), each one is
+/// dropped when it is sent or removed from the queue.
+pub struct SerialTxQueue<Serial_, TX_STREAM, const TX_CH: u8, const N: usize, BUF = &'static [u8]>
+where
+    Serial_: Instance,
+    TX_STREAM: Stream,
+{
+    hal_serial: Serial<Serial_>,
+    tx: TxDMA<Serial_, TX_STREAM, TX_CH, BUF>,
+    queue: [Option<BUF>; N],
+    head: usize,
+    len: usize,
+    error: Option<Error>,
+}
+
+impl<Serial_, TX_STREAM, const TX_CH: u8, const N: usize, BUF>
+    SerialTxQueue<Serial_, TX_STREAM, TX_CH, N, BUF>
+where
+    Serial_: Instance,
+    Serial_: Deref<Target = <Serial_ as Instance>::RegisterBlock>,
+    <Serial_ as Instance>::RegisterBlock: RegisterBlockImpl,
+
+    TX_STREAM: Stream,
+    ChannelX<TX_CH>: Channel,
+    Tx<Serial_>: DMASet<TX_STREAM, TX_CH, MemoryToPeripheral>,
+    BUF: ReadBuffer<Word = u8>,
+{
+    /// Enqueues `bytes` for transmission and returns immediately
+    ///
+    /// Transmission starts at once if the DMA stream is idle.
+    /// Returns `WouldBlock` if `N` buffers are already waiting in the queue.
+    /// An error of a previous transfer is reported (and cleared) here.
+    pub fn write_all(&mut self, bytes: BUF) -> nb::Result<(), Error> {
+        if let Some(e) = self.error.take() {
+            return Err(nb::Error::Other(e));
+        }
+        // NOTE(unsafe) Only the length is used
+        if unsafe { bytes.read_buffer() }.1 == 0 {
+            return Ok(());
+        }
+
+        if !self.tx.created() {
+            self.hal_serial.tx.usart.enable_error_interrupt_generation();
+            self.tx.create_transfer(bytes);
+            self.tx.tx_transfer.as_mut().unwrap().start(|_| {});
+            return Ok(());
+        }
+
+        if self.len == N {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.queue[(self.head + self.len) % N] = Some(bytes);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns `true` while a buffer is being sent or waiting in the queue
+    pub fn in_progress(&self) -> bool {
+        self.tx.created()
+    }
+
+    /// Number of buffers waiting in the queue, excluding the one being sent
+    pub fn queued(&self) -> usize {
+        self.len
+    }
+
+    /// Waits until all queued buffers are sent and the last byte has left the shift register
+    pub fn flush(&mut self) -> nb::Result<(), Error> {
+        if let Some(e) = self.error.take() {
+            return Err(nb::Error::Other(e));
+        }
+        if self.in_progress() {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.hal_serial
+            .tx
+            .usart
+            .flush()
+            .map_err(|e| e.map(Error::SerialError))
+    }
+
+    /// Drops all buffers waiting in the queue. The buffer being sent is not affected
+    pub fn clear_queue(&mut self) {
+        while self.pop().is_some() {}
+        self.head = 0;
+    }
+
+    /// Stops transmission, drops the queue and returns the serial and DMA stream
+    pub fn release(mut self) -> (Serial<Serial_>, TX_STREAM) {
+        self.finish_transfer();
+        self.clear_queue();
+        (self.hal_serial, self.tx.tx_stream.take().unwrap())
+    }
+
+    fn pop(&mut self) -> Option<BUF> {
+        if self.len == 0 {
+            return None;
+        }
+        let bytes = self.queue[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        bytes
+    }
+
+    fn finish_transfer(&mut self) {
+        self.hal_serial
+            .tx
+            .usart
+            .disable_error_interrupt_generation();
+        if self.tx.created() {
+            self.tx.destroy_transfer();
+        }
+    }
+}
+
+impl<Serial_, TX_STREAM, const TX_CH: u8, const N: usize, BUF> SerialHandleIT
+    for SerialTxQueue<Serial_, TX_STREAM, TX_CH, N, BUF>
+where
+    Serial_: Instance,
+    Serial_: Deref<Target = <Serial_ as Instance>::RegisterBlock>,
+    <Serial_ as Instance>::RegisterBlock: RegisterBlockImpl,
+
+    TX_STREAM: Stream,
+    ChannelX<TX_CH>: Channel,
+    Tx<Serial_>: DMASet<TX_STREAM, TX_CH, MemoryToPeripheral>,
+    BUF: ReadBuffer<Word = u8>,
+{
+    fn handle_dma_interrupt(&mut self) {
+        if let Some(tx_t) = &mut self.tx.tx_transfer {
+            let flags = tx_t.flags();
+
+            if flags.is_fifo_error() {
+                tx_t.clear_fifo_error();
+            } else if flags.is_transfer_error() {
+                tx_t.clear_transfer_error();
+
+                self.error = Some(Error::TransferError);
+                self.clear_queue();
+                self.finish_transfer();
+            } else if flags.is_transfer_complete() {
+                tx_t.clear_transfer_complete();
+
+                // Chain the next queued buffer, `next_transfer` restarts the stream
+                if let Some(bytes) = self.pop() {
+                    let tx_t = self.tx.tx_transfer.as_mut().unwrap();
+                    if tx_t.next_transfer(bytes).is_err() {
+                        self.error = Some(Error::TransferError);
+                        self.clear_queue();
+                        self.finish_transfer();
+                    }
+                } else {
+                    self.finish_transfer();
+                }
+            }
+        }
+    }
+
+    fn handle_error_interrupt(&mut self) {
+        let res = self
+            .hal_serial
+            .tx
+            .usart
+            .deref()
+            .check_and_clear_error_flags();
+        if let Err(e) = res {
+            self.error = Some(Error::SerialError(e));
+        }
+    }
+}
+
+/// Receiver of frames delimited by idle line
+///
+/// Bytes are received by DMA to one of two buffers. When the line becomes idle (or the buffer is full)
+/// the buffers are swapped and the received frame becomes available with [`read_frame`](Self::read_frame).
+/// If the previous frame was not read yet, it is dropped.
+///
+/// A client must follow these requirements to use that feature:
+/// * Configure serial with [`DmaConfig::Rx`](super::config::DmaConfig::Rx) or [`DmaConfig::TxRx`](super::config::DmaConfig::TxRx).
+/// * Enable interrupt DMAx_STREAMy used for receive and call [`handle_dma_interrupt`](SerialHandleIT::handle_dma_interrupt) in it.
+/// * Enable interrupt USARTx or UARTx and call [`handle_error_interrupt`](SerialHandleIT::handle_error_interrupt) in it,
+///   IDLE events are handled there.
+pub struct FrameReader<Serial_, RX_STREAM, const RX_CH: u8, const N: usize>
+where
+    Serial_: Instance,
+    RX_STREAM: Stream,
+{
+    hal_serial: Serial<Serial_>,
+    rx_transfer: Transfer<RX_STREAM, RX_CH, Rx<Serial_>, PeripheralToMemory, &'static mut [u8; N]>,
+    spare: Option<&'static mut [u8; N]>,
+    ready: Option<(&'static mut [u8; N], usize)>,
+    dropped: u32,
+    error: Option<Error>,
+}
+
+impl<Serial_, RX_STREAM, const RX_CH: u8, const N: usize> FrameReader<Serial_, RX_STREAM, RX_CH, N>
+where
+    Serial_: Instance,
+    Serial_: Deref<Target = <Serial_ as Instance>::RegisterBlock>,
+    <Serial_ as Instance>::RegisterBlock: RegisterBlockImpl,
+
+    RX_STREAM: Stream,
+    ChannelX<RX_CH>: Channel,
+    Rx<Serial_>: DMASet<RX_STREAM, RX_CH, PeripheralToMemory>,
+{
+    fn new(
+        hal_serial: Serial<Serial_>,
+        rx_stream: RX_STREAM,
+        buffers: [&'static mut [u8; N]; 2],
+    ) -> Self {
+        let [first, second] = buffers;
+        let mut rx_transfer = Transfer::init_peripheral_to_memory(
+            rx_stream,
+            Rx {
+                serial: PhantomData,
+            },
+            first,
+            None,
+            DmaConfig::default()
+                .memory_increment(true)
+                .transfer_complete_interrupt(true)
+                .transfer_error_interrupt(true),
+        );
+
+        let usart = hal_serial.tx.usart.deref();
+        usart.clear_idle_interrupt();
+        usart.listen_idle();
+        usart.enable_error_interrupt_generation();
+        rx_transfer.start(|_| {});
+
+        Self {
+            hal_serial,
+            rx_transfer,
+            spare: Some(second),
+            ready: None,
+            dropped: 0,
+            error: None,
+        }
+    }
+
+    /// Passes received frame to `f` if one is available
+    ///
+    /// An error of reception is reported (and cleared) here.
+    pub fn read_frame<R>(&mut self, f: impl FnOnce(&[u8]) -> R) -> nb::Result<R, Error> {
+        if let Some(e) = self.error.take() {
+            return Err(nb::Error::Other(e));
+        }
+        let (buf, len) = self.ready.take().ok_or(nb::Error::WouldBlock)?;
+        let res = f(&buf[..len]);
+        self.spare = Some(buf);
+        Ok(res)
+    }
+
+    /// Returns `true` if a received frame is waiting to be read
+    pub fn is_frame_ready(&self) -> bool {
+        self.ready.is_some()
+    }
+
+    /// Number of frames dropped because they were not read in time
+    pub fn dropped_frames(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Stops reception and returns the serial, DMA stream and buffers
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> (Serial<Serial_>, RX_STREAM, [&'static mut [u8; N]; 2]) {
+        let usart = self.hal_serial.tx.usart.deref();
+        usart.unlisten_idle();
+        usart.disable_error_interrupt_generation();
+        let (stream, _, buf, _) = self.rx_transfer.release();
+        let other = match (self.spare, self.ready) {
+            (Some(b), _) | (None, Some((b, _))) => b,
+            (None, None) => unreachable!(),
+        };
+        (self.hal_serial, stream, [buf, other])
+    }
+
+    fn finish_frame(&mut self) {
+        if self.rx_transfer.number_of_transfers() as usize == N {
+            return;
+        }
+        // Bytes may still come until the stream is stopped, so they are counted after it
+        self.rx_transfer.pause(|_| {});
+        let len = N - self.rx_transfer.number_of_transfers() as usize;
+        let new_buf = match (self.spare.take(), self.ready.take()) {
+            (Some(b), _) => b,
+            (None, Some((b, _))) => {
+                self.dropped = self.dropped.wrapping_add(1);
+                b
+            }
+            (None, None) => unreachable!(),
+        };
+        // `next_transfer` restarts the stream with the other buffer
+        match self.rx_transfer.next_transfer(new_buf) {
+            Ok((buf, _)) => self.ready = Some((buf, len)),
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+impl<Serial_, RX_STREAM, const RX_CH: u8, const N: usize> SerialHandleIT
+    for FrameReader<Serial_, RX_STREAM, RX_CH, N>
+where
+    Serial_: Instance,
+    Serial_: Deref<Target = <Serial_ as Instance>::RegisterBlock>,
+    <Serial_ as Instance>::RegisterBlock: RegisterBlockImpl,
+
+    RX_STREAM: Stream,
+    ChannelX<RX_CH>: Channel,
+    Rx<Serial_>: DMASet<RX_STREAM, RX_CH, PeripheralToMemory>,
+{
+    fn handle_dma_interrupt(&mut self) {
+        let flags = self.rx_transfer.flags();
+
+        if flags.is_fifo_error() {
+            self.rx_transfer.clear_fifo_error();
+        } else if flags.is_transfer_error() {
+            self.rx_transfer.clear_transfer_error();
+            self.error = Some(Error::TransferError);
+        } else if flags.is_transfer_complete() {
+            // Buffer is full, pass it as a frame
+            self.rx_transfer.clear_transfer_complete();
+            self.finish_frame();
+        }
+    }
+
+    fn handle_error_interrupt(&mut self) {
+        let usart = self.hal_serial.tx.usart.deref();
+        let idle = usart.is_idle();
+        // This also clears IDLE flag
+        if let Err(e) = usart.check_and_clear_error_flags() {
+            self.error = Some(Error::SerialError(e));
+        }
+        if idle {
+            self.finish_frame();
+        }
+    }
+}
+
+/// Continuous receiver using DMA double buffer mode
+///
+/// DMA switches between two buffers without stopping, so no bytes are lost at buffer boundaries.
+/// On transfer complete a third, spare buffer replaces the filled one, which is then passed to the
+/// callback from [`handle_dma_interrupt`](SerialHandleIT::handle_dma_interrupt).
+/// The callback must return before the other buffer gets filled, otherwise an overrun is counted.
+///
+/// A client must follow these requirements to use that feature:
+/// * Configure serial with [`DmaConfig::Rx`](super::config::DmaConfig::Rx) or [`DmaConfig::TxRx`](super::config::DmaConfig::TxRx).
+/// * Enable interrupt DMAx_STREAMy used for receive and call [`handle_dma_interrupt`](SerialHandleIT::handle_dma_interrupt) in it.
+/// * Enable interrupt USARTx or UARTx for handling errors and call [`handle_error_interrupt`](SerialHandleIT::handle_error_interrupt) in it.
+pub struct SerialRxDoubleBuffer<Serial_, RX_STREAM, const RX_CH: u8, const N: usize>
+where
+    Serial_: Instance,
+    RX_STREAM: Stream,
+{
+    hal_serial: Serial<Serial_>,
+    rx_transfer: Transfer<RX_STREAM, RX_CH, Rx<Serial_>, PeripheralToMemory, &'static mut [u8; N]>,
+    spare: Option<&'static mut [u8; N]>,
+    callback: SerialRxBufferCallback,
+    overruns: u32,
+    error: Option<Error>,
+}
+
+impl<Serial_, RX_STREAM, const RX_CH: u8, const N: usize>
+    SerialRxDoubleBuffer<Serial_, RX_STREAM, RX_CH, N>
+where
+    Serial_: Instance,
+    Serial_: Deref<Target = <Serial_ as Instance>::RegisterBlock>,
+    <Serial_ as Instance>::RegisterBlock: RegisterBlockImpl,
+
+    RX_STREAM: Stream,
+    ChannelX<RX_CH>: Channel,
+    Rx<Serial_>: DMASet<RX_STREAM, RX_CH, PeripheralToMemory>,
+{
+    fn new(
+        hal_serial: Serial<Serial_>,
+        rx_stream: RX_STREAM,
+        buffers: [&'static mut [u8; N]; 3],
+        callback: SerialRxBufferCallback,
+    ) -> Self {
+        let [first, second, spare] = buffers;
+        let mut rx_transfer = Transfer::init_peripheral_to_memory(
+            rx_stream,
+            Rx {
+                serial: PhantomData,
+            },
+            first,
+            Some(second),
+            DmaConfig::default()
+                .memory_increment(true)
+                .double_buffer(true)
+                .transfer_complete_interrupt(true)
+                .transfer_error_interrupt(true),
+        );
+
+        hal_serial
+            .tx
+            .usart
+            .deref()
+            .enable_error_interrupt_generation();
+        rx_transfer.start(|_| {});
+
+        Self {
+            hal_serial,
+            rx_transfer,
+            spare: Some(spare),
+            callback,
+            overruns: 0,
+            error: None,
+        }
+    }
+
+    /// Number of buffers lost because callback took too long
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// Returns and clears the last error
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    /// Stops reception and returns the serial, DMA stream and buffers
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> (Serial<Serial_>, RX_STREAM, [&'static mut [u8; N]; 3]) {
+        self.hal_serial
+            .tx
+            .usart
+            .deref()
+            .disable_error_interrupt_generation();
+        let (stream, _, first, second) = self.rx_transfer.release();
+        (
+            self.hal_serial,
+            stream,
+            [first, second.unwrap(), self.spare.unwrap()],
+        )
+    }
+}
+
+impl<Serial_, RX_STREAM, const RX_CH: u8, const N: usize> SerialHandleIT
+    for SerialRxDoubleBuffer<Serial_, RX_STREAM, RX_CH, N>
+where
+    Serial_: Instance,
+    Serial_: Deref<Target = <Serial_ as Instance>::RegisterBlock>,
+    <Serial_ as Instance>::RegisterBlock: RegisterBlockImpl,
+
+    RX_STREAM: Stream,
+    ChannelX<RX_CH>: Channel,
+    Rx<Serial_>: DMASet<RX_STREAM, RX_CH, PeripheralToMemory>,
+{
+    fn handle_dma_interrupt(&mut self) {
+        let flags = self.rx_transfer.flags();
+
+        if flags.is_fifo_error() {
+            self.rx_transfer.clear_fifo_error();
+        } else if flags.is_transfer_error() {
+            self.rx_transfer.clear_transfer_error();
+            self.error = Some(Error::TransferError);
+        } else if flags.is_transfer_complete() {
+            // `next_transfer` clears the flag and replaces the filled buffer with the spare one
+            let spare = self.spare.take().unwrap();
+            match self.rx_transfer.next_transfer(spare) {
+                Ok((filled, _)) => {
+                    (self.callback)(&filled[..]);
+                    self.spare = Some(filled);
+                }
+                Err(DMAError::Overrun(spare))
+                | Err(DMAError::NotReady(spare))
+                | Err(DMAError::SmallBuffer(spare)) => {
+                    self.overruns = self.overruns.wrapping_add(1);
+                    self.spare = Some(spare);
+                }
+            }
+        }
+    }
+
+    fn handle_error_interrupt(&mut self) {
+        let res = self
+            .hal_serial
+            .tx
+            .usart
+            .deref()
+            .check_and_clear_error_flags();
+        if let Err(e) = res {
+            self.error = Some(Error::SerialError(e));
+        }
+    }
+}
+
 pub struct Tx<Serial_> {
     serial: PhantomData<Serial_>,
 }
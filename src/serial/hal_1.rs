@@ -95,3 +95,50 @@ mod nb {
         }
     }
 }
+
+mod spi {
+    use core::ops::Deref;
+
+    use super::super::{Error, Instance, SyncSerial};
+    use crate::gpio::alt::SerialSync;
+    use crate::pac;
+    use embedded_hal::spi::{ErrorKind, ErrorType, SpiBus};
+
+    impl embedded_hal::spi::Error for Error {
+        fn kind(&self) -> ErrorKind {
+            match self {
+                Error::Overrun => ErrorKind::Overrun,
+                _ => ErrorKind::Other,
+            }
+        }
+    }
+
+    impl<USART: Instance + SerialSync> ErrorType for SyncSerial<USART> {
+        type Error = Error;
+    }
+
+    impl<USART> SpiBus<u8> for SyncSerial<USART>
+    where
+        USART: Instance + SerialSync + Deref<Target = pac::usart1::RegisterBlock>,
+    {
+        fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+            Self::read(self, words)
+        }
+
+        fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+            Self::write(self, words)
+        }
+
+        fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+            Self::transfer(self, read, write)
+        }
+
+        fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+            Self::transfer_in_place(self, words)
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Self::flush(self)
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use core::ops::Deref;
+
+use super::{Error, Instance, RegisterBlockImpl, Rx, Serial, Tx};
+use embedded_io::{ErrorKind, ErrorType, Read, ReadReady, Write, WriteReady};
+
+impl embedded_io::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::FrameFormat | Error::Parity | Error::Noise => ErrorKind::InvalidData,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl<USART: Instance, WORD> ErrorType for Serial<USART, WORD> {
+    type Error = Error;
+}
+impl<USART: Instance, WORD> ErrorType for Rx<USART, WORD> {
+    type Error = Error;
+}
+impl<USART: Instance, WORD> ErrorType for Tx<USART, WORD> {
+    type Error = Error;
+}
+
+/// Blocks until at least one byte is received, then reads all bytes already available
+impl<USART: Instance> Read for Rx<USART, u8> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let Some((first, rest)) = buf.split_first_mut() else {
+            return Ok(0);
+        };
+        let usart = unsafe { &*USART::ptr() };
+        *first = nb::block!(usart.read_u8())?;
+        let mut count = 1;
+        for b in rest {
+            match usart.read_u8() {
+                Ok(byte) => *b = byte,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+impl<USART: Instance> ReadReady for Rx<USART, u8> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(unsafe { (*USART::ptr()).is_rx_not_empty() })
+    }
+}
+
+/// Blocks until at least one byte is written, then writes bytes while transmit register is empty
+impl<USART: Instance> Write for Tx<USART, u8>
+where
+    USART: Deref<Target = <USART as Instance>::RegisterBlock>,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let Some((&first, rest)) = buf.split_first() else {
+            return Ok(0);
+        };
+        nb::block!(self.usart.write_u8(first))?;
+        let mut count = 1;
+        for &b in rest {
+            match self.usart.write_u8(b) {
+                Ok(()) => count += 1,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+        }
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.usart.bflush()
+    }
+}
+
+impl<USART: Instance> WriteReady for Tx<USART, u8>
+where
+    USART: Deref<Target = <USART as Instance>::RegisterBlock>,
+{
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.usart.is_tx_empty())
+    }
+}
+
+impl<USART: Instance> Read for Serial<USART, u8> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.rx.read(buf)
+    }
+}
+
+impl<USART: Instance> ReadReady for Serial<USART, u8> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        self.rx.read_ready()
+    }
+}
+
+impl<USART: Instance> Write for Serial<USART, u8>
+where
+    USART: Deref<Target = <USART as Instance>::RegisterBlock>,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.tx.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.tx.flush()
+    }
+}
+
+impl<USART: Instance> WriteReady for Serial<USART, u8>
+where
+    USART: Deref<Target = <USART as Instance>::RegisterBlock>,
+{
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        self.tx.write_ready()
+    }
+}
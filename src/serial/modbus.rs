@@ -0,0 +1,171 @@
+//! Modbus RTU frame timing
+//!
+//! Modbus RTU frames are separated by at least 3.5 characters of silence and characters inside
+//! a frame must not be separated by more than 1.5 characters. [`ModbusRtu`] measures these
+//! intervals with a timer and reports frame boundaries. For baud rates above 19200 fixed
+//! intervals of 750 µs and 1.75 ms are used, as the specification recommends.
+//!
+//! Call [`ModbusRtu::handle_rx`] in USART interrupt and [`ModbusRtu::handle_timeout`] in timer interrupt.
+
+use fugit::TimerDurationU32;
+
+use super::{Error, Instance, RegisterBlockImpl, Serial};
+use crate::time::Bps;
+use crate::timer::{self, Counter, Event as TimerEvent};
+use crate::Listen;
+
+/// Frame boundary reported by [`ModbusRtu::handle_timeout`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtuEvent {
+    /// Frame ended with 3.5 characters of silence
+    FrameEnd,
+    /// Frame ended, but characters were separated by more than 1.5 characters
+    FrameError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Line was silent for 3.5 characters
+    Idle,
+    /// Less than 1.5 characters passed since last received byte
+    Receiving,
+    /// Between 1.5 and 3.5 characters since last received byte
+    Gap,
+    /// Waiting for 3.5 characters silence after transmission or initialization
+    Silence,
+}
+
+/// Serial with Modbus RTU inter-frame and inter-character timing
+pub struct ModbusRtu<USART: Instance, TIM, const FREQ: u32> {
+    serial: Serial<USART>,
+    timer: Counter<TIM, FREQ>,
+    t15: TimerDurationU32<FREQ>,
+    t35: TimerDurationU32<FREQ>,
+    state: State,
+    corrupted: bool,
+}
+
+impl<USART: Instance> Serial<USART> {
+    /// Adds Modbus RTU timing using `timer`. `baudrate` must match the serial configuration
+    ///
+    /// Update interrupt of the timer and RXNE interrupt of USART are enabled.
+    /// Returns [`timer::Error::WrongAutoReload`] if 1.5 or 3.5 characters can't be measured
+    /// with timer frequency `FREQ`.
+    pub fn modbus_rtu<TIM: timer::Instance, const FREQ: u32>(
+        self,
+        timer: Counter<TIM, FREQ>,
+        baudrate: Bps,
+    ) -> Result<ModbusRtu<USART, TIM, FREQ>, timer::Error> {
+        ModbusRtu::new(self, timer, baudrate)
+    }
+}
+
+impl<USART: Instance, TIM: timer::Instance, const FREQ: u32> ModbusRtu<USART, TIM, FREQ> {
+    fn new(
+        serial: Serial<USART>,
+        mut timer: Counter<TIM, FREQ>,
+        baudrate: Bps,
+    ) -> Result<Self, timer::Error> {
+        let baud = baudrate.0 as u64;
+        // Character is 11 bits: start, 8 data, parity (or second stop) and stop bit
+        let (t15, t35) = if baud > 19_200 {
+            (
+                FREQ as u64 * 750 / 1_000_000,
+                FREQ as u64 * 1750 / 1_000_000,
+            )
+        } else {
+            (FREQ as u64 * 33 / (2 * baud), FREQ as u64 * 77 / (2 * baud))
+        };
+        // Timer can't count less than 2 ticks, longer intervals are checked by `start`
+        if t15 < 2 || t35 > u32::MAX as u64 {
+            return Err(timer::Error::WrongAutoReload);
+        }
+        let t15 = TimerDurationU32::from_ticks(t15 as u32);
+        let t35 = TimerDurationU32::from_ticks(t35 as u32);
+
+        // Line must be silent for 3.5 characters before the first frame.
+        // All other intervals are shorter, so they can't fail to start later
+        timer.start(t35)?;
+        timer.listen(TimerEvent::Update);
+        unsafe { (*USART::ptr()).listen_rxne() };
+
+        Ok(Self {
+            serial,
+            timer,
+            t15,
+            t35,
+            state: State::Silence,
+            corrupted: false,
+        })
+    }
+
+    /// Reads received byte and restarts inter-character timing. Call from USART interrupt
+    pub fn handle_rx(&mut self) -> nb::Result<u8, Error> {
+        let byte = unsafe { (*USART::ptr()).read_u8() }?;
+        match self.state {
+            State::Idle | State::Silence => self.corrupted = false,
+            State::Receiving => {}
+            State::Gap => self.corrupted = true,
+        }
+        self.state = State::Receiving;
+        self.timer.start(self.t15).unwrap();
+        Ok(byte)
+    }
+
+    /// Advances timing state. Call from timer interrupt
+    ///
+    /// Returns frame boundary when 3.5 characters of silence passed after the last received byte.
+    pub fn handle_timeout(&mut self) -> Option<RtuEvent> {
+        self.timer.wait().ok()?;
+        match self.state {
+            State::Receiving => {
+                self.state = State::Gap;
+                self.timer.start(self.t35 - self.t15).unwrap();
+                None
+            }
+            State::Gap => {
+                self.state = State::Idle;
+                let _ = self.timer.cancel();
+                Some(if self.corrupted {
+                    RtuEvent::FrameError
+                } else {
+                    RtuEvent::FrameEnd
+                })
+            }
+            State::Idle | State::Silence => {
+                self.state = State::Idle;
+                let _ = self.timer.cancel();
+                None
+            }
+        }
+    }
+
+    /// Returns `true` if line was silent long enough to start transmission
+    pub fn is_idle(&self) -> bool {
+        self.state == State::Idle
+    }
+
+    /// Sends `bytes` as a frame and starts 3.5 characters silence interval after it
+    ///
+    /// Returns `WouldBlock` while the line is not idle.
+    pub fn bwrite_frame(&mut self, bytes: &[u8]) -> nb::Result<(), Error> {
+        if !self.is_idle() {
+            return Err(nb::Error::WouldBlock);
+        }
+        let usart = unsafe { &*USART::ptr() };
+        usart.bwrite_all_u8(bytes)?;
+        usart.bflush()?;
+        self.state = State::Silence;
+        self.timer.start(self.t35).unwrap();
+        Ok(())
+    }
+
+    /// Disables interrupts and returns serial and timer
+    pub fn release(mut self) -> (Serial<USART>, Counter<TIM, FREQ>) {
+        unsafe { (*USART::ptr()).unlisten_rxne() };
+        self.timer.unlisten(TimerEvent::Update);
+        let _ = self.timer.cancel();
+        (self.serial, self.timer)
+    }
+}
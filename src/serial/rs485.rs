@@ -0,0 +1,251 @@
+//! RS-485 half-duplex transmitter
+//!
+//! USARTs of STM32F4 have no hardware driver enable output, so the transceiver DE (driver enable)
+//! pin is driven by software: it is asserted before the first word is written and deasserted
+//! after transmission complete (TC) flag is set.
+//!
+//! Both blocking [`Tx`] and DMA based [`SerialTxQueue`] transmitters are supported.
+//! In DMA mode call [`handle_dma_interrupt`](SerialHandleIT::handle_dma_interrupt) in DMA stream
+//! interrupt and [`handle_error_interrupt`](SerialHandleIT::handle_error_interrupt) in USART interrupt
+//! as transmission complete is detected there. Deassertion time is then waited with a busy loop
+//! inside the USART interrupt handler, so it should be kept short.
+
+use core::fmt;
+
+use embedded_dma::ReadBuffer;
+use embedded_hal::digital::OutputPin;
+
+use super::dma::{SerialHandleIT, SerialTxQueue, Tx as TxDma};
+use super::{config::Rs485Config, Error, Event, Flag, Instance, RegisterBlockImpl, Tx};
+use crate::dma::{
+    traits::{Channel, DMASet, Stream},
+    ChannelX, MemoryToPeripheral,
+};
+use crate::rcc::Clocks;
+
+/// Transmitter which drives RS-485 transceiver DE pin
+pub struct Rs485<TX, DE> {
+    tx: TX,
+    de: DE,
+    active: bool,
+    assertion_cycles: u32,
+    deassertion_cycles: u32,
+}
+
+impl<TX, DE: OutputPin> Rs485<TX, DE> {
+    fn new(tx: TX, mut de: DE, config: Rs485Config, clocks: &Clocks) -> Self {
+        let _ = de.set_low();
+        let cycles_per_us = clocks.sysclk().raw() / 1_000_000;
+        Self {
+            tx,
+            de,
+            active: false,
+            assertion_cycles: config.assertion_time.to_micros() * cycles_per_us,
+            deassertion_cycles: config.deassertion_time.to_micros() * cycles_per_us,
+        }
+    }
+
+    fn assert_de(&mut self) {
+        if !self.active {
+            let _ = self.de.set_high();
+            self.active = true;
+            if self.assertion_cycles != 0 {
+                cortex_m::asm::delay(self.assertion_cycles);
+            }
+        }
+    }
+
+    // Busy waits for deassertion time, in USART interrupt handler in DMA mode
+    fn deassert_de(&mut self) {
+        if self.active {
+            if self.deassertion_cycles != 0 {
+                cortex_m::asm::delay(self.deassertion_cycles);
+            }
+            let _ = self.de.set_low();
+            self.active = false;
+        }
+    }
+
+    /// Returns `true` while transceiver driver is enabled
+    pub fn is_driver_enabled(&self) -> bool {
+        self.active
+    }
+
+    /// Returns the transmitter and DE pin
+    pub fn release(mut self) -> (TX, DE) {
+        let _ = self.de.set_low();
+        (self.tx, self.de)
+    }
+}
+
+impl<USART: Instance, WORD> Tx<USART, WORD> {
+    /// Converts transmitter to RS-485 one with driver enable `de` pin
+    pub fn rs485<DE: OutputPin>(
+        self,
+        de: DE,
+        config: impl Into<Rs485Config>,
+        clocks: &Clocks,
+    ) -> Rs485<Self, DE> {
+        Rs485::new(self, de, config.into(), clocks)
+    }
+}
+
+impl<USART: Instance, DE: OutputPin> Rs485<Tx<USART, u8>, DE> {
+    /// Writes `bytes` with driver enabled and waits for transmission complete
+    pub fn bwrite_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.assert_de();
+        let usart = unsafe { &*USART::ptr() };
+        let res = usart.bwrite_all_u8(bytes).and_then(|_| usart.bflush());
+        self.deassert_de();
+        res
+    }
+
+    /// Writes a single byte, enabling the driver if needed
+    pub fn write(&mut self, word: u8) -> nb::Result<(), Error> {
+        self.assert_de();
+        unsafe { (*USART::ptr()).write_u8(word) }
+    }
+}
+
+impl<USART: Instance, DE: OutputPin> Rs485<Tx<USART, u16>, DE> {
+    /// Writes `words` with driver enabled and waits for transmission complete
+    pub fn bwrite_all(&mut self, words: &[u16]) -> Result<(), Error> {
+        self.assert_de();
+        let usart = unsafe { &*USART::ptr() };
+        let res = usart.bwrite_all_u16(words).and_then(|_| usart.bflush());
+        self.deassert_de();
+        res
+    }
+
+    /// Writes a single word, enabling the driver if needed
+    pub fn write(&mut self, word: u16) -> nb::Result<(), Error> {
+        self.assert_de();
+        unsafe { (*USART::ptr()).write_u16(word) }
+    }
+}
+
+impl<USART: Instance, WORD, DE: OutputPin> Rs485<Tx<USART, WORD>, DE> {
+    /// Waits for transmission complete and disables the driver
+    pub fn flush(&mut self) -> nb::Result<(), Error> {
+        unsafe { (*USART::ptr()).flush() }?;
+        self.deassert_de();
+        Ok(())
+    }
+}
+
+impl<USART: Instance, DE: OutputPin> fmt::Write for Rs485<Tx<USART>, DE> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.bwrite_all(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+impl<USART: Instance, DE: OutputPin> embedded_hal_02::blocking::serial::Write<u8>
+    for Rs485<Tx<USART, u8>, DE>
+{
+    type Error = Error;
+
+    fn bwrite_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        Self::bwrite_all(self, bytes)
+    }
+
+    fn bflush(&mut self) -> Result<(), Self::Error> {
+        nb::block!(Self::flush(self))
+    }
+}
+
+impl<USART: Instance, WORD, DE> embedded_hal_nb::serial::ErrorType for Rs485<Tx<USART, WORD>, DE> {
+    type Error = Error;
+}
+
+impl<USART: Instance, DE: OutputPin> embedded_hal_nb::serial::Write<u8>
+    for Rs485<Tx<USART, u8>, DE>
+{
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        Self::write(self, word)
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        Self::flush(self)
+    }
+}
+
+impl<USART, TX_STREAM, const TX_CH: u8, const N: usize, BUF>
+    SerialTxQueue<USART, TX_STREAM, TX_CH, N, BUF>
+where
+    USART: Instance,
+    TX_STREAM: Stream,
+{
+    /// Converts DMA transmitter to RS-485 one with driver enable `de` pin
+    pub fn rs485<DE: OutputPin>(
+        self,
+        de: DE,
+        config: impl Into<Rs485Config>,
+        clocks: &Clocks,
+    ) -> Rs485<Self, DE> {
+        Rs485::new(self, de, config.into(), clocks)
+    }
+}
+
+impl<USART, TX_STREAM, const TX_CH: u8, const N: usize, BUF, DE>
+    Rs485<SerialTxQueue<USART, TX_STREAM, TX_CH, N, BUF>, DE>
+where
+    USART: Instance,
+    USART: core::ops::Deref<Target = <USART as Instance>::RegisterBlock>,
+    TX_STREAM: Stream,
+    ChannelX<TX_CH>: Channel,
+    TxDma<USART>: DMASet<TX_STREAM, TX_CH, MemoryToPeripheral>,
+    BUF: ReadBuffer<Word = u8>,
+    DE: OutputPin,
+{
+    /// Enables the driver and enqueues `bytes` for transmission
+    ///
+    /// See [`SerialTxQueue::write_all`]
+    pub fn write_all(&mut self, bytes: BUF) -> nb::Result<(), super::dma::Error> {
+        let was_active = self.active;
+        self.assert_de();
+        let res = self.tx.write_all(bytes);
+        if !was_active && !self.tx.in_progress() {
+            // Nothing was sent (empty buffer or error), so no TC interrupt disables the driver
+            let _ = self.de.set_low();
+            self.active = false;
+        }
+        res
+    }
+
+    /// Returns `true` until all queued buffers are sent and the driver is disabled
+    pub fn in_progress(&self) -> bool {
+        self.active
+    }
+}
+
+impl<USART, TX_STREAM, const TX_CH: u8, const N: usize, BUF, DE> SerialHandleIT
+    for Rs485<SerialTxQueue<USART, TX_STREAM, TX_CH, N, BUF>, DE>
+where
+    USART: Instance,
+    USART: core::ops::Deref<Target = <USART as Instance>::RegisterBlock>,
+    TX_STREAM: Stream,
+    ChannelX<TX_CH>: Channel,
+    TxDma<USART>: DMASet<TX_STREAM, TX_CH, MemoryToPeripheral>,
+    BUF: ReadBuffer<Word = u8>,
+    DE: OutputPin,
+{
+    fn handle_dma_interrupt(&mut self) {
+        self.tx.handle_dma_interrupt();
+        if self.active && !self.tx.in_progress() {
+            // Last byte is still in shift register, wait for TC in USART interrupt
+            unsafe { (*USART::ptr()).listen_event(None, Some(Event::TransmissionComplete.into())) };
+        }
+    }
+
+    fn handle_error_interrupt(&mut self) {
+        self.tx.handle_error_interrupt();
+        let usart = unsafe { &*USART::ptr() };
+        if self.active
+            && !self.tx.in_progress()
+            && usart.flags().contains(Flag::TransmissionComplete)
+        {
+            usart.listen_event(Some(Event::TransmissionComplete.into()), None);
+            self.deassert_de();
+        }
+    }
+}
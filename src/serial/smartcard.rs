@@ -0,0 +1,180 @@
+//! Smartcard (ISO 7816-3) mode of USART
+//!
+//! In this mode the USART drives the card clock on CK pin and uses TX pin
+//! (configured as open-drain) as bidirectional data line.
+//!
+//! Frames are 8 data bits with parity and 1.5 stop bits. If NACK is enabled, the receiver
+//! signals parity errors to the card which then repeats the character.
+
+use core::ops::Deref;
+
+use super::config::{InvalidConfig, Oversampling, Parity, SmartcardConfig};
+use super::uart_impls::calculate_brr;
+use super::{Error, Instance, RegisterBlockImpl};
+use crate::gpio::{alt::SerialSync, OpenDrain};
+use crate::pac::{self, usart1::cr2::STOP};
+use crate::rcc::Clocks;
+
+/// Smartcard interface
+pub struct Smartcard<USART: Instance + SerialSync> {
+    usart: USART,
+    pins: (USART::Tx<OpenDrain>, USART::Ck),
+}
+
+pub trait SmartcardExt: Sized + Instance + SerialSync {
+    fn smartcard(
+        self,
+        pins: (impl Into<Self::Tx<OpenDrain>>, impl Into<Self::Ck>),
+        config: impl Into<SmartcardConfig>,
+        clocks: &Clocks,
+    ) -> Result<Smartcard<Self>, InvalidConfig>;
+}
+
+impl<USART> SmartcardExt for USART
+where
+    USART: Instance + SerialSync + Deref<Target = pac::usart1::RegisterBlock>,
+{
+    fn smartcard(
+        self,
+        pins: (impl Into<Self::Tx<OpenDrain>>, impl Into<Self::Ck>),
+        config: impl Into<SmartcardConfig>,
+        clocks: &Clocks,
+    ) -> Result<Smartcard<Self>, InvalidConfig> {
+        Smartcard::new(self, pins, config, clocks)
+    }
+}
+
+impl<USART> Smartcard<USART>
+where
+    USART: Instance + SerialSync + Deref<Target = pac::usart1::RegisterBlock>,
+{
+    pub fn new(
+        usart: USART,
+        pins: (impl Into<USART::Tx<OpenDrain>>, impl Into<USART::Ck>),
+        config: impl Into<SmartcardConfig>,
+        clocks: &Clocks,
+    ) -> Result<Self, InvalidConfig> {
+        let config = config.into();
+        unsafe {
+            // Enable clock.
+            USART::enable_unchecked();
+            USART::reset_unchecked();
+        }
+
+        let pclk_freq = USART::kernel_clock(clocks).raw();
+
+        // Card clock is pclk / (2 x PSC), PSC is 5 bits wide
+        let clock = config.clock.raw();
+        if clock == 0 {
+            return Err(InvalidConfig);
+        }
+        let psc = (pclk_freq + 2 * clock - 1) / (2 * clock);
+        if psc == 0 || psc > 0x1f {
+            return Err(InvalidConfig);
+        }
+
+        // ISO 7816-3 default elementary time unit is 372 card clock cycles
+        let baud = match config.baudrate {
+            Some(baud) => baud.0,
+            None => pclk_freq / (2 * psc) / 372,
+        };
+        // Oversampling by 8 is not available in Smartcard mode
+        let (_, div) = calculate_brr(pclk_freq, baud, Oversampling::By16)?;
+
+        if config.parity == Parity::ParityNone {
+            return Err(InvalidConfig);
+        }
+
+        usart.brr().write(|w| unsafe { w.bits(div) });
+        usart.gtpr().write(|w| unsafe {
+            w.psc().bits(psc as u8);
+            w.gt().bits(config.guard_time)
+        });
+
+        // Clock output and 1.5 stop bits, LIN disabled
+        usart.cr2().write(|w| {
+            w.clken().enabled();
+            w.stop().variant(STOP::Stop1p5)
+        });
+
+        // Smartcard mode, IrDA and half-duplex disabled
+        usart.cr3().write(|w| {
+            w.scen().enabled();
+            w.nack().bit(config.nack)
+        });
+
+        // 9 bit frames: 8 data bits + parity
+        usart.cr1().write(|w| {
+            w.ue().set_bit();
+            w.over8().clear_bit();
+            w.te().set_bit();
+            w.re().set_bit();
+            w.m().set_bit();
+            w.pce().set_bit();
+            w.ps().bit(config.parity == Parity::ParityOdd)
+        });
+
+        Ok(Self {
+            usart,
+            pins: (pins.0.into(), pins.1.into()),
+        })
+    }
+
+    /// Writes a byte to the card
+    ///
+    /// Receiver is disabled until [`flush`](Self::flush) returns, so the character is not echoed back.
+    pub fn write(&mut self, byte: u8) -> nb::Result<(), Error> {
+        self.usart.cr1().modify(|_, w| w.re().clear_bit());
+        self.usart.write_u8(byte)
+    }
+
+    /// Waits for transmission complete and enables receiver again
+    pub fn flush(&mut self) -> nb::Result<(), Error> {
+        self.usart.flush()?;
+        self.usart.cr1().modify(|_, w| w.re().set_bit());
+        Ok(())
+    }
+
+    /// Reads a byte from the card
+    ///
+    /// Returns [`Error::Parity`] if character was received with wrong parity.
+    /// In that case NACK was sent to the card if enabled.
+    pub fn read(&mut self) -> nb::Result<u8, Error> {
+        self.usart.read_u8()
+    }
+
+    pub fn bwrite_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        for &b in bytes {
+            nb::block!(self.write(b))?;
+        }
+        nb::block!(self.flush())
+    }
+
+    pub fn bread_all(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        self.usart.bread_all_u8(buffer)
+    }
+
+    /// Changes guard time (in baud clock periods) between transmitted characters
+    pub fn set_guard_time(&mut self, guard_time: u8) {
+        self.usart
+            .gtpr()
+            .modify(|_, w| unsafe { w.gt().bits(guard_time) });
+    }
+
+    /// Stops card clock output
+    ///
+    /// Some cards support clock stop in idle state to save power
+    pub fn stop_clock(&mut self) {
+        self.usart.cr2().modify(|_, w| w.clken().disabled());
+    }
+
+    /// Restarts card clock output
+    pub fn start_clock(&mut self) {
+        self.usart.cr2().modify(|_, w| w.clken().enabled());
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> (USART, (USART::Tx<OpenDrain>, USART::Ck)) {
+        (self.usart, self.pins)
+    }
+}
@@ -0,0 +1,66 @@
+//! Serial error counters
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use enumflags2::BitFlags;
+
+use super::Flag;
+
+/// Error counters of USART instance, incremented from interrupt handler
+#[doc(hidden)]
+pub struct ErrorStats {
+    overrun: AtomicU32,
+    noise: AtomicU32,
+    framing: AtomicU32,
+    parity: AtomicU32,
+}
+
+impl ErrorStats {
+    pub const fn new() -> Self {
+        Self {
+            overrun: AtomicU32::new(0),
+            noise: AtomicU32::new(0),
+            framing: AtomicU32::new(0),
+            parity: AtomicU32::new(0),
+        }
+    }
+
+    pub(crate) fn record(&self, flags: BitFlags<Flag>) {
+        for (flag, counter) in [
+            (Flag::Overrun, &self.overrun),
+            (Flag::Noise, &self.noise),
+            (Flag::FramingError, &self.framing),
+            (Flag::ParityError, &self.parity),
+        ] {
+            if flags.contains(flag) {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub(crate) fn take(&self) -> ErrorCounts {
+        ErrorCounts {
+            overrun: self.overrun.swap(0, Ordering::Relaxed),
+            noise: self.noise.swap(0, Ordering::Relaxed),
+            framing: self.framing.swap(0, Ordering::Relaxed),
+            parity: self.parity.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+/// Numbers of receive errors since previous read
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorCounts {
+    pub overrun: u32,
+    pub noise: u32,
+    pub framing: u32,
+    pub parity: u32,
+}
+
+impl ErrorCounts {
+    /// Total number of errors
+    pub fn total(&self) -> u32 {
+        self.overrun + self.noise + self.framing + self.parity
+    }
+}
@@ -0,0 +1,173 @@
+//! Synchronous (clocked) mode of USART
+//!
+//! USART outputs bit clock on CK pin while transmitting, so it can be used as a simple
+//! SPI master when all SPI peripherals are occupied. TX pin is MOSI, RX pin is MISO.
+//!
+//! Clock is only generated for transmitted data, so each received word needs a word
+//! to be written. Frames are always 8 bits, start and stop bits are not clocked.
+//! USART sends least significant bit first, use [`SyncConfig::msb_first`] to swap bits in software.
+
+use core::ops::Deref;
+
+use super::config::{InvalidConfig, Oversampling, SyncConfig};
+use super::uart_impls::calculate_brr;
+use super::{Error, Instance, RegisterBlockImpl};
+use crate::gpio::{alt::SerialSync, PushPull};
+use crate::pac;
+use crate::rcc::Clocks;
+use crate::spi::{Phase, Polarity};
+
+/// Synchronous serial interface
+pub struct SyncSerial<USART: Instance + SerialSync> {
+    usart: USART,
+    pins: (USART::Tx<PushPull>, USART::Rx<PushPull>, USART::Ck),
+    msb_first: bool,
+}
+
+pub trait SyncSerialExt: Sized + Instance + SerialSync {
+    fn synchronous(
+        self,
+        pins: (
+            impl Into<Self::Tx<PushPull>>,
+            impl Into<Self::Rx<PushPull>>,
+            impl Into<Self::Ck>,
+        ),
+        config: impl Into<SyncConfig>,
+        clocks: &Clocks,
+    ) -> Result<SyncSerial<Self>, InvalidConfig>;
+}
+
+impl<USART> SyncSerialExt for USART
+where
+    USART: Instance + SerialSync + Deref<Target = pac::usart1::RegisterBlock>,
+{
+    fn synchronous(
+        self,
+        pins: (
+            impl Into<Self::Tx<PushPull>>,
+            impl Into<Self::Rx<PushPull>>,
+            impl Into<Self::Ck>,
+        ),
+        config: impl Into<SyncConfig>,
+        clocks: &Clocks,
+    ) -> Result<SyncSerial<Self>, InvalidConfig> {
+        SyncSerial::new(self, pins, config, clocks)
+    }
+}
+
+impl<USART> SyncSerial<USART>
+where
+    USART: Instance + SerialSync + Deref<Target = pac::usart1::RegisterBlock>,
+{
+    pub fn new(
+        usart: USART,
+        pins: (
+            impl Into<USART::Tx<PushPull>>,
+            impl Into<USART::Rx<PushPull>>,
+            impl Into<USART::Ck>,
+        ),
+        config: impl Into<SyncConfig>,
+        clocks: &Clocks,
+    ) -> Result<Self, InvalidConfig> {
+        let config = config.into();
+        unsafe {
+            // Enable clock.
+            USART::enable_unchecked();
+            USART::reset_unchecked();
+        }
+
+        let pclk_freq = USART::kernel_clock(clocks).raw();
+        let (over8, div) = calculate_brr(pclk_freq, config.baudrate.0, Oversampling::Auto)?;
+
+        usart.brr().write(|w| unsafe { w.bits(div) });
+
+        // Clock settings must be written before transmitter is enabled, LIN disabled
+        usart.cr2().write(|w| {
+            w.clken().enabled();
+            w.cpol().bit(config.mode.polarity == Polarity::IdleHigh);
+            w.cpha()
+                .bit(config.mode.phase == Phase::CaptureOnSecondTransition);
+            w.lbcl().bit(config.last_bit_clock)
+        });
+
+        // Smartcard, IrDA and half-duplex disabled
+        usart.cr3().reset();
+
+        usart.cr1().write(|w| {
+            w.ue().set_bit();
+            w.over8().bit(over8);
+            w.te().set_bit();
+            w.re().set_bit()
+        });
+
+        Ok(Self {
+            usart,
+            pins: (pins.0.into(), pins.1.into(), pins.2.into()),
+            msb_first: config.msb_first,
+        })
+    }
+
+    #[inline(always)]
+    fn swap(&self, word: u8) -> u8 {
+        if self.msb_first {
+            word.reverse_bits()
+        } else {
+            word
+        }
+    }
+
+    /// Sends `word` and returns the word received at the same time
+    pub fn transfer_word(&mut self, word: u8) -> Result<u8, Error> {
+        nb::block!(self.usart.write_u8(self.swap(word)))?;
+        let received = nb::block!(self.usart.read_u8())?;
+        Ok(self.swap(received))
+    }
+
+    /// Sends `words` replacing them with received ones
+    pub fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        for word in words {
+            *word = self.transfer_word(*word)?;
+        }
+        Ok(())
+    }
+
+    /// Sends `write` while receiving to `read`
+    ///
+    /// If `read` is longer than `write`, zeros are sent. If `write` is longer,
+    /// extra received words are discarded.
+    pub fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Error> {
+        for i in 0..read.len().max(write.len()) {
+            let received = self.transfer_word(write.get(i).copied().unwrap_or(0))?;
+            if let Some(r) = read.get_mut(i) {
+                *r = received;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends `words` discarding received data
+    pub fn write(&mut self, words: &[u8]) -> Result<(), Error> {
+        for &word in words {
+            self.transfer_word(word)?;
+        }
+        Ok(())
+    }
+
+    /// Receives to `words` sending zeros
+    pub fn read(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        for word in words {
+            *word = self.transfer_word(0)?;
+        }
+        Ok(())
+    }
+
+    /// Waits for transmission complete
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.usart.bflush()
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> (USART, (USART::Tx<PushPull>, USART::Rx<PushPull>, USART::Ck)) {
+        (self.usart, self.pins)
+    }
+}
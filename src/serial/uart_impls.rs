@@ -10,7 +10,7 @@ use crate::dma::{
     traits::{DMASet, PeriAddress},
     MemoryToPeripheral, PeripheralToMemory,
 };
-use crate::gpio::{alt::SerialAsync as CommonPins, NoPin, PushPull};
+use crate::gpio::{alt::SerialAsync as CommonPins, alt::SerialRs232, NoPin, PushPull};
 use crate::rcc::{self, Clocks};
 
 #[cfg(feature = "uart4")]
@@ -22,7 +22,15 @@ impl crate::Sealed for RegisterBlockUart {}
 impl crate::Sealed for RegisterBlockUsart {}
 
 // Implemented by all USART/UART instances
-pub trait Instance: crate::Sealed + rcc::Enable + rcc::Reset + rcc::BusClock + CommonPins {
+pub trait Instance:
+    crate::Sealed
+    + rcc::Enable
+    + rcc::Reset
+    + rcc::BusClock
+    + rcc::PeripheralClock
+    + CommonPins
+    + SerialRs232
+{
     type RegisterBlock: RegisterBlockImpl;
 
     #[doc(hidden)]
@@ -31,8 +39,18 @@ pub trait Instance: crate::Sealed + rcc::Enable + rcc::Reset + rcc::BusClock + C
     fn set_stopbits(&self, bits: config::StopBits);
     #[doc(hidden)]
     fn peri_address() -> u32;
+    #[doc(hidden)]
+    fn error_stats() -> &'static super::stats::ErrorStats;
+    #[cfg(feature = "async")]
+    #[doc(hidden)]
+    fn wakers() -> &'static super::asynch::Wakers;
 }
 
+/// Nominal IrDA low-power frequency
+const IRDA_LOW_POWER_FREQ: u32 = 1_843_200;
+/// IrDA SIR physical layer supports rates up to 115.2 kbps
+const IRDA_MAX_BAUDRATE: u32 = 115_200;
+
 pub trait RegisterBlockImpl: crate::Sealed {
     #[allow(clippy::new_ret_no_self)]
     fn new<UART: Instance<RegisterBlock = Self>, WORD>(
@@ -107,6 +125,26 @@ pub trait RegisterBlockImpl: crate::Sealed {
     fn enable_error_interrupt_generation(&self);
     fn disable_error_interrupt_generation(&self);
 
+    // Hardware flow control
+    fn enable_rts(&self, state: bool);
+    fn enable_cts(&self, state: bool);
+    fn enable_cts_interrupt(&self, state: bool);
+
+    /// Baud rate achieved with current `BRR` and `OVER8` settings
+    fn baudrate(&self, pclk_freq: u32) -> u32;
+    /// Changes baud rate divider and frame format with transmitter and receiver disabled
+    fn set_frame(&self, over8: bool, div: u32, config: &config::Config);
+
+    // Break
+    fn send_break(&self);
+    fn enable_break_detection(&self, length: Option<config::BreakLength>);
+    fn listen_break(&self, state: bool);
+
+    // Multiprocessor communication
+    fn set_wakeup(&self, wakeup: config::Wakeup);
+    fn set_mute(&self, state: bool);
+    fn is_muted(&self) -> bool;
+
     // Listen
     fn listen_event(&self, disable: Option<BitFlags<Event>>, enable: Option<BitFlags<Event>>);
 
@@ -139,6 +177,127 @@ pub trait RegisterBlockImpl: crate::Sealed {
     fn peri_address(&self) -> u32;
 }
 
+/// Calculates `OVER8` bit and `BRR` register value for `baud`
+pub(crate) fn calculate_brr(
+    pclk_freq: u32,
+    baud: u32,
+    oversampling: config::Oversampling,
+) -> Result<(bool, u32), config::InvalidConfig> {
+    use config::Oversampling;
+
+    // The frequency to calculate USARTDIV is this:
+    //
+    // (Taken from STM32F411xC/E Reference Manual,
+    // Section 19.3.4, Equation 1)
+    //
+    // 16 bit oversample: OVER8 = 0
+    // 8 bit oversample:  OVER8 = 1
+    //
+    // USARTDIV =          (pclk)
+    //            ------------------------
+    //            8 x (2 - OVER8) x (baud)
+    //
+    // BUT, the USARTDIV has 4 "fractional" bits, which effectively
+    // means that we need to "correct" the equation as follows:
+    //
+    // USARTDIV =      (pclk) * 16
+    //            ------------------------
+    //            8 x (2 - OVER8) x (baud)
+    //
+    // When OVER8 is enabled, we can only use the lowest three
+    // fractional bits, so we'll need to shift those last four bits
+    // right one bit
+    //
+    // In Smartcard, LIN, and IrDA modes, OVER8 is always disabled.
+    //
+    // (Taken from STM32F411xC/E Reference Manual,
+    // Section 19.3.4, Equation 2)
+    //
+    // USARTDIV =   pclk
+    //            ---------
+    //            16 x baud
+    //
+    // With reference to the above, OVER8 == 0 when in Smartcard, LIN, and
+    // IrDA modes, so the register value needed for USARTDIV is the same
+    // as for 16 bit oversampling.
+
+    if baud == 0 {
+        Err(config::InvalidConfig)
+    } else if oversampling != Oversampling::By8 && (pclk_freq / 16) >= baud {
+        // We have the ability to oversample to 16 bits, take
+        // advantage of it.
+        //
+        // We also add `baud / 2` to the `pclk_freq` to ensure
+        // rounding of values to the closest scale, rather than the
+        // floored behavior of normal integer division.
+        let div = (pclk_freq + (baud / 2)) / baud;
+        Ok((false, div))
+    } else if oversampling != Oversampling::By16 && (pclk_freq / 8) >= baud {
+        // We are close enough to pclk where we can only
+        // oversample 8.
+        //
+        // See note above regarding `baud` and rounding.
+        let div = ((pclk_freq * 2) + (baud / 2)) / baud;
+
+        // Ensure the the fractional bits (only 3) are
+        // right-aligned.
+        let frac = div & 0xF;
+        let div = (div & !0xF) | (frac >> 1);
+        Ok((true, div))
+    } else {
+        Err(config::InvalidConfig)
+    }
+}
+
+/// Calculates `OVER8` bit and `BRR` register value for serial `config`
+pub(crate) fn calculate_config_brr(
+    pclk_freq: u32,
+    config: &config::Config,
+) -> Result<(bool, u32), config::InvalidConfig> {
+    use config::{IrdaMode, Oversampling};
+
+    let baud = config.baudrate.0;
+    // In IrDA mode, OVER8 is always disabled.
+    let oversampling = match (config.irda, config.oversampling) {
+        (IrdaMode::None, oversampling) => oversampling,
+        (_, Oversampling::By8) => return Err(config::InvalidConfig),
+        _ => Oversampling::By16,
+    };
+    let (over8, div) = calculate_brr(pclk_freq, baud, oversampling)?;
+    if let Some(max_error) = config.max_baud_error {
+        if baud_error_ppm(baud, actual_baudrate(pclk_freq, over8, div)) > max_error {
+            return Err(config::InvalidConfig);
+        }
+    }
+    Ok((over8, div))
+}
+
+/// Baud rate achieved with `OVER8` bit and `BRR` register value
+pub(crate) fn actual_baudrate(pclk_freq: u32, over8: bool, brr: u32) -> u32 {
+    // USARTDIV with 4 (or 3 with OVER8) fractional bits is pclk / baud
+    let div = if over8 {
+        ((brr & !0xF) >> 1) | (brr & 0x7)
+    } else {
+        brr
+    };
+    if div == 0 {
+        0
+    } else {
+        (pclk_freq + div / 2) / div
+    }
+}
+
+/// Deviation of `actual` baud rate from `requested` one in ppm
+///
+/// Saturates at `u32::MAX`, which is also returned for any nonzero `actual` if `requested` is 0.
+pub fn baud_error_ppm(requested: u32, actual: u32) -> u32 {
+    if requested == 0 {
+        return if actual == 0 { 0 } else { u32::MAX };
+    }
+    let ppm = requested.abs_diff(actual) as u64 * 1_000_000 / requested as u64;
+    ppm.min(u32::MAX as u64) as u32
+}
+
 macro_rules! uartCommon {
     () => {
         fn read_u16(&self) -> nb::Result<u16, Error> {
@@ -248,6 +407,64 @@ macro_rules! uartCommon {
             });
         }
 
+        fn baudrate(&self, pclk_freq: u32) -> u32 {
+            actual_baudrate(
+                pclk_freq,
+                self.cr1().read().over8().bit_is_set(),
+                self.brr().read().bits(),
+            )
+        }
+
+        fn set_frame(&self, over8: bool, div: u32, config: &config::Config) {
+            use config::{Parity, WordLength};
+
+            self.cr1().modify(|_, w| {
+                w.te().clear_bit();
+                w.re().clear_bit()
+            });
+            self.brr().write(|w| unsafe { w.bits(div) });
+            self.cr1().modify(|_, w| {
+                w.over8().bit(over8);
+                w.m().bit(config.wordlength == WordLength::DataBits9);
+                w.pce().bit(config.parity != Parity::ParityNone);
+                w.ps().bit(config.parity == Parity::ParityOdd);
+                w.te().set_bit();
+                w.re().set_bit()
+            });
+        }
+
+        fn send_break(&self) {
+            self.cr1().modify(|_, w| w.sbk().set_bit());
+        }
+
+        fn enable_break_detection(&self, length: Option<config::BreakLength>) {
+            self.cr2().modify(|_, w| {
+                w.lbdl().bit(length == Some(config::BreakLength::Bits11));
+                w.linen().bit(length.is_some())
+            });
+        }
+
+        fn listen_break(&self, state: bool) {
+            self.cr2().modify(|_, w| w.lbdie().bit(state));
+        }
+
+        fn set_wakeup(&self, wakeup: config::Wakeup) {
+            let (address_mark, address) = match wakeup {
+                config::Wakeup::IdleLine => (false, 0),
+                config::Wakeup::AddressMark(address) => (true, address & 0xf),
+            };
+            self.cr2().modify(|_, w| unsafe { w.add().bits(address) });
+            self.cr1().modify(|_, w| w.wake().bit(address_mark));
+        }
+
+        fn set_mute(&self, state: bool) {
+            self.cr1().modify(|_, w| w.rwu().bit(state));
+        }
+
+        fn is_muted(&self) -> bool {
+            self.cr1().read().rwu().bit_is_set()
+        }
+
         fn peri_address(&self) -> u32 {
             self.dr().as_ptr() as u32
         }
@@ -271,80 +488,24 @@ where {
             UART::reset_unchecked();
         }
 
-        let pclk_freq = UART::clock(clocks).raw();
+        let pclk_freq = UART::kernel_clock(clocks).raw();
         let baud = config.baudrate.0;
 
-        // The frequency to calculate USARTDIV is this:
-        //
-        // (Taken from STM32F411xC/E Reference Manual,
-        // Section 19.3.4, Equation 1)
-        //
-        // 16 bit oversample: OVER8 = 0
-        // 8 bit oversample:  OVER8 = 1
-        //
-        // USARTDIV =          (pclk)
-        //            ------------------------
-        //            8 x (2 - OVER8) x (baud)
-        //
-        // BUT, the USARTDIV has 4 "fractional" bits, which effectively
-        // means that we need to "correct" the equation as follows:
-        //
-        // USARTDIV =      (pclk) * 16
-        //            ------------------------
-        //            8 x (2 - OVER8) x (baud)
-        //
-        // When OVER8 is enabled, we can only use the lowest three
-        // fractional bits, so we'll need to shift those last four bits
-        // right one bit
-        //
-        // In IrDA Smartcard, LIN, and IrDA modes, OVER8 is always disabled.
-        //
-        // (Taken from STM32F411xC/E Reference Manual,
-        // Section 19.3.4, Equation 2)
-        //
-        // USARTDIV =   pclk
-        //            ---------
-        //            16 x baud
-        //
-        // With reference to the above, OVER8 == 0 when in Smartcard, LIN, and
-        // IrDA modes, so the register value needed for USARTDIV is the same
-        // as for 16 bit oversampling.
-
-        // Calculate correct baudrate divisor on the fly
-        let (over8, div) = if (pclk_freq / 16) >= baud || config.irda != IrdaMode::None {
-            // We have the ability to oversample to 16 bits, take
-            // advantage of it.
-            //
-            // We also add `baud / 2` to the `pclk_freq` to ensure
-            // rounding of values to the closest scale, rather than the
-            // floored behavior of normal integer division.
-            let div = (pclk_freq + (baud / 2)) / baud;
-            (false, div)
-        } else if (pclk_freq / 8) >= baud {
-            // We are close enough to pclk where we can only
-            // oversample 8.
-            //
-            // See note above regarding `baud` and rounding.
-            let div = ((pclk_freq * 2) + (baud / 2)) / baud;
-
-            // Ensure the the fractional bits (only 3) are
-            // right-aligned.
-            let frac = div & 0xF;
-            let div = (div & !0xF) | (frac >> 1);
-            (true, div)
-        } else {
-            return Err(config::InvalidConfig);
-        };
+        let (over8, div) = calculate_config_brr(pclk_freq, &config)?;
 
         let register_block = unsafe { &*UART::ptr() };
         register_block.brr().write(|w| unsafe { w.bits(div) });
 
         // Reset other registers to disable advanced USART features
         register_block.cr2().reset();
-        register_block.cr3().reset(); // IrDA configuration - see STM32F411xC/E (RM0383) sections:
-                                      // 19.3.12 "IrDA SIR ENDEC block"
-                                      // 19.6.7 "Guard time and prescaler register (USART_GTPR)"
-        if config.irda != IrdaMode::None && config.stopbits != StopBits::STOP1 {
+        register_block.cr3().reset();
+
+        // IrDA configuration - see STM32F411xC/E (RM0383) sections:
+        // 19.3.12 "IrDA SIR ENDEC block"
+        // 19.6.7 "Guard time and prescaler register (USART_GTPR)"
+        if config.irda != IrdaMode::None
+            && (config.stopbits != StopBits::STOP1 || baud > IRDA_MAX_BAUDRATE)
+        {
             return Err(config::InvalidConfig);
         }
 
@@ -354,16 +515,24 @@ where {
                 register_block.cr3().write(|w| w.iren().enabled());
                 register_block.gtpr().write(|w| w.psc().bits(1u8))
             },
-            IrdaMode::LowPower => unsafe {
+            IrdaMode::LowPower => {
+                // Low-power pulse width is 3 periods of pclk / PSC,
+                // where pclk / PSC must be between 1.42 MHz and 2.12 MHz
+                let psc = match config.irda_prescaler {
+                    Some(psc) => psc as u32,
+                    None => (pclk_freq + IRDA_LOW_POWER_FREQ / 2) / IRDA_LOW_POWER_FREQ,
+                };
+                if psc == 0 || psc > 255 {
+                    return Err(config::InvalidConfig);
+                }
                 register_block.gtpr().reset();
                 register_block
                     .cr3()
                     .write(|w| w.iren().enabled().irlp().low_power());
-                // FIXME
                 register_block
                     .gtpr()
-                    .write(|w| w.psc().bits((1843200u32 / pclk_freq) as u8))
-            },
+                    .write(|w| unsafe { w.psc().bits(psc as u8) });
+            }
             IrdaMode::None => {}
         }
 
@@ -381,22 +550,38 @@ where {
         });
 
         match config.dma {
-            DmaConfig::Tx => register_block.cr3().write(|w| w.dmat().enabled()),
-            DmaConfig::Rx => register_block.cr3().write(|w| w.dmar().enabled()),
+            DmaConfig::Tx => register_block.cr3().modify(|_, w| w.dmat().enabled()),
+            DmaConfig::Rx => register_block.cr3().modify(|_, w| w.dmar().enabled()),
             DmaConfig::TxRx => register_block
                 .cr3()
-                .write(|w| w.dmar().enabled().dmat().enabled()),
+                .modify(|_, w| w.dmar().enabled().dmat().enabled()),
             DmaConfig::None => {}
         }
 
+        register_block.enable_rts(config.flow_control.rts());
+        register_block.enable_cts(config.flow_control.cts());
+
         let serial = Serial {
             tx: Tx::new(uart, pins.0.into()),
             rx: Rx::new(pins.1.into()),
         };
         serial.tx.usart.set_stopbits(config.stopbits);
+        register_block.set_wakeup(config.wakeup);
         Ok(serial)
     }
 
+    fn enable_rts(&self, state: bool) {
+        self.cr3().modify(|_, w| w.rtse().bit(state));
+    }
+
+    fn enable_cts(&self, state: bool) {
+        self.cr3().modify(|_, w| w.ctse().bit(state));
+    }
+
+    fn enable_cts_interrupt(&self, state: bool) {
+        self.cr3().modify(|_, w| w.ctsie().bit(state));
+    }
+
     uartCommon! {}
 }
 
@@ -418,57 +603,10 @@ where {
             UART::reset_unchecked();
         }
 
-        let pclk_freq = UART::clock(clocks).raw();
+        let pclk_freq = UART::kernel_clock(clocks).raw();
         let baud = config.baudrate.0;
 
-        // The frequency to calculate USARTDIV is this:
-        //
-        // (Taken from STM32F411xC/E Reference Manual,
-        // Section 19.3.4, Equation 1)
-        //
-        // 16 bit oversample: OVER8 = 0
-        // 8 bit oversample:  OVER8 = 1
-        //
-        // USARTDIV =          (pclk)
-        //            ------------------------
-        //            8 x (2 - OVER8) x (baud)
-        //
-        // BUT, the USARTDIV has 4 "fractional" bits, which effectively
-        // means that we need to "correct" the equation as follows:
-        //
-        // USARTDIV =      (pclk) * 16
-        //            ------------------------
-        //            8 x (2 - OVER8) x (baud)
-        //
-        // When OVER8 is enabled, we can only use the lowest three
-        // fractional bits, so we'll need to shift those last four bits
-        // right one bit
-
-        // Calculate correct baudrate divisor on the fly
-        let (over8, div) = if (pclk_freq / 16) >= baud {
-            // We have the ability to oversample to 16 bits, take
-            // advantage of it.
-            //
-            // We also add `baud / 2` to the `pclk_freq` to ensure
-            // rounding of values to the closest scale, rather than the
-            // floored behavior of normal integer division.
-            let div = (pclk_freq + (baud / 2)) / baud;
-            (false, div)
-        } else if (pclk_freq / 8) >= baud {
-            // We are close enough to pclk where we can only
-            // oversample 8.
-            //
-            // See note above regarding `baud` and rounding.
-            let div = ((pclk_freq * 2) + (baud / 2)) / baud;
-
-            // Ensure the the fractional bits (only 3) are
-            // right-aligned.
-            let frac = div & 0xF;
-            let div = (div & !0xF) | (frac >> 1);
-            (true, div)
-        } else {
-            return Err(config::InvalidConfig);
-        };
+        let (over8, div) = calculate_config_brr(pclk_freq, &config)?;
 
         let register_block = unsafe { &*UART::ptr() };
         register_block.brr().write(|w| unsafe { w.bits(div) });
@@ -477,6 +615,15 @@ where {
         register_block.cr2().reset();
         register_block.cr3().reset();
 
+        // UARTs have no prescaler register, so only normal IrDA mode is available
+        match config.irda {
+            IrdaMode::Normal if config.stopbits == StopBits::STOP1 && baud <= IRDA_MAX_BAUDRATE => {
+                register_block.cr3().write(|w| w.iren().enabled())
+            }
+            IrdaMode::None => {}
+            _ => return Err(config::InvalidConfig),
+        }
+
         // Enable transmission and receiving
         // and configure frame
 
@@ -491,25 +638,54 @@ where {
         });
 
         match config.dma {
-            DmaConfig::Tx => register_block.cr3().write(|w| w.dmat().enabled()),
-            DmaConfig::Rx => register_block.cr3().write(|w| w.dmar().enabled()),
+            DmaConfig::Tx => register_block.cr3().modify(|_, w| w.dmat().enabled()),
+            DmaConfig::Rx => register_block.cr3().modify(|_, w| w.dmar().enabled()),
             DmaConfig::TxRx => register_block
                 .cr3()
-                .write(|w| w.dmar().enabled().dmat().enabled()),
+                .modify(|_, w| w.dmar().enabled().dmat().enabled()),
             DmaConfig::None => {}
         }
 
+        register_block.enable_rts(config.flow_control.rts());
+        register_block.enable_cts(config.flow_control.cts());
+
         let serial = Serial {
             tx: Tx::new(uart, pins.0.into()),
             rx: Rx::new(pins.1.into()),
         };
         serial.tx.usart.set_stopbits(config.stopbits);
+        register_block.set_wakeup(config.wakeup);
         Ok(serial)
     }
 
+    // RTSE, CTSE and CTSIE are missing in UART register description,
+    // but UARTs with RTS/CTS pins support them at the same positions as USARTs
+    fn enable_rts(&self, state: bool) {
+        uart_cr3_bit(self, 8, state);
+    }
+
+    fn enable_cts(&self, state: bool) {
+        uart_cr3_bit(self, 9, state);
+    }
+
+    fn enable_cts_interrupt(&self, state: bool) {
+        uart_cr3_bit(self, 10, state);
+    }
+
     uartCommon! {}
 }
 
+#[cfg(feature = "uart4")]
+fn uart_cr3_bit(uart: &RegisterBlockUart, bit: u8, state: bool) {
+    uart.cr3().modify(|r, w| unsafe {
+        w.bits(if state {
+            r.bits() | (1 << bit)
+        } else {
+            r.bits() & !(1 << bit)
+        })
+    });
+}
+
 impl<UART: Instance, WORD> RxISR for Serial<UART, WORD>
 where
     Rx<UART, WORD>: RxISR,
@@ -732,8 +908,8 @@ unsafe impl<UART: Instance> PeriAddress for Rx<UART, u8> {
     type MemSize = u8;
 }
 
-unsafe impl<UART: CommonPins, STREAM, const CHANNEL: u8> DMASet<STREAM, CHANNEL, PeripheralToMemory>
-    for Rx<UART>
+unsafe impl<UART: CommonPins + SerialRs232, STREAM, const CHANNEL: u8>
+    DMASet<STREAM, CHANNEL, PeripheralToMemory> for Rx<UART>
 where
     UART: DMASet<STREAM, CHANNEL, PeripheralToMemory>,
 {
@@ -751,8 +927,8 @@ where
     type MemSize = u8;
 }
 
-unsafe impl<UART: CommonPins, STREAM, const CHANNEL: u8> DMASet<STREAM, CHANNEL, MemoryToPeripheral>
-    for Tx<UART>
+unsafe impl<UART: CommonPins + SerialRs232, STREAM, const CHANNEL: u8>
+    DMASet<STREAM, CHANNEL, MemoryToPeripheral> for Tx<UART>
 where
     UART: DMASet<STREAM, CHANNEL, MemoryToPeripheral>,
 {
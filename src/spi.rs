@@ -200,6 +200,7 @@ pub trait Instance:
     + rcc::Enable
     + rcc::Reset
     + rcc::BusClock
+    + rcc::PeripheralClock
     + gpio::alt::SpiCommon
 {
     #[doc(hidden)]
@@ -217,6 +218,12 @@ macro_rules! spi {
                 <$SPI>::ptr() as *const _
             }
         }
+
+        impl rcc::PeripheralClock for $SPI {
+            fn kernel_clock(clocks: &Clocks) -> Hertz {
+                <$SPI as rcc::BusClock>::clock(clocks)
+            }
+        }
     };
 }
 
@@ -477,7 +484,7 @@ impl<SPI: Instance> Spi<SPI, false, u8> {
         let pins = (pins.0.into(), pins.1.into(), pins.2.into());
 
         Self::_new(spi, pins)
-            .pre_init(mode.into(), freq, SPI::clock(clocks))
+            .pre_init(mode.into(), freq, SPI::kernel_clock(clocks))
             .init()
     }
 }
@@ -506,7 +513,7 @@ impl<SPI: Instance> Spi<SPI, true, u8> {
         let pins = (pins.0.into(), NoPin::new().into(), pins.1.into());
 
         Self::_new(spi, pins)
-            .pre_init(mode.into(), freq, SPI::clock(clocks))
+            .pre_init(mode.into(), freq, SPI::kernel_clock(clocks))
             .init()
     }
 }
@@ -1,3 +1,15 @@
+//! System configuration controller
+//!
+//! [`SysCfg`] is needed to connect GPIO pins to EXTI lines. It also selects memory mapped
+//! at address `0x0000_0000` and controls I/O compensation cell, which reduces slew rate
+//! noise of I/O with speed above 50 MHz (like FSMC at 100 MHz).
+//!
+//! ```rust,ignore
+//! let mut syscfg = dp.SYSCFG.constrain();
+//! syscfg.enable_compensation_cell();
+//! while !syscfg.is_compensation_cell_ready() {}
+//! ```
+
 use crate::pac::SYSCFG;
 use crate::rcc::Enable;
 use core::fmt;
@@ -43,3 +55,66 @@ impl fmt::Debug for SysCfg {
         f.debug_struct("SysCfg").finish()
     }
 }
+
+/// Memory mapped at address `0x0000_0000`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum MemoryMapping {
+    /// Main flash memory
+    MainFlash = 0b000,
+    /// System flash memory (bootloader)
+    SystemFlash = 0b001,
+    /// FSMC/FMC bank 1 (NOR/PSRAM 1 and 2)
+    #[cfg(any(feature = "fsmc", feature = "fmc"))]
+    Fsmc = 0b010,
+    /// Embedded SRAM1
+    Sram = 0b011,
+    /// FMC SDRAM bank 1
+    #[cfg(feature = "fmc")]
+    FmcSdram = 0b100,
+}
+
+impl SysCfg {
+    /// Maps `memory` at address `0x0000_0000`
+    ///
+    /// Boot pins select the initial mapping. Code running from flash at its own address
+    /// (`0x0800_0000`) is not affected.
+    pub fn remap_memory(&mut self, memory: MemoryMapping) {
+        self.0
+            .memrm()
+            .modify(|_, w| unsafe { w.mem_mode().bits(memory as u8) });
+    }
+
+    /// Memory mapped at address `0x0000_0000`, `None` for reserved values
+    pub fn memory_mapping(&self) -> Option<MemoryMapping> {
+        Some(match self.0.memrm().read().mem_mode().bits() {
+            0b000 => MemoryMapping::MainFlash,
+            0b001 => MemoryMapping::SystemFlash,
+            #[cfg(any(feature = "fsmc", feature = "fmc"))]
+            0b010 => MemoryMapping::Fsmc,
+            0b011 => MemoryMapping::Sram,
+            #[cfg(feature = "fmc")]
+            0b100 => MemoryMapping::FmcSdram,
+            _ => return None,
+        })
+    }
+
+    /// Enables I/O compensation cell
+    ///
+    /// Can be used only with supply voltage from 2.4 to 3.6 V. The cell is ready for use
+    /// when [`is_compensation_cell_ready`](Self::is_compensation_cell_ready) returns `true`.
+    pub fn enable_compensation_cell(&mut self) {
+        self.0.cmpcr().modify(|_, w| w.cmp_pd().set_bit());
+    }
+
+    /// Disables I/O compensation cell (power-down mode)
+    pub fn disable_compensation_cell(&mut self) {
+        self.0.cmpcr().modify(|_, w| w.cmp_pd().clear_bit());
+    }
+
+    /// Is I/O compensation cell ready?
+    pub fn is_compensation_cell_ready(&self) -> bool {
+        self.0.cmpcr().read().ready().bit_is_set()
+    }
+}
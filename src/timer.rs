@@ -26,6 +26,32 @@ pub use pwm::*;
 pub mod pwm_input;
 #[cfg(not(feature = "gpio-f410"))]
 pub use pwm_input::PwmInput;
+pub mod capture;
+pub use capture::{CaptureConfig, CaptureDma, CaptureEdge};
+pub mod one_pulse;
+pub use one_pulse::OnePulse;
+pub mod burst;
+pub use burst::{BurstRegister, PwmBurst};
+pub mod duty_dma;
+pub use duty_dma::PwmDutyDma;
+pub mod slave;
+pub use slave::{InternalTrigger, SlaveMode, TriggerSource};
+pub mod ext_clock;
+pub use ext_clock::{EtrConfig, EtrPrescaler, PulseCounter};
+pub mod wide;
+pub use wide::WideTimer;
+pub mod adc_trigger;
+pub use adc_trigger::{AdcTrigger, CompareEvent, TrgoEvent};
+pub mod pulse_train;
+pub use pulse_train::PulseTrain;
+#[cfg(feature = "timer-callbacks")]
+pub mod callbacks;
+#[cfg(feature = "timer-callbacks")]
+pub use callbacks::{Callback, Callbacks};
+#[cfg(feature = "systick-uptime")]
+pub mod uptime;
+#[cfg(feature = "systick-uptime")]
+pub use uptime::{uptime, SysInstant, SysUptime, SysUptimeExt};
 #[cfg(feature = "rtic1")]
 pub mod monotonic;
 #[cfg(feature = "rtic1")]
@@ -35,7 +61,8 @@ pub use monotonic::*;
     feature = "rtic-tim2",
     feature = "rtic-tim3",
     feature = "rtic-tim4",
-    feature = "rtic-tim5"
+    feature = "rtic-tim5",
+    feature = "rtic-systick"
 ))]
 pub mod monotonics;
 #[cfg(feature = "rtic2")]
@@ -43,7 +70,8 @@ pub mod monotonics;
     feature = "rtic-tim2",
     feature = "rtic-tim3",
     feature = "rtic-tim4",
-    feature = "rtic-tim5"
+    feature = "rtic-tim5",
+    feature = "rtic-systick"
 ))]
 pub use monotonics::*;
 
@@ -72,7 +100,20 @@ pub enum Channel {
     C4 = 3,
 }
 
+impl Channel {
+    pub(crate) const fn from_index(c: u8) -> Self {
+        match c {
+            0 => Self::C1,
+            1 => Self::C2,
+            2 => Self::C3,
+            _ => Self::C4,
+        }
+    }
+}
+
+pub use crate::gpio::alt::TimBkin as BkinPin;
 pub use crate::gpio::alt::TimCPin as CPin;
+pub use crate::gpio::alt::TimEtr as EtrPin;
 pub use crate::gpio::alt::TimNCPin as NCPin;
 
 /// Channel wrapper
@@ -306,6 +347,22 @@ pub enum Ocm {
     PwmMode2 = 7,
 }
 
+/// Counter alignment of PWM
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Alignment {
+    /// Edge-aligned, counting up
+    EdgeUp,
+    /// Edge-aligned, counting down
+    EdgeDown,
+    /// Center-aligned, compare interrupt flags are set only when counting down
+    Center1,
+    /// Center-aligned, compare interrupt flags are set only when counting up
+    Center2,
+    /// Center-aligned, compare interrupt flags are set both when counting up and down
+    Center3,
+}
+
 /// Wrapper type that indicates which register of the contained timer to use for DMA.
 pub struct CCR<T, const C: u8>(T);
 pub type CCR1<T> = CCR<T, 0>;
@@ -317,7 +374,10 @@ pub type CCR4<T> = CCR<T, 3>;
 pub struct DMAR<T>(T);
 
 mod sealed {
-    use super::{BitFlags, Channel, Event, Flag, IdleState, Ocm, Polarity};
+    use super::{
+        Alignment, BitFlags, Channel, Event, Flag, IdleState, Ocm, Polarity, SlaveMode,
+        TriggerSource,
+    };
     pub trait General {
         type Width: Into<u32> + From<u16>;
         fn max_auto_reload() -> u32;
@@ -338,9 +398,13 @@ mod sealed {
         );
         fn clear_interrupt_flag(&mut self, event: BitFlags<Flag>);
         fn get_interrupt_flag(&self) -> BitFlags<Flag>;
+        fn get_listened_event(&self) -> BitFlags<Event>;
+        unsafe fn steal() -> Self;
         fn read_count(&self) -> Self::Width;
         fn write_count(&mut self, value: Self::Width);
         fn start_one_pulse(&mut self);
+        fn set_one_pulse(&mut self, b: bool);
+        fn disable_update_event(&mut self, b: bool);
         fn start_free(&mut self, update: bool);
         fn cr1_reset(&mut self);
         fn cnt_reset(&mut self);
@@ -354,6 +418,9 @@ mod sealed {
         fn enable_channel(channel: u8, b: bool);
         fn set_channel_polarity(channel: u8, p: Polarity);
         fn set_nchannel_polarity(channel: u8, p: Polarity);
+        fn read_channel_polarity(channel: u8) -> Polarity;
+        fn read_nchannel_polarity(channel: u8) -> Polarity;
+        fn take_overcapture(channel: u8) -> bool;
     }
 
     pub trait Advanced: WithPwmCommon {
@@ -361,22 +428,60 @@ mod sealed {
         fn set_dtg_value(value: u8);
         fn read_dtg_value() -> u8;
         fn idle_state(channel: u8, comp: bool, s: IdleState);
+        fn read_idle_state(channel: u8, comp: bool) -> IdleState;
+        fn set_off_state(run: bool, idle: bool);
+        fn enable_break(polarity: Polarity, automatic_output: bool);
+        fn disable_break();
+        fn is_main_output_enabled() -> bool;
     }
 
     pub trait WithPwm: WithPwmCommon {
         fn preload_output_channel_in_mode(&mut self, channel: Channel, mode: Ocm);
+        fn set_output_mode(channel: u8, mode: Ocm);
+        fn enable_cc_preload(channel: u8, b: bool);
+        fn is_cc_preload_enabled(channel: u8) -> bool;
+        fn set_input_capture(&mut self, channel: Channel, filter: u8, prescaler: u8);
+        fn enable_cc_dma(&mut self, channel: Channel, b: bool);
+        fn enable_main_output(&mut self, b: bool);
+        fn max_repetition() -> u32;
+        fn set_repetition(&mut self, rcr: u8);
+        fn is_center_aligned() -> bool;
         fn start_pwm(&mut self);
     }
 
+    pub trait WithDmar: General {
+        fn set_dma_burst(&mut self, base: u8, length: u8);
+        fn enable_update_dma(&mut self, b: bool);
+        fn cc_dma_on_update(&mut self, b: bool);
+    }
+
+    pub trait CenterAligned: WithPwm {
+        fn set_alignment(&mut self, alignment: Alignment);
+    }
+
     pub trait MasterTimer: General {
         type Mms;
         fn master_mode(&mut self, mode: Self::Mms);
+        fn master_mode_update(&mut self);
+    }
+
+    pub trait SlaveTimer: General {
+        fn slave_mode(&mut self, mode: SlaveMode, trigger: TriggerSource);
+        fn master_slave_sync(&mut self, b: bool);
+    }
+
+    pub trait WithEtr: SlaveTimer {
+        fn external_trigger(&mut self, prescaler: u8, filter: u8, inverted: bool);
+        fn enable_external_clock2(&mut self, b: bool);
     }
 }
-pub(crate) use sealed::{Advanced, General, MasterTimer, WithPwm, WithPwmCommon};
+pub(crate) use sealed::{
+    Advanced, CenterAligned, General, MasterTimer, SlaveTimer, WithDmar, WithEtr, WithPwm,
+    WithPwmCommon,
+};
 
 pub trait Instance:
-    crate::Sealed + rcc::Enable + rcc::Reset + rcc::BusTimerClock + General
+    crate::Sealed + rcc::Enable + rcc::Reset + rcc::BusTimerClock + rcc::PeripheralClock + General
 {
 }
 
@@ -389,6 +494,11 @@ macro_rules! hal {
         $(m: $timbase:ident,)?
     ]) => {
         impl Instance for $TIM { }
+        impl rcc::PeripheralClock for $TIM {
+            fn kernel_clock(clocks: &Clocks) -> Hertz {
+                <$TIM as rcc::BusTimerClock>::timer_clock(clocks)
+            }
+        }
         pub type $Timer = Timer<$TIM>;
 
         impl General for $TIM {
@@ -469,6 +579,14 @@ macro_rules! hal {
                 BitFlags::from_bits_truncate(self.sr().read().bits())
             }
             #[inline(always)]
+            fn get_listened_event(&self) -> BitFlags<Event> {
+                BitFlags::from_bits_truncate(self.dier().read().bits())
+            }
+            #[inline(always)]
+            unsafe fn steal() -> Self {
+                <$TIM>::steal()
+            }
+            #[inline(always)]
             fn read_count(&self) -> Self::Width {
                 self.cnt().read().bits() as Self::Width
             }
@@ -481,6 +599,14 @@ macro_rules! hal {
                 self.cr1().modify(|_, w| unsafe { w.bits(1 << 3) }.cen().set_bit());
             }
             #[inline(always)]
+            fn set_one_pulse(&mut self, b: bool) {
+                self.cr1().modify(|_, w| w.opm().bit(b));
+            }
+            #[inline(always)]
+            fn disable_update_event(&mut self, b: bool) {
+                self.cr1().modify(|_, w| w.udis().bit(b));
+            }
+            #[inline(always)]
             fn start_free(&mut self, update: bool) {
                 self.cr1().modify(|_, w| w.cen().set_bit().udis().bit(!update));
             }
@@ -542,6 +668,38 @@ macro_rules! hal {
                         unsafe { bb::write(tim.ccer(), c*4 + 3, p == Polarity::ActiveLow); }
                     }
                 }
+
+                #[inline(always)]
+                fn read_channel_polarity(c: u8) -> Polarity {
+                    let tim = unsafe { &*<$TIM>::ptr() };
+                    if tim.ccer().read().bits() & (1 << (c*4 + 1)) != 0 {
+                        Polarity::ActiveLow
+                    } else {
+                        Polarity::ActiveHigh
+                    }
+                }
+
+                #[inline(always)]
+                fn read_nchannel_polarity(c: u8) -> Polarity {
+                    let tim = unsafe { &*<$TIM>::ptr() };
+                    if c < Self::COMP_CH_NUMBER && tim.ccer().read().bits() & (1 << (c*4 + 3)) != 0 {
+                        Polarity::ActiveLow
+                    } else {
+                        Polarity::ActiveHigh
+                    }
+                }
+
+                #[inline(always)]
+                fn take_overcapture(c: u8) -> bool {
+                    let tim = unsafe { &*<$TIM>::ptr() };
+                    let bit = 1 << (c + 9);
+                    if c < Self::CH_NUMBER && tim.sr().read().bits() & bit != 0 {
+                        tim.sr().write(|w| unsafe { w.bits(0xffff & !bit) });
+                        true
+                    } else {
+                        false
+                    }
+                }
             }
 
             $(
@@ -573,6 +731,37 @@ macro_rules! hal {
                             }
                         }
                     }
+                    fn read_idle_state(c: u8, comp: bool) -> IdleState {
+                        let tim = unsafe { &*<$TIM>::ptr() };
+                        if tim.cr2().read().bits() & (1 << (c*2 + 8 + comp as u8)) != 0 {
+                            IdleState::Set
+                        } else {
+                            IdleState::Reset
+                        }
+                    }
+                    fn set_off_state(run: bool, idle: bool) {
+                        let tim = unsafe { &*<$TIM>::ptr() };
+                        tim.bdtr().modify(|_, w| {
+                            w.ossr().bit(run);
+                            w.ossi().bit(idle)
+                        });
+                    }
+                    fn enable_break(polarity: Polarity, automatic_output: bool) {
+                        let tim = unsafe { &*<$TIM>::ptr() };
+                        tim.bdtr().modify(|_, w| {
+                            w.bke().set_bit();
+                            w.bkp().bit(polarity == Polarity::ActiveHigh);
+                            w.aoe().bit(automatic_output)
+                        });
+                    }
+                    fn disable_break() {
+                        let tim = unsafe { &*<$TIM>::ptr() };
+                        tim.bdtr().modify(|_, w| w.bke().clear_bit());
+                    }
+                    fn is_main_output_enabled() -> bool {
+                        let tim = unsafe { &*<$TIM>::ptr() };
+                        tim.bdtr().read().moe().bit_is_set()
+                    }
                 }
             )?
 
@@ -592,6 +781,9 @@ macro_rules! hal {
             fn master_mode(&mut self, mode: Self::Mms) {
                 self.cr2().modify(|_,w| w.mms().variant(mode));
             }
+            fn master_mode_update(&mut self) {
+                self.cr2().modify(|_,w| w.mms().variant(pac::$timbase::cr2::MMS::Update));
+            }
         })?
     };
 }
@@ -607,11 +799,32 @@ macro_rules! with_dmar {
 
             type MemSize = $memsize;
         }
+
+        impl WithDmar for $TIM {
+            #[inline(always)]
+            fn set_dma_burst(&mut self, base: u8, length: u8) {
+                // DBL is number of transfers minus one
+                self.dcr()
+                    .write(|w| unsafe { w.bits((((length - 1) as u32) << 8) | base as u32) });
+            }
+
+            #[inline(always)]
+            fn enable_update_dma(&mut self, b: bool) {
+                unsafe {
+                    bb::write(self.dier(), 8, b);
+                }
+            }
+
+            #[inline(always)]
+            fn cc_dma_on_update(&mut self, b: bool) {
+                self.cr2().modify(|_, w| w.ccds().bit(b));
+            }
+        }
     };
 }
 
 macro_rules! with_pwm {
-    ($TIM:ty: [$($Cx:ident, $ccmrx_output:ident, $ocxpe:ident, $ocxm:ident;)+] $(, $aoe:ident)?) => {
+    ($TIM:ty: [$($Cx:ident, $ccmrx_output:ident, $ccmrx_input:ident, $ocxpe:ident, $ocxm:ident;)+] $(, $aoe:ident)?) => {
         impl WithPwm for $TIM {
             #[inline(always)]
             fn preload_output_channel_in_mode(&mut self, channel: Channel, mode: Ocm) {
@@ -627,6 +840,94 @@ macro_rules! with_pwm {
                 }
             }
 
+            #[inline(always)]
+            fn set_output_mode(c: u8, mode: Ocm) {
+                let tim = unsafe { &*<$TIM>::ptr() };
+                match Channel::from_index(c) {
+                    $(
+                        Channel::$Cx => {
+                            tim.$ccmrx_output().modify(|_, w| w.$ocxm().set(mode as _));
+                        }
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => {},
+                }
+            }
+
+            #[inline(always)]
+            fn enable_cc_preload(c: u8, b: bool) {
+                let tim = unsafe { &*<$TIM>::ptr() };
+                match Channel::from_index(c) {
+                    $(
+                        Channel::$Cx => {
+                            tim.$ccmrx_output().modify(|_, w| w.$ocxpe().bit(b));
+                        }
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => {},
+                }
+            }
+
+            #[inline(always)]
+            fn is_cc_preload_enabled(c: u8) -> bool {
+                let tim = unsafe { &*<$TIM>::ptr() };
+                match Channel::from_index(c) {
+                    $(
+                        Channel::$Cx => tim.$ccmrx_output().read().$ocxpe().bit_is_set(),
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => false,
+                }
+            }
+
+            #[inline(always)]
+            fn set_input_capture(&mut self, channel: Channel, filter: u8, prescaler: u8) {
+                // CCxS = 01 (TIx input), ICxPSC, ICxF
+                let offset = (channel as u8 % 2) * 8;
+                let bits = (0b01 | ((prescaler as u32 & 0b11) << 2) | ((filter as u32 & 0xf) << 4)) << offset;
+                match channel {
+                    $(
+                        Channel::$Cx => {
+                            self.$ccmrx_input()
+                            .modify(|r, w| unsafe { w.bits((r.bits() & !(0xff << offset)) | bits) });
+                        }
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => {},
+                }
+            }
+
+            #[inline(always)]
+            fn enable_cc_dma(&mut self, channel: Channel, b: bool) {
+                unsafe { bb::write(self.dier(), channel as u8 + 9, b); }
+            }
+
+            #[inline(always)]
+            fn enable_main_output(&mut self, _b: bool) {
+                $(let $aoe = self.bdtr().modify(|_, w| w.moe().bit(_b));)?
+            }
+
+            #[inline(always)]
+            fn max_repetition() -> u32 {
+                // Only advanced timers have repetition counter
+                #[allow(unused_mut)]
+                let mut max = 1;
+                $(let $aoe = (); max = 256;)?
+                max
+            }
+
+            #[inline(always)]
+            fn set_repetition(&mut self, _rcr: u8) {
+                $(let $aoe = self.rcr().write(|w| unsafe { w.bits(_rcr as u32) });)?
+            }
+
+            #[inline(always)]
+            fn is_center_aligned() -> bool {
+                let tim = unsafe { &*<$TIM>::ptr() };
+                // CMS bits, reserved on timers without center-aligned mode
+                tim.cr1().read().bits() & (0b11 << 5) != 0
+            }
+
             #[inline(always)]
             fn start_pwm(&mut self) {
                 $(let $aoe = self.bdtr().modify(|_, w| w.aoe().set_bit());)?
@@ -636,22 +937,36 @@ macro_rules! with_pwm {
     };
     ($TIM:ty: 1) => {
         with_pwm!($TIM: [
-            C1, ccmr1_output, oc1pe, oc1m;
+            C1, ccmr1_output, ccmr1_input, oc1pe, oc1m;
         ]);
     };
     ($TIM:ty: 2) => {
         with_pwm!($TIM: [
-            C1, ccmr1_output, oc1pe, oc1m;
-            C2, ccmr1_output, oc2pe, oc2m;
+            C1, ccmr1_output, ccmr1_input, oc1pe, oc1m;
+            C2, ccmr1_output, ccmr1_input, oc2pe, oc2m;
         ]);
     };
     ($TIM:ty: 4 $(, $aoe:ident)?) => {
         with_pwm!($TIM: [
-            C1, ccmr1_output, oc1pe, oc1m;
-            C2, ccmr1_output, oc2pe, oc2m;
-            C3, ccmr2_output, oc3pe, oc3m;
-            C4, ccmr2_output, oc4pe, oc4m;
+            C1, ccmr1_output, ccmr1_input, oc1pe, oc1m;
+            C2, ccmr1_output, ccmr1_input, oc2pe, oc2m;
+            C3, ccmr2_output, ccmr2_input, oc3pe, oc3m;
+            C4, ccmr2_output, ccmr2_input, oc4pe, oc4m;
         ] $(, $aoe)?);
+
+        impl CenterAligned for $TIM {
+            #[inline(always)]
+            fn set_alignment(&mut self, alignment: Alignment) {
+                let (cms, dir) = match alignment {
+                    Alignment::EdgeUp => (0b00, false),
+                    Alignment::EdgeDown => (0b00, true),
+                    Alignment::Center1 => (0b01, false),
+                    Alignment::Center2 => (0b10, false),
+                    Alignment::Center3 => (0b11, false),
+                };
+                self.cr1().modify(|_, w| unsafe { w.cms().bits(cms).dir().bit(dir) });
+            }
+        }
     };
 }
 
@@ -665,13 +980,13 @@ impl<TIM: Instance> Timer<TIM> {
         }
 
         Self {
-            clk: TIM::timer_clock(clocks),
+            clk: TIM::kernel_clock(clocks),
             tim,
         }
     }
 
     pub fn configure(&mut self, clocks: &Clocks) {
-        self.clk = TIM::timer_clock(clocks);
+        self.clk = TIM::kernel_clock(clocks);
     }
 
     pub fn counter_hz(self) -> CounterHz<TIM> {
@@ -689,6 +1004,26 @@ impl<TIM: Instance + MasterTimer> Timer<TIM> {
     }
 }
 
+impl<TIM: Instance + SlaveTimer> Timer<TIM> {
+    /// Configures slave mode controller with `trigger` input
+    pub fn set_slave_mode(&mut self, mode: SlaveMode, trigger: TriggerSource) {
+        self.tim.slave_mode(mode, trigger)
+    }
+
+    /// Configures slave mode controller triggered by TRGO of `MASTER` timer
+    pub fn set_slave_of<MASTER>(&mut self, mode: SlaveMode)
+    where
+        TIM: InternalTrigger<MASTER>,
+    {
+        self.tim.slave_mode(mode, TIM::ITR)
+    }
+
+    /// Delays trigger output to synchronize slave timers with this one (MSM)
+    pub fn enable_master_slave_sync(&mut self, b: bool) {
+        self.tim.master_slave_sync(b)
+    }
+}
+
 /// Timer wrapper for fixed precision timers.
 ///
 /// Uses `fugit::TimerDurationU32` for most of operations
@@ -720,7 +1055,7 @@ impl<TIM: Instance, const FREQ: u32> FTimer<TIM, FREQ> {
 
     /// Calculate prescaler depending on `Clocks` state
     pub fn configure(&mut self, clocks: &Clocks) {
-        let clk = TIM::timer_clock(clocks);
+        let clk = TIM::kernel_clock(clocks);
         assert!(clk.raw() % FREQ == 0);
         let psc = clk.raw() / FREQ;
         self.tim.set_prescaler(u16::try_from(psc - 1).unwrap());
@@ -748,6 +1083,26 @@ impl<TIM: Instance + MasterTimer, const FREQ: u32> FTimer<TIM, FREQ> {
     }
 }
 
+impl<TIM: Instance + SlaveTimer, const FREQ: u32> FTimer<TIM, FREQ> {
+    /// Configures slave mode controller with `trigger` input
+    pub fn set_slave_mode(&mut self, mode: SlaveMode, trigger: TriggerSource) {
+        self.tim.slave_mode(mode, trigger)
+    }
+
+    /// Configures slave mode controller triggered by TRGO of `MASTER` timer
+    pub fn set_slave_of<MASTER>(&mut self, mode: SlaveMode)
+    where
+        TIM: InternalTrigger<MASTER>,
+    {
+        self.tim.slave_mode(mode, TIM::ITR)
+    }
+
+    /// Delays trigger output to synchronize slave timers with this one (MSM)
+    pub fn enable_master_slave_sync(&mut self, b: bool) {
+        self.tim.master_slave_sync(b)
+    }
+}
+
 #[inline(always)]
 pub(crate) const fn compute_arr_presc(freq: u32, clock: u32) -> (u16, u32) {
     let ticks = clock / freq;
@@ -756,61 +1111,74 @@ pub(crate) const fn compute_arr_presc(freq: u32, clock: u32) -> (u16, u32) {
     (psc as u16, arr)
 }
 
-impl<TIM: Instance> crate::Listen for Timer<TIM> {
-    type Event = Event;
-    fn listen(&mut self, event: impl Into<BitFlags<Event>>) {
-        self.tim.listen_event(None, Some(event.into()));
-    }
-    fn listen_only(&mut self, event: impl Into<BitFlags<Event>>) {
-        self.tim
-            .listen_event(Some(BitFlags::ALL), Some(event.into()));
-    }
-    fn unlisten(&mut self, event: impl Into<BitFlags<Event>>) {
-        self.tim.listen_event(Some(event.into()), None);
-    }
-}
-
-impl<TIM: Instance, const FREQ: u32> crate::Listen for FTimer<TIM, FREQ> {
-    type Event = Event;
-    fn listen(&mut self, event: impl Into<BitFlags<Event>>) {
-        self.tim.listen_event(None, Some(event.into()));
-    }
-    fn listen_only(&mut self, event: impl Into<BitFlags<Event>>) {
-        self.tim
-            .listen_event(Some(BitFlags::ALL), Some(event.into()));
-    }
-    fn unlisten(&mut self, event: impl Into<BitFlags<Event>>) {
-        self.tim.listen_event(Some(event.into()), None);
-    }
+/// Same as [`compute_arr_presc`] for center-aligned counter, which counts up to ARR and back in one period
+#[inline(always)]
+pub(crate) const fn compute_arr_presc_center(freq: u32, clock: u32) -> (u16, u32) {
+    let ticks = clock / freq / 2;
+    let psc = ticks / (1 << 16);
+    let arr = ticks / (psc + 1);
+    (psc as u16, arr)
 }
 
-impl<TIM: Instance> crate::ClearFlags for Timer<TIM> {
-    type Flag = Flag;
-    fn clear_flags(&mut self, event: impl Into<BitFlags<Flag>>) {
-        self.tim.clear_interrupt_flag(event.into());
-    }
-}
+/// Implements `Listen`, `ClearFlags` and `ReadFlags` for timer or a driver owning it
+///
+/// Timer is accessed through the given field path, or stolen with `steal`
+/// if the driver doesn't keep it accessible (e.g. it's owned by DMA transfer).
+macro_rules! impl_listen_flags {
+    ([$($gen:tt)*] $ty:ty $(where [$($bound:tt)*])? => $($tim:tt)+) => {
+        impl<$($gen)*> $crate::Listen for $ty $(where $($bound)*)? {
+            type Event = $crate::timer::Event;
+            fn listen(&mut self, event: impl Into<enumflags2::BitFlags<Self::Event>>) {
+                $crate::timer::General::listen_event(
+                    &mut $crate::timer::impl_listen_flags!(@tim self, $($tim)+),
+                    None,
+                    Some(event.into()),
+                );
+            }
+            fn listen_only(&mut self, event: impl Into<enumflags2::BitFlags<Self::Event>>) {
+                $crate::timer::General::listen_event(
+                    &mut $crate::timer::impl_listen_flags!(@tim self, $($tim)+),
+                    Some(enumflags2::BitFlags::ALL),
+                    Some(event.into()),
+                );
+            }
+            fn unlisten(&mut self, event: impl Into<enumflags2::BitFlags<Self::Event>>) {
+                $crate::timer::General::listen_event(
+                    &mut $crate::timer::impl_listen_flags!(@tim self, $($tim)+),
+                    Some(event.into()),
+                    None,
+                );
+            }
+        }
 
-impl<TIM: Instance> crate::ReadFlags for Timer<TIM> {
-    type Flag = Flag;
-    fn flags(&self) -> BitFlags<Flag> {
-        self.tim.get_interrupt_flag()
-    }
-}
+        impl<$($gen)*> $crate::ClearFlags for $ty $(where $($bound)*)? {
+            type Flag = $crate::timer::Flag;
+            fn clear_flags(&mut self, flags: impl Into<enumflags2::BitFlags<Self::Flag>>) {
+                $crate::timer::General::clear_interrupt_flag(
+                    &mut $crate::timer::impl_listen_flags!(@tim self, $($tim)+),
+                    flags.into(),
+                );
+            }
+        }
 
-impl<TIM: Instance, const FREQ: u32> crate::ClearFlags for FTimer<TIM, FREQ> {
-    type Flag = Flag;
-    fn clear_flags(&mut self, event: impl Into<BitFlags<Flag>>) {
-        self.tim.clear_interrupt_flag(event.into());
-    }
+        impl<$($gen)*> $crate::ReadFlags for $ty $(where $($bound)*)? {
+            type Flag = $crate::timer::Flag;
+            fn flags(&self) -> enumflags2::BitFlags<Self::Flag> {
+                $crate::timer::General::get_interrupt_flag(&$crate::timer::impl_listen_flags!(@tim self, $($tim)+))
+            }
+        }
+    };
+    (@tim $self:ident, steal) => {
+        unsafe { TIM::steal() }
+    };
+    (@tim $self:ident, $($field:ident).+) => {
+        $self.$($field).+
+    };
 }
+pub(crate) use impl_listen_flags;
 
-impl<TIM: Instance, const FREQ: u32> crate::ReadFlags for FTimer<TIM, FREQ> {
-    type Flag = Flag;
-    fn flags(&self) -> BitFlags<Flag> {
-        self.tim.get_interrupt_flag()
-    }
-}
+impl_listen_flags!([TIM: Instance] Timer<TIM> => tim);
+impl_listen_flags!([TIM: Instance, const FREQ: u32] FTimer<TIM, FREQ> => tim);
 
 #[cfg(not(feature = "gpio-f410"))]
 #[cfg(feature = "tim1")]
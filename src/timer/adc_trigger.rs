@@ -0,0 +1,105 @@
+//! Starting ADC conversions by timer events
+//!
+//! ADC regular conversions can be started by some timer events: trigger output (TRGO)
+//! or compare events of specific channels. Only fixed pairs are available,
+//! [`AdcTrigger`] is implemented for them, so a wrong combination is a compile error.
+//!
+//! ```rust,ignore
+//! let mut timer = dp.TIM2.counter_hz(&clocks);
+//! let trigger = timer.adc_trigger_update();
+//! let config = AdcConfig::default().external_trigger(TriggerMode::RisingEdge, trigger);
+//! let mut adc = Adc::adc1(dp.ADC1, true, config);
+//! // ...
+//! timer.start(8.kHz()).unwrap();
+//! ```
+
+use super::{Channel, FTimer, Instance, MasterTimer, Ocm, Timer, WithPwm};
+use crate::adc::config::ExternalTrigger;
+use crate::pac;
+
+/// Update event sent through trigger output (TRGO)
+pub struct TrgoEvent;
+/// Compare event of channel `C`
+pub struct CompareEvent<const C: u8>;
+
+/// Timer `EVENT` is connected to `EXTSEL` trigger of ADC regular group
+pub trait AdcTrigger<EVENT>: Instance {
+    const EXTSEL: ExternalTrigger;
+}
+
+macro_rules! adc_trigger {
+    ($TIM:ty: $($EVENT:ty => $EXTSEL:ident),+) => {
+        $(
+            impl AdcTrigger<$EVENT> for $TIM {
+                const EXTSEL: ExternalTrigger = ExternalTrigger::$EXTSEL;
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "tim1")]
+adc_trigger!(pac::TIM1:
+    CompareEvent<0> => Tim_1_cc_1,
+    CompareEvent<1> => Tim_1_cc_2,
+    CompareEvent<2> => Tim_1_cc_3
+);
+#[cfg(feature = "tim2")]
+adc_trigger!(pac::TIM2:
+    CompareEvent<1> => Tim_2_cc_2,
+    CompareEvent<2> => Tim_2_cc_3,
+    CompareEvent<3> => Tim_2_cc_4,
+    TrgoEvent => Tim_2_trgo
+);
+#[cfg(feature = "tim3")]
+adc_trigger!(pac::TIM3:
+    CompareEvent<0> => Tim_3_cc_1,
+    TrgoEvent => Tim_3_trgo
+);
+#[cfg(feature = "tim4")]
+adc_trigger!(pac::TIM4: CompareEvent<3> => Tim_4_cc_4);
+#[cfg(feature = "tim5")]
+adc_trigger!(pac::TIM5:
+    CompareEvent<0> => Tim_5_cc_1,
+    CompareEvent<1> => Tim_5_cc_2,
+    CompareEvent<2> => Tim_5_cc_3
+);
+#[cfg(feature = "tim8")]
+adc_trigger!(pac::TIM8:
+    CompareEvent<0> => Tim_8_cc_1,
+    TrgoEvent => Tim_8_trgo
+);
+
+macro_rules! impl_adc_trigger {
+    ($Timer:ident $(, $FREQ:ident)?) => {
+        impl<TIM: Instance $(, const $FREQ: u32)?> $Timer<TIM $(, $FREQ)?> {
+            /// Sends update events to trigger output and returns corresponding ADC trigger
+            pub fn adc_trigger_update(&mut self) -> ExternalTrigger
+            where
+                TIM: AdcTrigger<TrgoEvent> + MasterTimer,
+            {
+                self.tim.master_mode_update();
+                TIM::EXTSEL
+            }
+
+            /// Generates compare event of channel `C` when counter reaches `compare` in each period
+            /// and returns corresponding ADC trigger (use with rising edge)
+            ///
+            /// Channel is configured as PWM output which becomes active at `compare`,
+            /// so the pin (if connected) shows trigger edges.
+            pub fn adc_trigger_compare<const C: u8>(&mut self, compare: u32) -> ExternalTrigger
+            where
+                TIM: AdcTrigger<CompareEvent<C>> + WithPwm,
+            {
+                self.tim
+                    .preload_output_channel_in_mode(Channel::from_index(C), Ocm::PwmMode2);
+                TIM::set_cc_value(C, compare);
+                TIM::enable_channel(C, true);
+                self.tim.enable_main_output(true);
+                TIM::EXTSEL
+            }
+        }
+    };
+}
+
+impl_adc_trigger!(Timer);
+impl_adc_trigger!(FTimer, FREQ);
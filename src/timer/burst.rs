@@ -0,0 +1,152 @@
+//! Timer DMA burst
+//!
+//! On each update event timer requests DMA to write a group of consecutive timer registers
+//! (for example `ARR` and `CCRx`) through `DMAR` register. Streaming a buffer of such groups
+//! produces arbitrary waveforms or acceleration profiles without CPU involvement.
+//!
+//! Buffer contains `registers` values per update event, in order of register addresses.
+//!
+//! ```rust,ignore
+//! // Change period and duty of channel 1 on each update: [ARR, RCR, CCR1] per event
+//! static mut PROFILE: [u32; 3 * 64] = [0; 3 * 64];
+//!
+//! let pwm = dp.TIM1.pwm_us(Channel1::new(gpioa.pa8), 100.micros(), &clocks);
+//! let burst = pwm.dma_burst::<_, 6, _>(
+//!     streams.5,
+//!     unsafe { &mut PROFILE },
+//!     BurstRegister::Arr,
+//!     3,
+//!     false,
+//! );
+//! ```
+
+use core::marker::PhantomData;
+
+use embedded_dma::ReadBuffer;
+
+use super::{FTimer, Instance, Pins, Pwm, WithDmar, WithPwm, DMAR};
+use crate::dma::{
+    config::DmaConfig,
+    traits::{Channel as DmaChannel, DMASet, PeriAddress, Stream, StreamISR},
+    ChannelX, MemoryToPeripheral, Transfer,
+};
+
+/// First timer register written by DMA burst
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum BurstRegister {
+    Psc = 10,
+    Arr = 11,
+    /// Repetition counter, only on advanced timers
+    Rcr = 12,
+    Ccr1 = 13,
+    Ccr2 = 14,
+    Ccr3 = 15,
+    Ccr4 = 16,
+}
+
+/// PWM which registers are updated by DMA burst on each update event
+pub struct PwmBurst<TIM, PINS, const FREQ: u32, STREAM, const DMA_CH: u8, BUF>
+where
+    TIM: Instance + WithPwm + WithDmar,
+    PINS: Pins<TIM>,
+    STREAM: Stream,
+    DMAR<TIM>: PeriAddress,
+{
+    transfer: Transfer<STREAM, DMA_CH, DMAR<TIM>, MemoryToPeripheral, BUF>,
+    circular: bool,
+    _pins: PhantomData<PINS>,
+}
+
+impl<TIM, PINS, const FREQ: u32> Pwm<TIM, PINS, FREQ>
+where
+    TIM: Instance + WithPwm + WithDmar,
+    PINS: Pins<TIM>,
+{
+    /// Starts writing `registers` timer registers beginning from `base` with values from `buf`
+    /// on each update event
+    ///
+    /// If `circular` is set, buffer is repeated endlessly.
+    ///
+    /// # Panics
+    ///
+    /// If `registers` is 0 or the burst exceeds `CCR4`.
+    pub fn dma_burst<STREAM, const DMA_CH: u8, BUF>(
+        self,
+        stream: STREAM,
+        buf: BUF,
+        base: BurstRegister,
+        registers: u8,
+        circular: bool,
+    ) -> PwmBurst<TIM, PINS, FREQ, STREAM, DMA_CH, BUF>
+    where
+        STREAM: Stream,
+        ChannelX<DMA_CH>: DmaChannel,
+        DMAR<TIM>: PeriAddress + DMASet<STREAM, DMA_CH, MemoryToPeripheral>,
+        BUF: ReadBuffer<Word = <DMAR<TIM> as PeriAddress>::MemSize>,
+    {
+        assert!(registers > 0 && base as u8 + registers <= BurstRegister::Ccr4 as u8 + 1);
+        let Pwm {
+            timer: FTimer { mut tim },
+            ..
+        } = self;
+
+        tim.set_dma_burst(base as u8, registers);
+
+        let mut transfer = Transfer::init_memory_to_peripheral(
+            stream,
+            DMAR(tim),
+            buf,
+            None,
+            DmaConfig::default().memory_increment(true),
+        );
+        if circular {
+            unsafe { transfer.stream().set_circular_mode(true) };
+        }
+        transfer.start(|dmar| dmar.0.enable_update_dma(true));
+
+        PwmBurst {
+            transfer,
+            circular,
+            _pins: PhantomData,
+        }
+    }
+}
+
+impl<TIM, PINS, const FREQ: u32, STREAM, const DMA_CH: u8, BUF>
+    PwmBurst<TIM, PINS, FREQ, STREAM, DMA_CH, BUF>
+where
+    TIM: Instance + WithPwm + WithDmar,
+    PINS: Pins<TIM>,
+    STREAM: Stream,
+    ChannelX<DMA_CH>: DmaChannel,
+    DMAR<TIM>: PeriAddress + DMASet<STREAM, DMA_CH, MemoryToPeripheral>,
+{
+    /// Returns `true` when the whole buffer is written (never in circular mode)
+    pub fn is_complete(&self) -> bool {
+        !self.circular && self.transfer.is_transfer_complete()
+    }
+
+    /// Number of buffer values not yet written in the current pass
+    pub fn remaining(&self) -> u16 {
+        self.transfer.number_of_transfers()
+    }
+
+    /// Stops DMA requests and returns the PWM, stream and buffer
+    ///
+    /// PWM keeps running with the last written register values.
+    pub fn release(self) -> (Pwm<TIM, PINS, FREQ>, STREAM, BUF) {
+        let (stream, dmar, buf, _) = self.transfer.release();
+        let DMAR(mut tim) = dmar;
+        tim.enable_update_dma(false);
+        (
+            Pwm {
+                timer: FTimer { tim },
+                _pins: PhantomData,
+            },
+            stream,
+            buf,
+        )
+    }
+}
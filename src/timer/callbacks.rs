@@ -0,0 +1,118 @@
+//! Per-event timer interrupt callbacks
+//!
+//! [`Callbacks`] stores a function for each timer [`Event`]. Its [`dispatch`](Callbacks::dispatch)
+//! reads pending flags of listened events, clears them and calls registered functions, so
+//! interrupt handlers don't need to check flags manually. [`timer_interrupt!`](crate::timer_interrupt)
+//! defines such handler, one interrupt vector can serve several timers (like `TIM1_UP_TIM10`).
+//!
+//! Callbacks are plain functions (closures without captures), they are called outside
+//! of critical section.
+//!
+//! ```rust,ignore
+//! static TIM2_CALLBACKS: Callbacks<pac::TIM2> = Callbacks::new();
+//! stm32f4xx_hal::timer_interrupt!(TIM2 => TIM2_CALLBACKS);
+//!
+//! TIM2_CALLBACKS.register(Event::Update, || { /* every period */ });
+//! TIM2_CALLBACKS.register(Event::C1, || { /* compare match of channel 1 */ });
+//! let mut counter = dp.TIM2.counter_hz(&clocks);
+//! counter.listen(Event::Update | Event::C1);
+//! unsafe { cortex_m::peripheral::NVIC::unmask(pac::Interrupt::TIM2) };
+//! ```
+
+use super::{Event, Instance};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use cortex_m::interrupt::Mutex;
+use enumflags2::BitFlags;
+
+/// Function called on timer event
+pub type Callback = fn();
+
+const EVENTS: usize = 8;
+
+/// Callbacks of `TIM` events
+pub struct Callbacks<TIM> {
+    handlers: Mutex<Cell<[Option<Callback>; EVENTS]>>,
+    _tim: PhantomData<fn() -> TIM>,
+}
+
+impl<TIM> Callbacks<TIM> {
+    /// Creates empty callback table, usually placed in a `static`
+    pub const fn new() -> Self {
+        Self {
+            handlers: Mutex::new(Cell::new([None; EVENTS])),
+            _tim: PhantomData,
+        }
+    }
+}
+
+impl<TIM: Instance> Callbacks<TIM> {
+    /// Sets `callback` for `event` and returns previous one
+    ///
+    /// Event must be also listened on timer to generate interrupt.
+    pub fn register(&self, event: Event, callback: Callback) -> Option<Callback> {
+        self.replace(event, Some(callback))
+    }
+
+    /// Removes callback of `event` and returns it
+    pub fn unregister(&self, event: Event) -> Option<Callback> {
+        self.replace(event, None)
+    }
+
+    fn replace(&self, event: Event, callback: Option<Callback>) -> Option<Callback> {
+        let i = (event as u32).trailing_zeros() as usize;
+        cortex_m::interrupt::free(|cs| {
+            let cell = self.handlers.borrow(cs);
+            let mut handlers = cell.get();
+            let old = core::mem::replace(&mut handlers[i], callback);
+            cell.set(handlers);
+            old
+        })
+    }
+
+    /// Handles timer interrupt: clears flags of pending listened events and calls their callbacks
+    ///
+    /// Events without callback are only cleared.
+    pub fn dispatch(&self) {
+        // Only status and interrupt enable registers are accessed
+        let mut tim = unsafe { TIM::steal() };
+        let pending = tim.get_listened_event().bits() & tim.get_interrupt_flag().bits();
+        tim.clear_interrupt_flag(BitFlags::from_bits_truncate(pending));
+        let handlers = cortex_m::interrupt::free(|cs| self.handlers.borrow(cs).get());
+        for event in BitFlags::<Event>::from_bits_truncate(pending).iter() {
+            if let Some(callback) = handlers[(event as u32).trailing_zeros() as usize] {
+                callback();
+            }
+        }
+    }
+}
+
+impl<TIM> Default for Callbacks<TIM> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Defines interrupt handler `$irq` which dispatches events of listed [`Callbacks`](crate::timer::Callbacks)
+///
+/// ```rust,ignore
+/// static TIM1_CALLBACKS: Callbacks<pac::TIM1> = Callbacks::new();
+/// static TIM10_CALLBACKS: Callbacks<pac::TIM10> = Callbacks::new();
+/// timer_interrupt!(TIM1_UP_TIM10 => TIM1_CALLBACKS, TIM10_CALLBACKS);
+/// ```
+///
+/// Handler is defined with `#[interrupt]` attribute, so `$irq` is checked to be
+/// a valid interrupt name of the device.
+#[macro_export]
+macro_rules! timer_interrupt {
+    ($irq:ident => $($callbacks:path),+ $(,)?) => {
+        const _: () = {
+            use $crate::interrupt;
+
+            #[interrupt]
+            fn $irq() {
+                $($callbacks.dispatch();)+
+            }
+        };
+    };
+}
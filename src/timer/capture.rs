@@ -0,0 +1,281 @@
+//! Input capture with DMA
+//!
+//! Counter of the timer runs freely with maximal auto-reload value and each captured edge
+//! timestamp (`CCRx` value) is transferred to memory by DMA, so no interrupt per edge is needed.
+//! Time between edges is the wrapping difference of consecutive timestamps in timer ticks.
+//!
+//! In circular mode DMA continuously overwrites the buffer and captured values are fetched with
+//! [`CaptureDma::read`], which must be called at least once per buffer length.
+//!
+//! ```rust,ignore
+//! static mut BUF: [u32; 64] = [0; 64];
+//!
+//! let streams = StreamsTuple::new(dp.DMA1);
+//! let timer = FTimer::<_, 1_000_000>::new(dp.TIM2, &clocks);
+//! // TIM2_CH1 is on DMA1 stream 5 channel 3
+//! let mut capture = timer.capture_dma::<0, _, 3, _>(
+//!     gpioa.pa0,
+//!     streams.5,
+//!     unsafe { &mut BUF },
+//!     CaptureConfig::default().edge(CaptureEdge::Rising).circular(true),
+//! );
+//!
+//! let mut stamps = [0; 16];
+//! let n = capture.read(&mut stamps)?;
+//! ```
+
+use embedded_dma::WriteBuffer;
+
+use super::{impl_listen_flags, CPin, Channel, FTimer, Instance, Polarity, WithPwm, CCR};
+use crate::dma::{
+    config::DmaConfig,
+    traits::{Channel as DmaChannel, DMASet, PeriAddress, Stream, StreamISR},
+    ChannelX, PeripheralToMemory, Transfer,
+};
+use crate::gpio::PushPull;
+
+/// Active edge of input capture
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CaptureEdge {
+    Rising,
+    Falling,
+    /// Both rising and falling edges
+    Both,
+}
+
+/// Input capture configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CaptureConfig {
+    edge: CaptureEdge,
+    filter: u8,
+    prescaler: u8,
+    circular: bool,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            edge: CaptureEdge::Rising,
+            filter: 0,
+            prescaler: 0,
+            circular: false,
+        }
+    }
+}
+
+impl CaptureConfig {
+    /// Active edge
+    pub fn edge(mut self, edge: CaptureEdge) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    /// Digital filter value written to `ICxF` (0 - 15)
+    pub fn filter(mut self, filter: u8) -> Self {
+        self.filter = filter & 0xf;
+        self
+    }
+
+    /// Capture is done once every 1, 2, 4 or 8 events for `prescaler` values from 0 to 3
+    pub fn prescaler(mut self, prescaler: u8) -> Self {
+        self.prescaler = prescaler & 0b11;
+        self
+    }
+
+    /// Restart DMA from the beginning of the buffer when it is full
+    pub fn circular(mut self, circular: bool) -> Self {
+        self.circular = circular;
+        self
+    }
+}
+
+/// Error of reading captured values
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// DMA has overwritten values which were not read yet.
+    /// Reading continues from the current DMA position.
+    Overflow,
+}
+
+/// Timer channel capturing edge timestamps with DMA
+pub struct CaptureDma<TIM, STREAM, const C: u8, const DMA_CH: u8, BUF, const FREQ: u32>
+where
+    TIM: Instance + WithPwm + CPin<C>,
+    STREAM: Stream,
+    ChannelX<DMA_CH>: DmaChannel,
+    CCR<TIM, C>: PeriAddress + DMASet<STREAM, DMA_CH, PeripheralToMemory>,
+{
+    transfer: Transfer<STREAM, DMA_CH, CCR<TIM, C>, PeripheralToMemory, BUF>,
+    pin: TIM::Ch<PushPull>,
+    buf_ptr: *const <CCR<TIM, C> as PeriAddress>::MemSize,
+    buf_len: usize,
+    read_pos: usize,
+    circular: bool,
+    overcaptures: u32,
+}
+
+impl<TIM, const FREQ: u32> FTimer<TIM, FREQ>
+where
+    TIM: Instance + WithPwm,
+{
+    /// Starts capturing edges on channel `C` to `buf` using DMA `stream`
+    ///
+    /// Timestamps are counter values in `FREQ` ticks.
+    pub fn capture_dma<const C: u8, STREAM, const DMA_CH: u8, BUF>(
+        mut self,
+        pin: impl Into<TIM::Ch<PushPull>>,
+        stream: STREAM,
+        mut buf: BUF,
+        config: CaptureConfig,
+    ) -> CaptureDma<TIM, STREAM, C, DMA_CH, BUF, FREQ>
+    where
+        TIM: CPin<C>,
+        STREAM: Stream,
+        ChannelX<DMA_CH>: DmaChannel,
+        CCR<TIM, C>: PeriAddress + DMASet<STREAM, DMA_CH, PeripheralToMemory>,
+        BUF: WriteBuffer<Word = <CCR<TIM, C> as PeriAddress>::MemSize>,
+    {
+        let pin = pin.into();
+        let channel = Channel::from_index(C);
+
+        // Free running counter
+        unsafe {
+            self.tim.set_auto_reload_unchecked(TIM::max_auto_reload());
+        }
+        self.tim.trigger_update();
+
+        TIM::enable_channel(C, false);
+        self.tim
+            .set_input_capture(channel, config.filter, config.prescaler);
+        let (p, np) = match config.edge {
+            CaptureEdge::Rising => (Polarity::ActiveHigh, Polarity::ActiveHigh),
+            CaptureEdge::Falling => (Polarity::ActiveLow, Polarity::ActiveHigh),
+            CaptureEdge::Both => (Polarity::ActiveLow, Polarity::ActiveLow),
+        };
+        TIM::set_channel_polarity(C, p);
+        TIM::set_nchannel_polarity(C, np);
+        TIM::take_overcapture(C);
+        self.tim.enable_cc_dma(channel, true);
+
+        // NOTE(unsafe) buffer is only read at positions already written by DMA
+        let (buf_ptr, buf_len) = unsafe { buf.write_buffer() };
+        let mut transfer = Transfer::init_peripheral_to_memory(
+            stream,
+            CCR(self.tim),
+            buf,
+            None,
+            DmaConfig::default().memory_increment(true),
+        );
+        if config.circular {
+            unsafe { transfer.stream().set_circular_mode(true) };
+        }
+        transfer.start(|ccr| {
+            TIM::enable_channel(C, true);
+            ccr.0.enable_counter(true);
+        });
+
+        CaptureDma {
+            transfer,
+            pin,
+            buf_ptr,
+            buf_len,
+            read_pos: 0,
+            circular: config.circular,
+            overcaptures: 0,
+        }
+    }
+}
+
+impl<TIM, STREAM, const C: u8, const DMA_CH: u8, BUF, const FREQ: u32>
+    CaptureDma<TIM, STREAM, C, DMA_CH, BUF, FREQ>
+where
+    TIM: Instance + WithPwm + CPin<C>,
+    STREAM: Stream,
+    ChannelX<DMA_CH>: DmaChannel,
+    CCR<TIM, C>: PeriAddress + DMASet<STREAM, DMA_CH, PeripheralToMemory>,
+    <CCR<TIM, C> as PeriAddress>::MemSize: Copy,
+{
+    /// Position in the buffer where the next captured value will be written
+    pub fn write_position(&self) -> usize {
+        self.buf_len - self.transfer.number_of_transfers() as usize
+    }
+
+    /// Returns `true` when the buffer is full (not used in circular mode)
+    pub fn is_complete(&self) -> bool {
+        !self.circular && self.transfer.is_transfer_complete()
+    }
+
+    /// Number of edges lost because DMA did not fetch previous capture in time
+    ///
+    /// Overcapture is only detected when this method or [`read`](Self::read) is called.
+    pub fn overcaptures(&mut self) -> u32 {
+        if TIM::take_overcapture(C) {
+            self.overcaptures = self.overcaptures.wrapping_add(1);
+        }
+        self.overcaptures
+    }
+
+    /// Copies values captured since the previous call to `values` and returns their number
+    pub fn read(
+        &mut self,
+        values: &mut [<CCR<TIM, C> as PeriAddress>::MemSize],
+    ) -> Result<usize, Error> {
+        self.overcaptures();
+
+        let mut wrapped = self.circular && self.transfer.is_transfer_complete();
+        let write_pos = self.write_position();
+        if self.circular && !wrapped && write_pos < self.read_pos {
+            // DMA has wrapped after transfer complete flag was read
+            wrapped = true;
+        }
+        if wrapped {
+            self.transfer.clear_transfer_complete();
+            if write_pos >= self.read_pos {
+                self.read_pos = write_pos;
+                return Err(Error::Overflow);
+            }
+        }
+
+        let mut count = 0;
+        for v in values.iter_mut() {
+            if self.read_pos == write_pos {
+                break;
+            }
+            // NOTE(unsafe) DMA has already written this position
+            *v = unsafe { self.buf_ptr.add(self.read_pos).read_volatile() };
+            count += 1;
+            self.read_pos += 1;
+            // Single transfer stops at the end of the buffer, write position stays there
+            if self.circular && self.read_pos == self.buf_len {
+                self.read_pos = 0;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Stops capturing and returns the timer, pin, DMA stream and buffer
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> (FTimer<TIM, FREQ>, TIM::Ch<PushPull>, STREAM, BUF) {
+        let (stream, ccr, buf, _) = self.transfer.release();
+        let CCR(mut tim) = ccr;
+        tim.enable_counter(false);
+        tim.enable_cc_dma(Channel::from_index(C), false);
+        TIM::enable_channel(C, false);
+        (FTimer { tim }, self.pin, stream, buf)
+    }
+}
+
+// Timer is owned by the DMA transfer, only DIER and SR registers are accessed
+impl_listen_flags!(
+    [TIM, STREAM, const C: u8, const DMA_CH: u8, BUF, const FREQ: u32]
+    CaptureDma<TIM, STREAM, C, DMA_CH, BUF, FREQ>
+    where [
+        TIM: Instance + WithPwm + CPin<C>,
+        STREAM: Stream,
+        ChannelX<DMA_CH>: DmaChannel,
+        CCR<TIM, C>: PeriAddress + DMASet<STREAM, DMA_CH, PeripheralToMemory>,
+    ] => steal
+);
@@ -0,0 +1,175 @@
+//! Streaming PWM duty cycles with DMA
+//!
+//! DMA writes next duty value to channel `CCRx` register on each update event, so every PWM period
+//! can have its own duty. This is the usual way to drive WS2812 (NeoPixel) and similar addressable LEDs:
+//! each data bit is one PWM period with short (`0`) or long (`1`) pulse.
+//!
+//! The last duty value stays active after the frame is sent, so a frame should end with zero duties
+//! which form the idle (reset) period. [`PwmDutyDma::idle_periods`] calculates how many are needed
+//! and [`ws2812_encode`] fills buffer for WS2812 LEDs.
+//!
+//! ```rust,ignore
+//! // 800 kHz bit rate
+//! let pwm = dp.TIM3.pwm::<_, 40_000_000>(
+//!     Channel1::new(gpiob.pb4),
+//!     TimerDurationU32::from_ticks(50),
+//!     &clocks,
+//! );
+//! // 8 LEDs and 50 us reset
+//! static mut FRAME: [u16; 24 * 8 + 40] = [0; 24 * 8 + 40];
+//! let frame = unsafe { &mut FRAME };
+//! let duty = pwm.get_max_duty();
+//! ws2812_encode(&colors, duty * 8 / 25, duty * 16 / 25, frame);
+//! // TIM3_CH1 is on DMA1 stream 4 channel 5
+//! let mut leds = pwm.duty_dma::<0, _, 5, _>(streams.4, frame);
+//! ```
+
+use core::marker::PhantomData;
+
+use embedded_dma::ReadBuffer;
+use fugit::TimerDurationU32;
+
+use super::{Channel, FTimer, Instance, Pins, Pwm, WithDmar, WithPwm, CCR};
+use crate::dma::{
+    config::DmaConfig,
+    traits::{Channel as DmaChannel, DMASet, PeriAddress, Stream, StreamISR},
+    ChannelX, DMAError, MemoryToPeripheral, Transfer,
+};
+
+/// PWM channel `C` which duty cycle is streamed from memory by DMA
+pub struct PwmDutyDma<TIM, PINS, const FREQ: u32, const C: u8, STREAM, const DMA_CH: u8, BUF>
+where
+    TIM: Instance + WithPwm + WithDmar,
+    PINS: Pins<TIM>,
+    STREAM: Stream,
+    CCR<TIM, C>: PeriAddress,
+{
+    transfer: Transfer<STREAM, DMA_CH, CCR<TIM, C>, MemoryToPeripheral, BUF>,
+    _pins: PhantomData<PINS>,
+}
+
+impl<TIM, PINS, const FREQ: u32> Pwm<TIM, PINS, FREQ>
+where
+    TIM: Instance + WithPwm + WithDmar,
+    PINS: Pins<TIM>,
+{
+    /// Starts streaming duty values of channel `C` from `frame`, one value per PWM period
+    pub fn duty_dma<const C: u8, STREAM, const DMA_CH: u8, BUF>(
+        self,
+        stream: STREAM,
+        frame: BUF,
+    ) -> PwmDutyDma<TIM, PINS, FREQ, C, STREAM, DMA_CH, BUF>
+    where
+        STREAM: Stream,
+        ChannelX<DMA_CH>: DmaChannel,
+        CCR<TIM, C>: PeriAddress + DMASet<STREAM, DMA_CH, MemoryToPeripheral>,
+        BUF: ReadBuffer<Word = <CCR<TIM, C> as PeriAddress>::MemSize>,
+    {
+        let channel = PINS::check_used(Channel::from_index(C));
+        let Pwm {
+            timer: FTimer { mut tim },
+            ..
+        } = self;
+
+        TIM::set_cc_value(C, 0);
+        // Request DMA on update event instead of compare match
+        tim.cc_dma_on_update(true);
+        TIM::enable_channel(C, true);
+
+        let mut transfer = Transfer::init_memory_to_peripheral(
+            stream,
+            CCR(tim),
+            frame,
+            None,
+            DmaConfig::default().memory_increment(true),
+        );
+        transfer.start(|ccr| ccr.0.enable_cc_dma(channel, true));
+
+        PwmDutyDma {
+            transfer,
+            _pins: PhantomData,
+        }
+    }
+}
+
+impl<TIM, PINS, const FREQ: u32, const C: u8, STREAM, const DMA_CH: u8, BUF>
+    PwmDutyDma<TIM, PINS, FREQ, C, STREAM, DMA_CH, BUF>
+where
+    TIM: Instance + WithPwm + WithDmar,
+    PINS: Pins<TIM>,
+    STREAM: Stream,
+    ChannelX<DMA_CH>: DmaChannel,
+    CCR<TIM, C>: PeriAddress + DMASet<STREAM, DMA_CH, MemoryToPeripheral>,
+    BUF: ReadBuffer<Word = <CCR<TIM, C> as PeriAddress>::MemSize>,
+{
+    /// Returns `true` when all values of the current frame are transferred
+    pub fn is_complete(&self) -> bool {
+        self.transfer.is_transfer_complete()
+    }
+
+    /// Starts sending `frame` and returns the previous one
+    ///
+    /// Returns [`DMAError::NotReady`] if the current frame is not sent yet.
+    pub fn next_frame(&mut self, frame: BUF) -> Result<BUF, DMAError<BUF>> {
+        if !self.is_complete() {
+            return Err(DMAError::NotReady(frame));
+        }
+        self.transfer.next_transfer(frame).map(|(old, _)| old)
+    }
+
+    /// Number of zero duty periods which give idle line for at least `time`
+    pub fn idle_periods(&self, time: TimerDurationU32<FREQ>) -> usize {
+        let period = TIM::read_auto_reload() + 1;
+        ((time.ticks() + period - 1) / period) as usize
+    }
+
+    /// Stops DMA requests and returns the PWM, stream and the last frame
+    ///
+    /// Channel duty is set to zero.
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> (Pwm<TIM, PINS, FREQ>, STREAM, BUF) {
+        let (stream, ccr, frame, _) = self.transfer.release();
+        let CCR(mut tim) = ccr;
+        tim.enable_cc_dma(Channel::from_index(C), false);
+        tim.cc_dma_on_update(false);
+        TIM::set_cc_value(C, 0);
+        (
+            Pwm {
+                timer: FTimer { tim },
+                _pins: PhantomData,
+            },
+            stream,
+            frame,
+        )
+    }
+}
+
+/// Fills `out` with duties encoding WS2812 `colors` (green, red, blue; most significant bit first)
+/// followed by zeros
+///
+/// `zero` and `one` are duties of `0` and `1` bits, about 32% and 64% of period.
+/// Returns number of duties used for colors, remaining ones are zeros which form the reset period.
+///
+/// # Panics
+///
+/// If `out` is shorter than 24 values per color.
+pub fn ws2812_encode<W: Copy + From<u16>>(
+    colors: &[[u8; 3]],
+    zero: W,
+    one: W,
+    out: &mut [W],
+) -> usize {
+    let len = colors.len() * 24;
+    assert!(out.len() >= len);
+    for (color, bits) in colors.iter().zip(out.chunks_exact_mut(24)) {
+        for (byte, bits) in color.iter().zip(bits.chunks_exact_mut(8)) {
+            for (i, bit) in bits.iter_mut().enumerate() {
+                *bit = if byte & (0x80 >> i) != 0 { one } else { zero };
+            }
+        }
+    }
+    for d in &mut out[len..] {
+        *d = W::from(0);
+    }
+    len
+}
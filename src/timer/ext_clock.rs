@@ -0,0 +1,160 @@
+//! External clock modes
+//!
+//! Timer counter can be clocked by edges of external signal instead of internal clock:
+//!
+//! * external clock mode 1: channel 1 or 2 input (TI1/TI2) through slave mode controller,
+//!   with input filter and edge selection;
+//! * external clock mode 2: ETR input with prescaler, filter and polarity.
+//!
+//! [`PulseCounter`] counts pulses of the signal, frequency can be measured by reading the count
+//! at known intervals.
+//!
+//! ```rust,ignore
+//! let mut counter = Timer::new(dp.TIM2, &clocks).pulse_counter_etr(gpioa.pa15, EtrConfig::default());
+//! // ...
+//! let pulses = counter.count();
+//! ```
+
+use super::{
+    impl_listen_flags, CPin, CaptureEdge, Channel, EtrPin, Instance, Polarity, SlaveMode,
+    SlaveTimer, Timer, TriggerSource, WithEtr, WithPwm,
+};
+use crate::gpio::PushPull;
+
+/// ETR input prescaler
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum EtrPrescaler {
+    Div1 = 0,
+    Div2 = 1,
+    Div4 = 2,
+    Div8 = 3,
+}
+
+/// ETR input configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EtrConfig {
+    pub prescaler: EtrPrescaler,
+    /// Digital filter value written to `ETF` (0 - 15)
+    pub filter: u8,
+    /// `ActiveLow` counts falling edges
+    pub polarity: Polarity,
+}
+
+impl Default for EtrConfig {
+    fn default() -> Self {
+        Self {
+            prescaler: EtrPrescaler::Div1,
+            filter: 0,
+            polarity: Polarity::ActiveHigh,
+        }
+    }
+}
+
+/// Timer counting edges of external signal
+pub struct PulseCounter<TIM: Instance, PIN> {
+    timer: Timer<TIM>,
+    pin: PIN,
+}
+
+impl<TIM: Instance + SlaveTimer + WithPwm> Timer<TIM> {
+    /// Counts `edge`s on channel `C` input (external clock mode 1)
+    ///
+    /// # Panics
+    ///
+    /// If `C` is not channel 1 or 2, or both edges are requested on channel 2.
+    pub fn pulse_counter_ti<const C: u8>(
+        mut self,
+        pin: impl Into<TIM::Ch<PushPull>>,
+        edge: CaptureEdge,
+        filter: u8,
+    ) -> PulseCounter<TIM, TIM::Ch<PushPull>>
+    where
+        TIM: CPin<C>,
+    {
+        assert!(C < 2);
+        let pin = pin.into();
+
+        TIM::enable_channel(C, false);
+        self.tim
+            .set_input_capture(Channel::from_index(C), filter & 0xf, 0);
+        let trigger = match (C, edge) {
+            (0, CaptureEdge::Both) => TriggerSource::Ti1FEdge,
+            (_, CaptureEdge::Both) => panic!("Both edges are available only on TI1"),
+            (0, _) => TriggerSource::Ti1Fp1,
+            _ => TriggerSource::Ti2Fp2,
+        };
+        TIM::set_channel_polarity(
+            C,
+            if edge == CaptureEdge::Falling {
+                Polarity::ActiveLow
+            } else {
+                Polarity::ActiveHigh
+            },
+        );
+        self.tim.slave_mode(SlaveMode::ExternalClock, trigger);
+
+        PulseCounter::new(self, pin)
+    }
+}
+
+impl<TIM: Instance + WithEtr + EtrPin> Timer<TIM> {
+    /// Counts edges on ETR input (external clock mode 2)
+    pub fn pulse_counter_etr(
+        mut self,
+        pin: impl Into<TIM::Etr>,
+        config: EtrConfig,
+    ) -> PulseCounter<TIM, TIM::Etr> {
+        let pin = pin.into();
+
+        self.tim.external_trigger(
+            config.prescaler as u8,
+            config.filter & 0xf,
+            config.polarity == Polarity::ActiveLow,
+        );
+        self.tim.enable_external_clock2(true);
+
+        PulseCounter::new(self, pin)
+    }
+}
+
+impl<TIM: Instance, PIN> PulseCounter<TIM, PIN> {
+    fn new(mut timer: Timer<TIM>, pin: PIN) -> Self {
+        timer.tim.set_prescaler(0);
+        unsafe {
+            timer.tim.set_auto_reload_unchecked(TIM::max_auto_reload());
+        }
+        timer.tim.trigger_update();
+        timer.tim.reset_counter();
+        timer.tim.enable_counter(true);
+        Self { timer, pin }
+    }
+
+    /// Number of counted pulses, wraps at maximal auto-reload value
+    pub fn count(&self) -> u32 {
+        self.timer.tim.read_count().into()
+    }
+
+    /// Resets the count to zero
+    pub fn reset(&mut self) {
+        self.timer.tim.reset_counter();
+    }
+
+    /// Returns the current count and resets it
+    pub fn take(&mut self) -> u32 {
+        let count = self.count();
+        self.reset();
+        count
+    }
+
+    /// Stops counting and returns the timer and input pin
+    ///
+    /// Timer should be reinitialized before use with internal clock.
+    pub fn release(mut self) -> (Timer<TIM>, PIN) {
+        self.timer.tim.cr1_reset();
+        (self.timer, self.pin)
+    }
+}
+
+impl_listen_flags!([TIM: Instance, PIN] PulseCounter<TIM, PIN> => timer.tim);
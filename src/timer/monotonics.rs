@@ -1,13 +1,9 @@
 // RTICv2 Monotonic impl
-use super::{FTimer, General};
 use crate::{pac, rcc::Clocks};
 use atomic_polyfill::{AtomicU64, Ordering};
 use core::marker::PhantomData;
 use rtic_time::timer_queue::TimerQueueBackend;
-use rtic_time::{
-    half_period_counter::calculate_now, monotonic::TimerQueueBasedMonotonic,
-    timer_queue::TimerQueue, Monotonic,
-};
+use rtic_time::{monotonic::TimerQueueBasedMonotonic, timer_queue::TimerQueue, Monotonic};
 
 pub struct MonoTimer<TIM, const FREQ: u32> {
     _tim: PhantomData<TIM>,
@@ -107,20 +103,22 @@ const fn cortex_logical2hw(logical: u8, nvic_prio_bits: u8) -> u8 {
     ((1 << nvic_prio_bits) - logical) << (8 - nvic_prio_bits)
 }
 
-pub(crate) unsafe fn set_monotonic_prio(
-    nvic: &mut cortex_m::peripheral::NVIC,
-    prio_bits: u8,
-    interrupt: impl cortex_m::interrupt::InterruptNumber,
-) {
+unsafe fn monotonic_hw_prio(prio_bits: u8) -> u8 {
     extern "C" {
         static RTIC_ASYNC_MAX_LOGICAL_PRIO: u8;
     }
 
     let max_prio = RTIC_ASYNC_MAX_LOGICAL_PRIO.max(1).min(1 << prio_bits);
 
-    let hw_prio = cortex_logical2hw(max_prio, prio_bits);
+    cortex_logical2hw(max_prio, prio_bits)
+}
 
-    nvic.set_priority(interrupt, hw_prio);
+pub(crate) unsafe fn set_monotonic_prio(
+    nvic: &mut cortex_m::peripheral::NVIC,
+    prio_bits: u8,
+    interrupt: impl cortex_m::interrupt::InterruptNumber,
+) {
+    nvic.set_priority(interrupt, monotonic_hw_prio(prio_bits));
 }
 
 #[doc(hidden)]
@@ -149,11 +147,11 @@ macro_rules! make_timer {
                 nvic: &mut cortex_m::peripheral::NVIC,
                 clocks: &Clocks,
             ) -> MonoTimer<Self, FREQ> {
-                FTimer::new(self, clocks).monotonic(nvic)
+                super::FTimer::new(self, clocks).monotonic(nvic)
             }
         }
 
-        impl<const FREQ: u32> FTimer<pac::$timer, FREQ> {
+        impl<const FREQ: u32> super::FTimer<pac::$timer, FREQ> {
             pub fn monotonic(
                 mut self,
                 nvic: &mut cortex_m::peripheral::NVIC,
@@ -180,7 +178,7 @@ macro_rules! make_timer {
                 $overflow.store(0, Ordering::SeqCst);
 
                 // Start the counter.
-                self.tim.enable_counter(true);
+                self.tim.cr1().modify(|_, w| w.cen().set_bit());
 
                 // SAFETY: We take full ownership of the peripheral and interrupt vector,
                 // plus we are not using any external shared resources so we won't impact
@@ -204,7 +202,7 @@ macro_rules! make_timer {
             type Ticks = u64;
 
             fn now() -> Self::Ticks {
-                calculate_now(
+                rtic_time::half_period_counter::calculate_now(
                     || $overflow.load(Ordering::Relaxed),
                     || Self::tim().cnt().read().bits(),
                 )
@@ -272,9 +270,98 @@ make_timer!(tim3, TIM3, u16, TIMER3_OVERFLOWS, TIMER3_TQ);
 #[cfg(all(feature = "tim4", feature = "rtic-tim4"))]
 make_timer!(tim4, TIM4, u16, TIMER4_OVERFLOWS, TIMER4_TQ);
 
-#[cfg(all(feature = "tim5", feature = "rtic-tim5"))]
+#[cfg(all(feature = "tim5", feature = "rtic-tim5", not(feature = "gpio-f410")))]
+make_timer!(tim5, TIM5, u32, TIMER5_OVERFLOWS, TIMER5_TQ);
+
+// TIM5 on F410 is 16-bit
+#[cfg(all(feature = "tim5", feature = "rtic-tim5", feature = "gpio-f410"))]
 make_timer!(tim5, TIM5, u16, TIMER5_OVERFLOWS, TIMER5_TQ);
 
+// SysTick is too narrow for half-period counting, so it interrupts on every tick
+// and the timer queue is checked each time.
+#[cfg(feature = "rtic-systick")]
+mod systick {
+    use super::*;
+    use cortex_m::peripheral::{scb::SystemHandler, syst::SystClkSource, SCB, SYST};
+
+    static SYSTICK_TICKS: AtomicU64 = AtomicU64::new(0);
+    static SYSTICK_TQ: TimerQueue<MonoTimerBackend<SYST>> = TimerQueue::new();
+
+    impl MonoTimerExt for SYST {
+        /// `FREQ` is the tick rate, usually 1 kHz or 10 kHz, as each tick causes an interrupt
+        ///
+        /// # Panics
+        ///
+        /// If `FREQ` can't be derived from HCLK with 24-bit reload value.
+        fn monotonic<const FREQ: u32>(
+            mut self,
+            _nvic: &mut cortex_m::peripheral::NVIC,
+            clocks: &Clocks,
+        ) -> MonoTimer<Self, FREQ> {
+            #[no_mangle]
+            #[allow(non_snake_case)]
+            unsafe extern "C" fn SysTick() {
+                MonoTimerBackend::<SYST>::timer_queue().on_monotonic_interrupt();
+            }
+
+            let reload = clocks.hclk().raw() / FREQ;
+            assert!(reload > 0 && reload <= 0x0100_0000);
+
+            self.disable_counter();
+            self.set_clock_source(SystClkSource::Core);
+            self.set_reload(reload - 1);
+            self.clear_current();
+
+            SYSTICK_TQ.initialize(MonoTimerBackend::<SYST> { _tim: PhantomData });
+            SYSTICK_TICKS.store(0, Ordering::SeqCst);
+
+            // SAFETY: We take full ownership of the SysTick and its exception,
+            // priority of other exceptions is not changed.
+            unsafe {
+                let mut scb = cortex_m::Peripherals::steal().SCB;
+                scb.set_priority(
+                    SystemHandler::SysTick,
+                    monotonic_hw_prio(pac::NVIC_PRIO_BITS),
+                );
+            }
+
+            self.enable_interrupt();
+            self.enable_counter();
+
+            MonoTimer { _tim: PhantomData }
+        }
+    }
+
+    impl TimerQueueBackend for MonoTimerBackend<SYST> {
+        type Ticks = u64;
+
+        fn now() -> Self::Ticks {
+            SYSTICK_TICKS.load(Ordering::Relaxed)
+        }
+
+        fn set_compare(_: Self::Ticks) {
+            // Queue is checked on every tick
+        }
+
+        fn clear_compare_flag() {}
+
+        fn pend_interrupt() {
+            SCB::set_pendst();
+        }
+
+        fn on_interrupt() {
+            // Reading COUNTFLAG clears it, so pended interrupts don't count as ticks
+            if unsafe { (*SYST::PTR).csr.read() } & (1 << 16) != 0 {
+                SYSTICK_TICKS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        fn timer_queue() -> &'static TimerQueue<Self> {
+            &SYSTICK_TQ
+        }
+    }
+}
+
 pub trait Irq {
     const IRQ: pac::Interrupt;
 }
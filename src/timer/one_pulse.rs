@@ -0,0 +1,187 @@
+//! One-pulse mode
+//!
+//! Timer generates a single pulse of programmable width after programmable delay
+//! and stops. Pulse is started either by software with [`OnePulse::trigger`]
+//! or by an edge on channel 1 or 2 input (see [`OnePulse::with_trigger`]).
+//!
+//! ```text
+//!  trigger
+//!     |<--- delay --->|<--- width --->|
+//! ____|_______________|‾‾‾‾‾‾‾‾‾‾‾‾‾‾‾|_____
+//! ```
+
+use super::{
+    impl_listen_flags, CPin, Channel, Error, FTimer, Instance, Ocm, Polarity, SlaveMode,
+    SlaveTimer, TriggerSource, WithPwm, WithPwmCommon,
+};
+use crate::gpio::PushPull;
+use fugit::TimerDurationU32;
+
+/// Timer configured to generate single pulses on channel `C`
+pub struct OnePulse<TIM, const C: u8, const FREQ: u32, TRIG = ()>
+where
+    TIM: Instance + WithPwm + CPin<C>,
+{
+    timer: FTimer<TIM, FREQ>,
+    pin: TIM::Ch<PushPull>,
+    trigger: TRIG,
+}
+
+impl<TIM, const FREQ: u32> FTimer<TIM, FREQ>
+where
+    TIM: Instance + WithPwm,
+{
+    /// Configures timer to generate a pulse of `width` after `delay` on channel `C`
+    pub fn one_pulse<const C: u8>(
+        mut self,
+        pin: impl Into<TIM::Ch<PushPull>>,
+        delay: TimerDurationU32<FREQ>,
+        width: TimerDurationU32<FREQ>,
+    ) -> Result<OnePulse<TIM, C, FREQ>, Error>
+    where
+        TIM: CPin<C>,
+    {
+        let pin = pin.into();
+
+        self.tim.enable_counter(false);
+        self.tim
+            .preload_output_channel_in_mode(Channel::from_index(C), Ocm::PwmMode2);
+        let mut op = OnePulse {
+            timer: self,
+            pin,
+            trigger: (),
+        };
+        op.set_timing(delay, width)?;
+        TIM::enable_channel(C, true);
+        op.timer.tim.enable_main_output(true);
+        Ok(op)
+    }
+}
+
+impl<TIM, const C: u8, const FREQ: u32, TRIG> OnePulse<TIM, C, FREQ, TRIG>
+where
+    TIM: Instance + WithPwm + CPin<C>,
+{
+    /// Changes pulse delay and width
+    ///
+    /// If a pulse is being generated, new values are applied to the next one.
+    /// Returns `Error::WrongAutoReload` if `width` is zero or `delay + width` doesn't fit
+    /// into the auto-reload register.
+    pub fn set_timing(
+        &mut self,
+        delay: TimerDurationU32<FREQ>,
+        width: TimerDurationU32<FREQ>,
+    ) -> Result<(), Error> {
+        if width.ticks() == 0 {
+            return Err(Error::WrongAutoReload);
+        }
+        // Output is active from CCR to ARR inclusive
+        let arr = delay
+            .ticks()
+            .checked_add(width.ticks() - 1)
+            .ok_or(Error::WrongAutoReload)?;
+        self.timer.tim.set_auto_reload(arr)?;
+        TIM::set_cc_value(C, delay.ticks());
+        if !self.is_busy() {
+            // Load preloaded compare value
+            self.timer.tim.trigger_update();
+        }
+        Ok(())
+    }
+
+    /// Sets output polarity
+    pub fn set_polarity(&mut self, p: Polarity) {
+        TIM::set_channel_polarity(C, p);
+    }
+
+    /// Starts pulse generation
+    ///
+    /// Ignored if a pulse is being generated.
+    pub fn trigger(&mut self) {
+        if !self.is_busy() {
+            self.timer.tim.start_one_pulse();
+        }
+    }
+
+    /// Returns `true` while delay or pulse is in progress
+    pub fn is_busy(&self) -> bool {
+        self.timer.tim.is_counter_enabled()
+    }
+}
+
+impl<TIM, const C: u8, const FREQ: u32> OnePulse<TIM, C, FREQ>
+where
+    TIM: Instance + WithPwm + CPin<C>,
+{
+    /// Disables output and returns the timer and pin
+    pub fn release(mut self) -> (FTimer<TIM, FREQ>, TIM::Ch<PushPull>) {
+        self.timer.tim.cr1_reset();
+        TIM::enable_channel(C, false);
+        (self.timer, self.pin)
+    }
+}
+
+impl<TIM, const C: u8, const FREQ: u32> OnePulse<TIM, C, FREQ>
+where
+    TIM: Instance + WithPwm + SlaveTimer + CPin<C>,
+{
+    /// Starts pulse on edge of channel `T` input instead of software trigger
+    ///
+    /// `T` must be channel 1 or 2 and differ from the output channel.
+    /// `Polarity::ActiveHigh` selects rising edge.
+    pub fn with_trigger<const T: u8>(
+        mut self,
+        pin: impl Into<<TIM as CPin<T>>::Ch<PushPull>>,
+        edge: Polarity,
+    ) -> OnePulse<TIM, C, FREQ, <TIM as CPin<T>>::Ch<PushPull>>
+    where
+        TIM: CPin<T>,
+    {
+        assert!(T < 2 && T != C);
+        let pin = pin.into();
+        let tim = &mut self.timer.tim;
+
+        tim.set_input_capture(Channel::from_index(T), 0, 0);
+        TIM::set_channel_polarity(T, edge);
+        TIM::set_nchannel_polarity(T, Polarity::ActiveHigh);
+        let trigger = if T == 0 {
+            TriggerSource::Ti1Fp1
+        } else {
+            TriggerSource::Ti2Fp2
+        };
+        tim.slave_mode(SlaveMode::Trigger, trigger);
+        // Counter is started by trigger and stopped at update event
+        tim.set_one_pulse(true);
+
+        OnePulse {
+            timer: self.timer,
+            pin: self.pin,
+            trigger: pin,
+        }
+    }
+}
+
+impl<TIM, const C: u8, const FREQ: u32, TRIG> OnePulse<TIM, C, FREQ, TRIG>
+where
+    TIM: Instance + WithPwm + SlaveTimer + CPin<C>,
+{
+    /// Returns to software trigger and returns the trigger pin
+    pub fn without_trigger(mut self) -> (OnePulse<TIM, C, FREQ>, TRIG) {
+        self.timer
+            .tim
+            .slave_mode(SlaveMode::Disabled, TriggerSource::Itr0);
+        (
+            OnePulse {
+                timer: self.timer,
+                pin: self.pin,
+                trigger: (),
+            },
+            self.trigger,
+        )
+    }
+}
+
+impl_listen_flags!(
+    [TIM, const C: u8, const FREQ: u32, TRIG] OnePulse<TIM, C, FREQ, TRIG>
+    where [TIM: Instance + WithPwm + CPin<C>] => timer.tim
+);
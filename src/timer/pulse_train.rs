@@ -0,0 +1,187 @@
+//! Pulse train generation
+//!
+//! [`PulseTrain`] generates given number of pulses of fixed period and width on channel `C`
+//! and stops, which is what stepper motor drivers need for step signal.
+//!
+//! Counting is done in timer update interrupt which must call [`PulseTrain::on_interrupt`].
+//! Advanced timers (TIM1, TIM8) use repetition counter, so the interrupt occurs once per
+//! up to 256 pulses, other timers interrupt after each pulse. The last group of pulses
+//! is stopped by hardware (one-pulse mode), so interrupt latency doesn't add extra pulses
+//! as long as it is shorter than one group.
+//!
+//! ```rust,ignore
+//! let mut steps = FTimer::<_, 1_000_000>::new(dp.TIM1, &clocks)
+//!     .pulse_train::<0>(gpioa.pa8, 500.micros(), 10.micros())
+//!     .unwrap();
+//! steps.start(1000);
+//!
+//! #[interrupt]
+//! fn TIM1_UP_TIM10() {
+//!     if steps.on_interrupt() {
+//!         // all pulses are sent
+//!     }
+//! }
+//! ```
+
+use super::{
+    impl_listen_flags, CPin, Channel, Error, Event, FTimer, Flag, Instance, Ocm, Polarity, WithPwm,
+};
+use crate::gpio::PushPull;
+use fugit::TimerDurationU32;
+
+/// Timer generating trains of pulses on channel `C`
+pub struct PulseTrain<TIM, const C: u8, const FREQ: u32>
+where
+    TIM: Instance + WithPwm + CPin<C>,
+{
+    timer: FTimer<TIM, FREQ>,
+    pin: TIM::Ch<PushPull>,
+    /// Pulses in the group loaded to repetition counter preload, started by the next update event
+    next: u32,
+    /// Pulses not assigned to any group yet
+    remaining: u32,
+}
+
+impl<TIM, const FREQ: u32> FTimer<TIM, FREQ>
+where
+    TIM: Instance + WithPwm,
+{
+    /// Configures timer to generate pulses of `width` with `period` on channel `C`
+    pub fn pulse_train<const C: u8>(
+        mut self,
+        pin: impl Into<TIM::Ch<PushPull>>,
+        period: TimerDurationU32<FREQ>,
+        width: TimerDurationU32<FREQ>,
+    ) -> Result<PulseTrain<TIM, C, FREQ>, Error>
+    where
+        TIM: CPin<C>,
+    {
+        let pin = pin.into();
+
+        self.tim.enable_counter(false);
+        self.tim.enable_preload(true);
+        // Output is inactive at the beginning of period, so it stays inactive after stop
+        self.tim
+            .preload_output_channel_in_mode(Channel::from_index(C), Ocm::PwmMode2);
+        let mut train = PulseTrain {
+            timer: self,
+            pin,
+            next: 0,
+            remaining: 0,
+        };
+        train.set_timing(period, width)?;
+        TIM::enable_channel(C, true);
+        train.timer.tim.enable_main_output(true);
+        train
+            .timer
+            .tim
+            .listen_event(None, Some(Event::Update.into()));
+        Ok(train)
+    }
+}
+
+impl<TIM, const C: u8, const FREQ: u32> PulseTrain<TIM, C, FREQ>
+where
+    TIM: Instance + WithPwm + CPin<C>,
+{
+    /// Changes pulse period and width
+    ///
+    /// `width` must be shorter than `period`. If a train is being generated,
+    /// new values are applied from the next pulse.
+    pub fn set_timing(
+        &mut self,
+        period: TimerDurationU32<FREQ>,
+        width: TimerDurationU32<FREQ>,
+    ) -> Result<(), Error> {
+        let (period, width) = (period.ticks(), width.ticks());
+        if width == 0 || width >= period {
+            return Err(Error::WrongAutoReload);
+        }
+        self.timer.tim.set_auto_reload(period - 1)?;
+        TIM::set_cc_value(C, period - width);
+        if !self.is_busy() {
+            // Load preloaded values
+            self.timer.tim.trigger_update();
+        }
+        Ok(())
+    }
+
+    /// Sets output polarity
+    pub fn set_polarity(&mut self, p: Polarity) {
+        TIM::set_channel_polarity(C, p);
+    }
+
+    /// Starts generation of `count` pulses
+    ///
+    /// Ignored if a train is being generated or `count` is 0.
+    pub fn start(&mut self, count: u32) {
+        if self.is_busy() || count == 0 {
+            return;
+        }
+        let max = TIM::max_repetition();
+        let first = count.min(max);
+        self.timer.tim.set_repetition((first - 1) as u8);
+        self.timer.tim.reset_counter();
+        self.timer.tim.trigger_update();
+        self.remaining = count - first;
+        self.next = 0;
+        self.load_next();
+        self.timer.tim.enable_counter(true);
+    }
+
+    /// Loads the next group of pulses, or stops the timer after the current one
+    fn load_next(&mut self) {
+        if self.remaining == 0 {
+            self.next = 0;
+            self.timer.tim.set_one_pulse(true);
+        } else {
+            self.next = self.remaining.min(TIM::max_repetition());
+            self.remaining -= self.next;
+            self.timer.tim.set_repetition((self.next - 1) as u8);
+            self.timer.tim.set_one_pulse(false);
+        }
+    }
+
+    /// Handles timer update interrupt, returns `true` when the train is complete
+    pub fn on_interrupt(&mut self) -> bool {
+        if !self.timer.tim.get_interrupt_flag().contains(Flag::Update) {
+            return false;
+        }
+        self.timer.tim.clear_interrupt_flag(Flag::Update.into());
+        if self.next == 0 {
+            true
+        } else {
+            self.load_next();
+            false
+        }
+    }
+
+    /// Returns `true` while pulses are being generated
+    pub fn is_busy(&self) -> bool {
+        self.timer.tim.is_counter_enabled()
+    }
+
+    /// Stops generation immediately, the current pulse may be cut
+    pub fn stop(&mut self) {
+        self.timer.tim.enable_counter(false);
+        self.timer.tim.reset_counter();
+        self.timer.tim.clear_interrupt_flag(Flag::Update.into());
+        self.next = 0;
+        self.remaining = 0;
+    }
+
+    /// Disables output and interrupt, returns the timer and pin
+    pub fn release(mut self) -> (FTimer<TIM, FREQ>, TIM::Ch<PushPull>) {
+        self.timer.tim.cr1_reset();
+        self.timer
+            .tim
+            .listen_event(Some(Event::Update.into()), None);
+        TIM::enable_channel(C, false);
+        (self.timer, self.pin)
+    }
+}
+
+impl_listen_flags!(
+    [TIM, const C: u8, const FREQ: u32] PulseTrain<TIM, C, FREQ>
+    where [TIM: Instance + WithPwm + CPin<C>] => timer.tim
+);
@@ -35,17 +35,28 @@
 //!
 //! After creating structures you can dynamically enable main or complementary channels with `enable` and `enable_complementary`
 //! and change their polarity with `set_polarity` and `set_complementary_polarity`.
+//!
+//! Timers with 4 channels can also use down-counting or center-aligned modes
+//! with `pwm_aligned` and `pwm_hz_aligned` constructors, see [`Alignment`].
+//!
+//! Advanced timers (TIM1, TIM8) also support dead time insertion with `set_dead_time`, break input
+//! with `enable_break_input` and main output enable (MOE) control with `enable_main_output` and `disable_main_output`.
+//! Levels of outputs while they are disabled are configured with `set_idle_state` and `set_off_state`.
+//!
+//! Outputs can be parked at fixed level with `force_active` and `force_inactive` and returned to PWM
+//! with `set_pwm_mode`. Duty cycle preload can be disabled with `set_preload` when new duty
+//! must be applied immediately instead of at the next period.
 
 use super::{
-    compute_arr_presc, Advanced, CPin, Channel, FTimer, IdleState, Instance, NCPin, Ocm, Polarity,
-    Timer, WithPwm,
+    compute_arr_presc, compute_arr_presc_center, Advanced, Alignment, BkinPin, CPin, CenterAligned,
+    Channel, Error, FTimer, IdleState, Instance, NCPin, Ocm, Polarity, Timer, WithPwm,
 };
 pub use super::{Ch, C1, C2, C3, C4};
 use crate::gpio::{OpenDrain, PushPull};
 use crate::rcc::Clocks;
 use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
-use fugit::{HertzU32 as Hertz, TimerDurationU32};
+use fugit::{HertzU32 as Hertz, NanosDurationU32, TimerDurationU32};
 
 pub type Channel1<TIM, const COMP: bool = false> = ChannelBuilder<TIM, C1, COMP, PushPull>;
 pub type Channel2<TIM, const COMP: bool = false> = ChannelBuilder<TIM, C2, COMP, PushPull>;
@@ -277,6 +288,12 @@ impl<TIM: Instance + WithPwm, const C: u8, const COMP: bool> PwmChannel<TIM, C,
         TIM::set_channel_polarity(C, p);
     }
 
+    /// Get PWM channel polarity
+    #[inline]
+    pub fn get_polarity(&self) -> Polarity {
+        TIM::read_channel_polarity(C)
+    }
+
     /// Get PWM channel duty cycle
     #[inline]
     pub fn get_duty(&self) -> u16 {
@@ -285,10 +302,11 @@ impl<TIM: Instance + WithPwm, const C: u8, const COMP: bool> PwmChannel<TIM, C,
 
     /// Get the maximum duty cycle value of the PWM channel
     ///
-    /// If `0` returned means max_duty is 2^16
+    /// If `0` returned means max_duty is 2^16.
+    /// In center-aligned mode it is equal to auto-reload value
     #[inline]
     pub fn get_max_duty(&self) -> u16 {
-        (TIM::read_auto_reload() as u16).wrapping_add(1)
+        max_duty::<TIM>()
     }
 
     /// Set PWM channel duty cycle
@@ -302,6 +320,45 @@ impl<TIM: Instance + WithPwm, const C: u8, const COMP: bool> PwmChannel<TIM, C,
     pub fn set_complementary_polarity(&mut self, p: Polarity) {
         TIM::set_nchannel_polarity(C, p);
     }
+
+    /// Get complementary PWM channel polarity
+    #[inline]
+    pub fn get_complementary_polarity(&self) -> Polarity {
+        TIM::read_nchannel_polarity(C)
+    }
+
+    /// Force PWM channel output to active level, duty cycle is ignored
+    #[inline]
+    pub fn force_active(&mut self) {
+        TIM::set_output_mode(C, Ocm::ForceActive);
+    }
+
+    /// Force PWM channel output to inactive level, duty cycle is ignored
+    #[inline]
+    pub fn force_inactive(&mut self) {
+        TIM::set_output_mode(C, Ocm::ForceInactive);
+    }
+
+    /// Switch PWM channel output back to PWM mode after forcing
+    #[inline]
+    pub fn set_pwm_mode(&mut self) {
+        TIM::set_output_mode(C, Ocm::PwmMode1);
+    }
+
+    /// Enable or disable duty cycle preload
+    ///
+    /// With preload (default) new duty cycle is applied at the next update event,
+    /// so the current period is not glitched. Without it the value is used immediately.
+    #[inline]
+    pub fn set_preload(&mut self, b: bool) {
+        TIM::enable_cc_preload(C, b);
+    }
+
+    /// Is duty cycle preload enabled
+    #[inline]
+    pub fn is_preload_enabled(&self) -> bool {
+        TIM::is_cc_preload_enabled(C)
+    }
 }
 
 impl<TIM: Instance + WithPwm + Advanced, const C: u8> PwmChannel<TIM, C, true> {
@@ -328,6 +385,23 @@ impl<TIM: Instance + WithPwm + Advanced, const C: u8> PwmChannel<TIM, C, true> {
     pub fn set_complementary_idle_state(&mut self, s: IdleState) {
         TIM::idle_state(C, true, s);
     }
+
+    /// Get PWM channel idle state
+    #[inline]
+    pub fn get_idle_state(&self) -> IdleState {
+        TIM::read_idle_state(C, false)
+    }
+
+    /// Get complementary PWM channel idle state
+    #[inline]
+    pub fn get_complementary_idle_state(&self) -> IdleState {
+        TIM::read_idle_state(C, true)
+    }
+}
+
+/// Break input enabled with `enable_break_input`
+pub struct BreakInput<TIM: BkinPin> {
+    pin: TIM::Bkin,
 }
 
 pub struct PwmHz<TIM, PINS>
@@ -403,7 +477,11 @@ impl<TIM: Instance + WithPwm> Timer<TIM> {
         // might as well enable for the auto-reload too
         self.tim.enable_preload(true);
 
-        let (psc, arr) = compute_arr_presc(freq.raw(), self.clk.raw());
+        let (psc, arr) = if TIM::is_center_aligned() {
+            compute_arr_presc_center(freq.raw(), self.clk.raw())
+        } else {
+            compute_arr_presc(freq.raw(), self.clk.raw())
+        };
         self.tim.set_prescaler(psc);
         self.tim.set_auto_reload(arr).unwrap();
 
@@ -419,6 +497,25 @@ impl<TIM: Instance + WithPwm> Timer<TIM> {
     }
 }
 
+impl<TIM: Instance + CenterAligned> Timer<TIM> {
+    /// Same as [`pwm_hz`](Self::pwm_hz) with selected counter `alignment`
+    ///
+    /// In center-aligned modes counter counts up to auto-reload value and back,
+    /// so PWM is symmetric and maximum duty is half as large as in edge-aligned mode.
+    pub fn pwm_hz_aligned<PINS>(
+        mut self,
+        pins: PINS,
+        freq: Hertz,
+        alignment: Alignment,
+    ) -> PwmHz<TIM, PINS>
+    where
+        PINS: Pins<TIM>,
+    {
+        self.tim.set_alignment(alignment);
+        self.pwm_hz(pins, freq)
+    }
+}
+
 impl<TIM, PINS> PwmHz<TIM, PINS>
 where
     TIM: Instance + WithPwm,
@@ -442,6 +539,12 @@ where
         TIM::set_channel_polarity(PINS::check_used(channel) as u8, p);
     }
 
+    /// Get the polarity of the active state for the primary PWM output of the timer on channel `channel`
+    #[inline]
+    pub fn get_polarity(&self, channel: Channel) -> Polarity {
+        TIM::read_channel_polarity(PINS::check_used(channel) as u8)
+    }
+
     /// Get the current duty cycle of the timer on channel `channel`
     #[inline]
     pub fn get_duty(&self, channel: Channel) -> u16 {
@@ -454,11 +557,85 @@ where
         TIM::set_cc_value(PINS::check_used(channel) as u8, duty as u32)
     }
 
+    /// Get the pulse width of the timer on channel `channel`
+    #[inline]
+    pub fn get_duty_time(&self, channel: Channel) -> NanosDurationU32 {
+        NanosDurationU32::from_ticks(self.ticks_to_nanos(self.get_duty(channel) as u64))
+    }
+
+    /// Set the pulse width of the timer on channel `channel`
+    #[inline]
+    pub fn set_duty_time(&mut self, channel: Channel, duty: NanosDurationU32) {
+        let psc = self.tim.read_prescaler() as u64 + 1;
+        let duty = duty.ticks() as u64 * self.clk.raw() as u64 / (psc * 1_000_000_000);
+        self.set_duty(channel, duty.min(u16::MAX as u64) as u16)
+    }
+
+    /// Converts counter ticks to nanoseconds
+    fn ticks_to_nanos(&self, ticks: u64) -> u32 {
+        let psc = self.tim.read_prescaler() as u64 + 1;
+        (ticks * psc * 1_000_000_000 / self.clk.raw() as u64) as u32
+    }
+
+    /// Switch output of channel `channel` to phase-shifted square wave
+    ///
+    /// STM32F4 timers have no combined PWM modes, so phase-shifted outputs are made with toggle mode:
+    /// output toggles each time the counter reaches `phase`, giving 50% duty cycle at half
+    /// of PWM frequency, delayed by `phase` ticks. Channels with different phases and
+    /// complementary outputs with dead time form phase-shifted complementary pairs for interleaved
+    /// converters or phase-shifted full bridges. Use `set_pwm_mode` to return to normal PWM.
+    #[inline]
+    pub fn set_phase_shifted(&mut self, channel: Channel, phase: u16) {
+        let channel = PINS::check_used(channel);
+        self.tim
+            .preload_output_channel_in_mode(channel, Ocm::Toggle);
+        TIM::set_cc_value(channel as u8, phase.into());
+    }
+
+    /// Switch output of channel `channel` back to PWM mode
+    #[inline]
+    pub fn set_pwm_mode(&mut self, channel: Channel) {
+        self.tim
+            .preload_output_channel_in_mode(PINS::check_used(channel), Ocm::PwmMode1);
+    }
+
+    /// Force output of channel `channel` to active level, duty cycle is ignored
+    ///
+    /// Use `set_pwm_mode` to return to PWM.
+    #[inline]
+    pub fn force_active(&mut self, channel: Channel) {
+        TIM::set_output_mode(PINS::check_used(channel) as u8, Ocm::ForceActive);
+    }
+
+    /// Force output of channel `channel` to inactive level, duty cycle is ignored
+    ///
+    /// Use `set_pwm_mode` to return to PWM.
+    #[inline]
+    pub fn force_inactive(&mut self, channel: Channel) {
+        TIM::set_output_mode(PINS::check_used(channel) as u8, Ocm::ForceInactive);
+    }
+
+    /// Enable or disable duty cycle preload of channel `channel`
+    ///
+    /// With preload (default) new duty cycle is applied at the next update event,
+    /// so the current period is not glitched. Without it the value is used immediately.
+    #[inline]
+    pub fn set_preload(&mut self, channel: Channel, b: bool) {
+        TIM::enable_cc_preload(PINS::check_used(channel) as u8, b);
+    }
+
+    /// Is duty cycle preload of channel `channel` enabled
+    #[inline]
+    pub fn is_preload_enabled(&self, channel: Channel) -> bool {
+        TIM::is_cc_preload_enabled(PINS::check_used(channel) as u8)
+    }
+
     /// Get the maximum duty cycle value of the timer
     ///
-    /// If `0` returned means max_duty is 2^16
+    /// If `0` returned means max_duty is 2^16.
+    /// In center-aligned mode it is equal to auto-reload value
     pub fn get_max_duty(&self) -> u16 {
-        (TIM::read_auto_reload() as u16).wrapping_add(1)
+        max_duty::<TIM>()
     }
 
     /// Get the PWM frequency of the timer in Hertz
@@ -466,16 +643,50 @@ where
         let clk = self.clk;
         let psc = self.tim.read_prescaler() as u32;
         let arr = TIM::read_auto_reload();
+        let period_ticks = if TIM::is_center_aligned() {
+            2 * arr
+        } else {
+            arr + 1
+        };
 
         // Length in ms of an internal clock pulse
-        clk / ((psc + 1) * (arr + 1))
+        clk / ((psc + 1) * period_ticks)
+    }
+
+    /// Change the PWM frequency keeping duty cycles of used channels
+    ///
+    /// New period and compare values are applied together at the next update event,
+    /// so the current period is not cut.
+    /// Returns `Error::WrongAutoReload` if `freq` is zero or can't be reached with timer clock.
+    pub fn set_frequency(&mut self, freq: Hertz) -> Result<(), Error> {
+        if freq.raw() == 0 || freq > self.clk {
+            return Err(Error::WrongAutoReload);
+        }
+        let (psc, arr) = if TIM::is_center_aligned() {
+            compute_arr_presc_center(freq.raw(), self.clk.raw())
+        } else {
+            compute_arr_presc(freq.raw(), self.clk.raw())
+        };
+        let old = duty_scale::<TIM>();
+        self.tim.disable_update_event(true);
+        let res = self.tim.set_auto_reload(arr);
+        if res.is_ok() {
+            self.tim.set_prescaler(psc);
+            scale_duties::<TIM, PINS>(old, duty_scale::<TIM>());
+        }
+        self.tim.disable_update_event(false);
+        res
     }
 
     /// Set the PWM frequency for the timer in Hertz
     pub fn set_period(&mut self, period: Hertz) {
         let clk = self.clk;
 
-        let (psc, arr) = compute_arr_presc(period.raw(), clk.raw());
+        let (psc, arr) = if TIM::is_center_aligned() {
+            compute_arr_presc_center(period.raw(), clk.raw())
+        } else {
+            compute_arr_presc(period.raw(), clk.raw())
+        };
         self.tim.set_prescaler(psc);
         self.tim.set_auto_reload(arr).unwrap();
         self.tim.cnt_reset();
@@ -486,6 +697,12 @@ where
     pub fn set_complementary_polarity(&mut self, channel: Channel, p: Polarity) {
         TIM::set_nchannel_polarity(PINS::check_complementary_used(channel) as u8, p);
     }
+
+    /// Get the polarity of the active state for the complementary PWM output of the advanced timer on channel `channel`
+    #[inline]
+    pub fn get_complementary_polarity(&self, channel: Channel) -> Polarity {
+        TIM::read_nchannel_polarity(PINS::check_complementary_used(channel) as u8)
+    }
 }
 
 impl<TIM, PINS> PwmHz<TIM, PINS>
@@ -537,6 +754,23 @@ where
         TIM::read_dtg_value()
     }
 
+    /// Set dead time duration, rounded up to the nearest available value
+    #[inline]
+    pub fn set_dead_time_duration(&mut self, dead_time: NanosDurationU32) {
+        // DTS ticks have timer input clock frequency
+        let ticks =
+            (dead_time.ticks() as u64 * self.clk.raw() as u64 + 999_999_999) / 1_000_000_000;
+        self.set_dead_time(ticks.min(u16::MAX as u64) as u16);
+    }
+
+    /// Return dead time duration
+    #[inline]
+    pub fn get_dead_time_duration(&self) -> NanosDurationU32 {
+        NanosDurationU32::from_ticks(
+            (self.get_dead_time() as u64 * 1_000_000_000 / self.clk.raw() as u64) as u32,
+        )
+    }
+
     /// Set the pin idle state
     #[inline]
     pub fn set_idle_state(&mut self, channel: Channel, s: IdleState) {
@@ -548,6 +782,83 @@ where
     pub fn set_complementary_idle_state(&mut self, channel: Channel, s: IdleState) {
         TIM::idle_state(PINS::check_complementary_used(channel) as u8, true, s);
     }
+
+    /// Get the pin idle state
+    #[inline]
+    pub fn get_idle_state(&self, channel: Channel) -> IdleState {
+        TIM::read_idle_state(PINS::check_used(channel) as u8, false)
+    }
+
+    /// Get the complementary pin idle state
+    #[inline]
+    pub fn get_complementary_idle_state(&self, channel: Channel) -> IdleState {
+        TIM::read_idle_state(PINS::check_complementary_used(channel) as u8, true)
+    }
+
+    /// Select off-state of enabled outputs
+    ///
+    /// If `idle` is set, outputs are driven to their idle states while main output is disabled
+    /// (OSSI), otherwise they are released. `run` does the same for inactive outputs
+    /// of enabled channels while main output is enabled (OSSR).
+    #[inline]
+    pub fn set_off_state(&mut self, run: bool, idle: bool) {
+        TIM::set_off_state(run, idle);
+    }
+
+    /// Enable all outputs (MOE)
+    ///
+    /// Outputs are enabled on start. They are disabled by hardware on break event
+    /// and can be enabled again with this method if automatic output enable is not used.
+    #[inline]
+    pub fn enable_main_output(&mut self) {
+        self.tim.enable_main_output(true);
+    }
+
+    /// Disable all outputs (MOE), pins are put to their idle states
+    #[inline]
+    pub fn disable_main_output(&mut self) {
+        self.tim.enable_main_output(false);
+    }
+
+    /// Returns `true` if outputs are enabled (MOE is set)
+    #[inline]
+    pub fn is_main_output_enabled(&self) -> bool {
+        TIM::is_main_output_enabled()
+    }
+
+    /// Enable break input with active `polarity`
+    ///
+    /// On break all outputs are disabled. If `automatic_output` is set, they are enabled
+    /// again at the next update event after break input becomes inactive.
+    /// Break event can be listened with [`Event::Break`](super::Event::Break).
+    ///
+    /// Returned [`BreakInput`] owns the pin until it is passed to `disable_break_input`.
+    #[inline]
+    pub fn enable_break_input(
+        &mut self,
+        pin: impl Into<TIM::Bkin>,
+        polarity: Polarity,
+        automatic_output: bool,
+    ) -> BreakInput<TIM>
+    where
+        TIM: BkinPin,
+    {
+        let pin = pin.into();
+        TIM::enable_break(polarity, automatic_output);
+        BreakInput { pin }
+    }
+
+    /// Disable break input and return its pin
+    ///
+    /// Automatic output enable (AOE) setting is not changed.
+    #[inline]
+    pub fn disable_break_input(&mut self, brk: BreakInput<TIM>) -> TIM::Bkin
+    where
+        TIM: BkinPin,
+    {
+        TIM::disable_break();
+        brk.pin
+    }
 }
 
 pub struct Pwm<TIM, PINS, const FREQ: u32>
@@ -555,8 +866,8 @@ where
     TIM: Instance + WithPwm,
     PINS: Pins<TIM>,
 {
-    timer: FTimer<TIM, FREQ>,
-    _pins: PhantomData<PINS>,
+    pub(super) timer: FTimer<TIM, FREQ>,
+    pub(super) _pins: PhantomData<PINS>,
 }
 
 impl<TIM, PINS, const FREQ: u32> Pwm<TIM, PINS, FREQ>
@@ -623,7 +934,9 @@ impl<TIM: Instance + WithPwm, const FREQ: u32> FTimer<TIM, FREQ> {
         // might as well enable for the auto-reload too
         self.tim.enable_preload(true);
 
-        self.tim.set_auto_reload(time.ticks() - 1).unwrap();
+        self.tim
+            .set_auto_reload(period_to_arr::<TIM>(time.ticks()))
+            .unwrap();
 
         // Trigger update event to load the registers
         self.tim.trigger_update();
@@ -637,6 +950,25 @@ impl<TIM: Instance + WithPwm, const FREQ: u32> FTimer<TIM, FREQ> {
     }
 }
 
+impl<TIM: Instance + CenterAligned, const FREQ: u32> FTimer<TIM, FREQ> {
+    /// Same as [`pwm`](Self::pwm) with selected counter `alignment`
+    ///
+    /// In center-aligned modes counter counts up to auto-reload value and back,
+    /// so PWM is symmetric and maximum duty is half of `time` ticks.
+    pub fn pwm_aligned<PINS>(
+        mut self,
+        pins: PINS,
+        time: TimerDurationU32<FREQ>,
+        alignment: Alignment,
+    ) -> Pwm<TIM, PINS, FREQ>
+    where
+        PINS: Pins<TIM>,
+    {
+        self.tim.set_alignment(alignment);
+        self.pwm(pins, time)
+    }
+}
+
 impl<TIM, PINS, const FREQ: u32> Pwm<TIM, PINS, FREQ>
 where
     TIM: Instance + WithPwm,
@@ -660,6 +992,12 @@ where
         TIM::set_channel_polarity(PINS::check_used(channel) as u8, p);
     }
 
+    /// Get the polarity of the active state for the primary PWM output of the timer on channel `channel`
+    #[inline]
+    pub fn get_polarity(&self, channel: Channel) -> Polarity {
+        TIM::read_channel_polarity(PINS::check_used(channel) as u8)
+    }
+
     /// Get the current duty cycle of the timer on channel `channel`
     #[inline]
     pub fn get_duty(&self, channel: Channel) -> u16 {
@@ -677,6 +1015,59 @@ where
         TIM::set_cc_value(PINS::check_used(channel) as u8, duty.into())
     }
 
+    /// Switch output of channel `channel` to phase-shifted square wave
+    ///
+    /// STM32F4 timers have no combined PWM modes, so phase-shifted outputs are made with toggle mode:
+    /// output toggles each time the counter reaches `phase`, giving 50% duty cycle at half
+    /// of PWM frequency, delayed by `phase` ticks. Channels with different phases and
+    /// complementary outputs with dead time form phase-shifted complementary pairs for interleaved
+    /// converters or phase-shifted full bridges. Use `set_pwm_mode` to return to normal PWM.
+    #[inline]
+    pub fn set_phase_shifted(&mut self, channel: Channel, phase: u16) {
+        let channel = PINS::check_used(channel);
+        self.tim
+            .preload_output_channel_in_mode(channel, Ocm::Toggle);
+        TIM::set_cc_value(channel as u8, phase.into());
+    }
+
+    /// Switch output of channel `channel` back to PWM mode
+    #[inline]
+    pub fn set_pwm_mode(&mut self, channel: Channel) {
+        self.tim
+            .preload_output_channel_in_mode(PINS::check_used(channel), Ocm::PwmMode1);
+    }
+
+    /// Force output of channel `channel` to active level, duty cycle is ignored
+    ///
+    /// Use `set_pwm_mode` to return to PWM.
+    #[inline]
+    pub fn force_active(&mut self, channel: Channel) {
+        TIM::set_output_mode(PINS::check_used(channel) as u8, Ocm::ForceActive);
+    }
+
+    /// Force output of channel `channel` to inactive level, duty cycle is ignored
+    ///
+    /// Use `set_pwm_mode` to return to PWM.
+    #[inline]
+    pub fn force_inactive(&mut self, channel: Channel) {
+        TIM::set_output_mode(PINS::check_used(channel) as u8, Ocm::ForceInactive);
+    }
+
+    /// Enable or disable duty cycle preload of channel `channel`
+    ///
+    /// With preload (default) new duty cycle is applied at the next update event,
+    /// so the current period is not glitched. Without it the value is used immediately.
+    #[inline]
+    pub fn set_preload(&mut self, channel: Channel, b: bool) {
+        TIM::enable_cc_preload(PINS::check_used(channel) as u8, b);
+    }
+
+    /// Is duty cycle preload of channel `channel` enabled
+    #[inline]
+    pub fn is_preload_enabled(&self, channel: Channel) -> bool {
+        TIM::is_cc_preload_enabled(PINS::check_used(channel) as u8)
+    }
+
     /// Set the duty cycle of the timer on channel `channel` from a duration
     #[inline]
     pub fn set_duty_time(&mut self, channel: Channel, duty: TimerDurationU32<FREQ>) {
@@ -685,26 +1076,61 @@ where
 
     /// Get the maximum duty cycle value of the timer
     ///
-    /// If `0` returned means max_duty is 2^16
+    /// If `0` returned means max_duty is 2^16.
+    /// In center-aligned mode it is equal to auto-reload value
     pub fn get_max_duty(&self) -> u16 {
-        (TIM::read_auto_reload() as u16).wrapping_add(1)
+        max_duty::<TIM>()
     }
 
     /// Get the PWM frequency of the timer as a duration
     pub fn get_period(&self) -> TimerDurationU32<FREQ> {
-        TimerDurationU32::from_ticks(TIM::read_auto_reload() + 1)
+        let arr = TIM::read_auto_reload();
+        TimerDurationU32::from_ticks(if TIM::is_center_aligned() {
+            2 * arr
+        } else {
+            arr + 1
+        })
+    }
+
+    /// Change the PWM frequency keeping duty cycles of used channels
+    ///
+    /// New period and compare values are applied together at the next update event,
+    /// so the current period is not cut.
+    /// Returns `Error::WrongAutoReload` if `freq` is zero, above `FREQ` or too low for the timer.
+    pub fn set_frequency(&mut self, freq: Hertz) -> Result<(), Error> {
+        if freq.raw() == 0 || freq.raw() > FREQ {
+            return Err(Error::WrongAutoReload);
+        }
+        let old = duty_scale::<TIM>();
+        self.tim.disable_update_event(true);
+        let res = self
+            .tim
+            .set_auto_reload(period_to_arr::<TIM>(FREQ / freq.raw()));
+        if res.is_ok() {
+            scale_duties::<TIM, PINS>(old, duty_scale::<TIM>());
+        }
+        self.tim.disable_update_event(false);
+        res
     }
 
     /// Set the PWM frequency for the timer from a duration
     pub fn set_period(&mut self, period: TimerDurationU32<FREQ>) {
-        self.tim.set_auto_reload(period.ticks() - 1).unwrap();
+        self.tim
+            .set_auto_reload(period_to_arr::<TIM>(period.ticks()))
+            .unwrap();
         self.tim.cnt_reset();
     }
 
     /// Set the polarity of the active state for the complementary PWM output of the advanced timer on channel `channel`
     #[inline]
     pub fn set_complementary_polarity(&mut self, channel: Channel, p: Polarity) {
-        TIM::set_channel_polarity(PINS::check_complementary_used(channel) as u8, p);
+        TIM::set_nchannel_polarity(PINS::check_complementary_used(channel) as u8, p);
+    }
+
+    /// Get the polarity of the active state for the complementary PWM output of the advanced timer on channel `channel`
+    #[inline]
+    pub fn get_complementary_polarity(&self, channel: Channel) -> Polarity {
+        TIM::read_nchannel_polarity(PINS::check_complementary_used(channel) as u8)
     }
 }
 
@@ -757,6 +1183,22 @@ where
         TIM::read_dtg_value()
     }
 
+    /// Set dead time duration, rounded up to the nearest available value
+    #[inline]
+    pub fn set_dead_time_duration(&mut self, dead_time: TimerDurationU32<FREQ>) {
+        // DTS ticks have timer input clock frequency, before prescaler
+        let ticks = dead_time.ticks() * (self.tim.read_prescaler() as u32 + 1);
+        self.set_dead_time(ticks.min(u16::MAX as u32) as u16);
+    }
+
+    /// Return dead time duration, rounded down
+    #[inline]
+    pub fn get_dead_time_duration(&self) -> TimerDurationU32<FREQ> {
+        TimerDurationU32::from_ticks(
+            self.get_dead_time() as u32 / (self.tim.read_prescaler() as u32 + 1),
+        )
+    }
+
     /// Set the pin idle state
     #[inline]
     pub fn set_idle_state(&mut self, channel: Channel, s: IdleState) {
@@ -768,6 +1210,128 @@ where
     pub fn set_complementary_idle_state(&mut self, channel: Channel, s: IdleState) {
         TIM::idle_state(PINS::check_complementary_used(channel) as u8, true, s);
     }
+
+    /// Get the pin idle state
+    #[inline]
+    pub fn get_idle_state(&self, channel: Channel) -> IdleState {
+        TIM::read_idle_state(PINS::check_used(channel) as u8, false)
+    }
+
+    /// Get the complementary pin idle state
+    #[inline]
+    pub fn get_complementary_idle_state(&self, channel: Channel) -> IdleState {
+        TIM::read_idle_state(PINS::check_complementary_used(channel) as u8, true)
+    }
+
+    /// Select off-state of enabled outputs
+    ///
+    /// If `idle` is set, outputs are driven to their idle states while main output is disabled
+    /// (OSSI), otherwise they are released. `run` does the same for inactive outputs
+    /// of enabled channels while main output is enabled (OSSR).
+    #[inline]
+    pub fn set_off_state(&mut self, run: bool, idle: bool) {
+        TIM::set_off_state(run, idle);
+    }
+
+    /// Enable all outputs (MOE)
+    ///
+    /// Outputs are enabled on start. They are disabled by hardware on break event
+    /// and can be enabled again with this method if automatic output enable is not used.
+    #[inline]
+    pub fn enable_main_output(&mut self) {
+        self.tim.enable_main_output(true);
+    }
+
+    /// Disable all outputs (MOE), pins are put to their idle states
+    #[inline]
+    pub fn disable_main_output(&mut self) {
+        self.tim.enable_main_output(false);
+    }
+
+    /// Returns `true` if outputs are enabled (MOE is set)
+    #[inline]
+    pub fn is_main_output_enabled(&self) -> bool {
+        TIM::is_main_output_enabled()
+    }
+
+    /// Enable break input with active `polarity`
+    ///
+    /// On break all outputs are disabled. If `automatic_output` is set, they are enabled
+    /// again at the next update event after break input becomes inactive.
+    /// Break event can be listened with [`Event::Break`](super::Event::Break).
+    ///
+    /// Returned [`BreakInput`] owns the pin until it is passed to `disable_break_input`.
+    #[inline]
+    pub fn enable_break_input(
+        &mut self,
+        pin: impl Into<TIM::Bkin>,
+        polarity: Polarity,
+        automatic_output: bool,
+    ) -> BreakInput<TIM>
+    where
+        TIM: BkinPin,
+    {
+        let pin = pin.into();
+        TIM::enable_break(polarity, automatic_output);
+        BreakInput { pin }
+    }
+
+    /// Disable break input and return its pin
+    ///
+    /// Automatic output enable (AOE) setting is not changed.
+    #[inline]
+    pub fn disable_break_input(&mut self, brk: BreakInput<TIM>) -> TIM::Bkin
+    where
+        TIM: BkinPin,
+    {
+        TIM::disable_break();
+        brk.pin
+    }
+}
+
+#[inline(always)]
+fn max_duty<TIM: WithPwm>() -> u16 {
+    let arr = TIM::read_auto_reload() as u16;
+    if TIM::is_center_aligned() {
+        arr
+    } else {
+        arr.wrapping_add(1)
+    }
+}
+
+#[inline(always)]
+/// Compare value of 100% duty cycle
+fn duty_scale<TIM: WithPwm>() -> u32 {
+    let arr = TIM::read_auto_reload();
+    if TIM::is_center_aligned() {
+        arr
+    } else {
+        arr + 1
+    }
+}
+/// Scales compare values of used channels by `new / old` to keep their duty cycles
+fn scale_duties<TIM: WithPwm, PINS: Pins<TIM>>(old: u32, new: u32) {
+    if old == 0 {
+        // Timer period was not configured, there are no duty cycles to keep
+        return;
+    }
+    for (c, used) in [PINS::C1, PINS::C2, PINS::C3, PINS::C4]
+        .into_iter()
+        .enumerate()
+    {
+        let c = c as u8;
+        if used && c < TIM::CH_NUMBER {
+            let duty = TIM::read_cc_value(c) as u64 * new as u64 / old as u64;
+            TIM::set_cc_value(c, duty as u32);
+        }
+    }
+}
+fn period_to_arr<TIM: WithPwm>(ticks: u32) -> u32 {
+    if TIM::is_center_aligned() {
+        ticks / 2
+    } else {
+        ticks - 1
+    }
 }
 
 /// Convert number dead time ticks to raw DTG register bits.
@@ -3,7 +3,7 @@ use crate::gpio::PushPull;
 use crate::pac;
 use core::convert::TryFrom;
 use core::ops::{Deref, DerefMut};
-use fugit::HertzU32 as Hertz;
+use fugit::{HertzU32 as Hertz, MicrosDurationU32};
 
 /// Represents a TIMer configured as a PWM input.
 /// This peripheral will emit an interrupt on CC2 events, which occurs at two times in this mode:
@@ -183,6 +183,32 @@ macro_rules! hal {
             pub fn is_valid_capture(&self) -> bool {
                 self.get_duty_cycle_clocks() != self.get_period_clocks()
             }
+            /// Frequency of the timer counter, used to convert clock cycles into time
+            pub fn counter_clock(&self) -> Hertz {
+                self.clk / (self.tim.read_prescaler() as u32 + 1)
+            }
+            /// Frequency of PWM signal, `None` if period was not captured yet
+            pub fn get_frequency(&self) -> Option<Hertz> {
+                let period_clocks: u32 = self.get_period_clocks().into();
+                if period_clocks == 0 {
+                    return None;
+                }
+                Some(self.counter_clock() / period_clocks)
+            }
+            /// Period of PWM signal in microseconds
+            pub fn get_period(&self) -> MicrosDurationU32 {
+                let period_clocks: u32 = self.get_period_clocks().into();
+                MicrosDurationU32::from_ticks(
+                    (period_clocks as u64 * 1_000_000 / self.counter_clock().raw() as u64) as u32,
+                )
+            }
+            /// Duration of the active part of PWM signal in microseconds
+            pub fn get_duty_time(&self) -> MicrosDurationU32 {
+                let duty_clocks: u32 = self.get_duty_cycle_clocks().into();
+                MicrosDurationU32::from_ticks(
+                    (duty_clocks as u64 * 1_000_000 / self.counter_clock().raw() as u64) as u32,
+                )
+            }
         }
     };
 }
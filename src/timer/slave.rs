@@ -0,0 +1,201 @@
+//! Timer synchronization
+//!
+//! Master timer sends its trigger output (TRGO, see `set_master_mode`) to internal trigger inputs
+//! (ITRx) of other timers. Slave timer can be reset, gated, started or clocked by the trigger.
+//!
+//! Connections between timers are fixed, [`InternalTrigger`] is implemented for valid pairs,
+//! so a wrong connection is a compile error:
+//!
+//! ```rust,ignore
+//! // TIM2 counts overflows of TIM3
+//! let mut master = Timer::new(dp.TIM3, &clocks);
+//! master.set_master_mode(pac::tim3::cr2::MMS::Update);
+//! let mut slave = Timer::new(dp.TIM2, &clocks);
+//! slave.set_slave_of::<pac::TIM3>(SlaveMode::ExternalClock);
+//! ```
+
+use super::{SlaveTimer, WithEtr};
+use crate::pac;
+
+/// Slave mode controller mode (SMS)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum SlaveMode {
+    Disabled = 0,
+    /// Encoder mode 1, counts on TI2FP1 edges
+    Encoder1 = 1,
+    /// Encoder mode 2, counts on TI1FP2 edges
+    Encoder2 = 2,
+    /// Encoder mode 3, counts on both TI1FP1 and TI2FP2 edges
+    Encoder3 = 3,
+    /// Counter is reinitialized on rising edge of trigger
+    Reset = 4,
+    /// Counter runs while trigger is high
+    Gated = 5,
+    /// Counter starts on rising edge of trigger
+    Trigger = 6,
+    /// Rising edges of trigger clock the counter
+    ExternalClock = 7,
+}
+
+/// Trigger input of slave mode controller (TS)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum TriggerSource {
+    Itr0 = 0,
+    Itr1 = 1,
+    Itr2 = 2,
+    Itr3 = 3,
+    /// Both edges of TI1
+    Ti1FEdge = 4,
+    /// Filtered timer input 1
+    Ti1Fp1 = 5,
+    /// Filtered timer input 2
+    Ti2Fp2 = 6,
+    /// External trigger input
+    Etrf = 7,
+}
+
+/// Timer `MASTER` trigger output is connected to `ITR` input of this timer
+pub trait InternalTrigger<MASTER>: SlaveTimer {
+    const ITR: TriggerSource;
+}
+
+macro_rules! slave {
+    ($TIM:ty) => {
+        impl SlaveTimer for $TIM {
+            #[inline(always)]
+            fn slave_mode(&mut self, mode: SlaveMode, trigger: TriggerSource) {
+                // Trigger must be selected while slave mode is disabled
+                self.smcr().modify(|_, w| unsafe { w.sms().bits(0) });
+                self.smcr()
+                    .modify(|_, w| unsafe { w.ts().bits(trigger as u8) });
+                self.smcr()
+                    .modify(|_, w| unsafe { w.sms().bits(mode as u8) });
+            }
+
+            #[inline(always)]
+            fn master_slave_sync(&mut self, b: bool) {
+                self.smcr().modify(|_, w| w.msm().bit(b));
+            }
+        }
+    };
+    ($TIM:ty, etr) => {
+        slave!($TIM);
+
+        impl WithEtr for $TIM {
+            #[inline(always)]
+            fn external_trigger(&mut self, prescaler: u8, filter: u8, inverted: bool) {
+                self.smcr().modify(|_, w| unsafe {
+                    w.etps().bits(prescaler);
+                    w.etf().bits(filter);
+                    w.etp().bit(inverted)
+                });
+            }
+
+            #[inline(always)]
+            fn enable_external_clock2(&mut self, b: bool) {
+                self.smcr().modify(|_, w| w.ece().bit(b));
+            }
+        }
+    };
+}
+
+macro_rules! itr {
+    ($TIM:ty: $ITR:ident, $MASTER:ty) => {
+        impl InternalTrigger<$MASTER> for $TIM {
+            const ITR: TriggerSource = TriggerSource::$ITR;
+        }
+    };
+}
+
+#[cfg(feature = "tim1")]
+slave!(pac::TIM1, etr);
+#[cfg(feature = "tim2")]
+slave!(pac::TIM2, etr);
+#[cfg(feature = "tim3")]
+slave!(pac::TIM3, etr);
+#[cfg(feature = "tim4")]
+slave!(pac::TIM4, etr);
+#[cfg(feature = "tim5")]
+slave!(pac::TIM5);
+#[cfg(feature = "tim8")]
+slave!(pac::TIM8, etr);
+#[cfg(feature = "tim9")]
+slave!(pac::TIM9);
+#[cfg(feature = "tim12")]
+slave!(pac::TIM12);
+
+#[cfg(all(feature = "tim1", feature = "tim5"))]
+itr!(pac::TIM1: Itr0, pac::TIM5);
+#[cfg(all(feature = "tim1", feature = "tim2"))]
+itr!(pac::TIM1: Itr1, pac::TIM2);
+#[cfg(all(feature = "tim1", feature = "tim3"))]
+itr!(pac::TIM1: Itr2, pac::TIM3);
+#[cfg(all(feature = "tim1", feature = "tim4"))]
+itr!(pac::TIM1: Itr3, pac::TIM4);
+
+#[cfg(all(feature = "tim2", feature = "tim1"))]
+itr!(pac::TIM2: Itr0, pac::TIM1);
+#[cfg(all(feature = "tim2", feature = "tim8"))]
+itr!(pac::TIM2: Itr1, pac::TIM8);
+#[cfg(all(feature = "tim2", feature = "tim3"))]
+itr!(pac::TIM2: Itr2, pac::TIM3);
+#[cfg(all(feature = "tim2", feature = "tim4"))]
+itr!(pac::TIM2: Itr3, pac::TIM4);
+
+#[cfg(all(feature = "tim3", feature = "tim1"))]
+itr!(pac::TIM3: Itr0, pac::TIM1);
+#[cfg(all(feature = "tim3", feature = "tim2"))]
+itr!(pac::TIM3: Itr1, pac::TIM2);
+#[cfg(all(feature = "tim3", feature = "tim5"))]
+itr!(pac::TIM3: Itr2, pac::TIM5);
+#[cfg(all(feature = "tim3", feature = "tim4"))]
+itr!(pac::TIM3: Itr3, pac::TIM4);
+
+#[cfg(all(feature = "tim4", feature = "tim1"))]
+itr!(pac::TIM4: Itr0, pac::TIM1);
+#[cfg(all(feature = "tim4", feature = "tim2"))]
+itr!(pac::TIM4: Itr1, pac::TIM2);
+#[cfg(all(feature = "tim4", feature = "tim3"))]
+itr!(pac::TIM4: Itr2, pac::TIM3);
+#[cfg(all(feature = "tim4", feature = "tim8"))]
+itr!(pac::TIM4: Itr3, pac::TIM8);
+
+#[cfg(all(feature = "tim5", feature = "tim2"))]
+itr!(pac::TIM5: Itr0, pac::TIM2);
+#[cfg(all(feature = "tim5", feature = "tim3"))]
+itr!(pac::TIM5: Itr1, pac::TIM3);
+#[cfg(all(feature = "tim5", feature = "tim4"))]
+itr!(pac::TIM5: Itr2, pac::TIM4);
+#[cfg(all(feature = "tim5", feature = "tim8"))]
+itr!(pac::TIM5: Itr3, pac::TIM8);
+
+#[cfg(all(feature = "tim8", feature = "tim1"))]
+itr!(pac::TIM8: Itr0, pac::TIM1);
+#[cfg(all(feature = "tim8", feature = "tim2"))]
+itr!(pac::TIM8: Itr1, pac::TIM2);
+#[cfg(all(feature = "tim8", feature = "tim4"))]
+itr!(pac::TIM8: Itr2, pac::TIM4);
+#[cfg(all(feature = "tim8", feature = "tim5"))]
+itr!(pac::TIM8: Itr3, pac::TIM5);
+
+#[cfg(all(feature = "tim9", feature = "tim2"))]
+itr!(pac::TIM9: Itr0, pac::TIM2);
+#[cfg(all(feature = "tim9", feature = "tim3"))]
+itr!(pac::TIM9: Itr1, pac::TIM3);
+#[cfg(all(feature = "tim9", feature = "tim10"))]
+itr!(pac::TIM9: Itr2, pac::TIM10);
+#[cfg(all(feature = "tim9", feature = "tim11"))]
+itr!(pac::TIM9: Itr3, pac::TIM11);
+
+#[cfg(all(feature = "tim12", feature = "tim4"))]
+itr!(pac::TIM12: Itr0, pac::TIM4);
+#[cfg(all(feature = "tim12", feature = "tim5"))]
+itr!(pac::TIM12: Itr1, pac::TIM5);
+#[cfg(all(feature = "tim12", feature = "tim13"))]
+itr!(pac::TIM12: Itr2, pac::TIM13);
+#[cfg(all(feature = "tim12", feature = "tim14"))]
+itr!(pac::TIM12: Itr3, pac::TIM14);
@@ -0,0 +1,144 @@
+//! Uptime counting with SysTick
+//!
+//! [`SysUptime`] configures SysTick to interrupt with 1 kHz rate and counts milliseconds in its
+//! exception handler, which is defined by HAL under `systick-uptime` feature. So timekeeping
+//! is available without using any TIM peripheral. [`uptime`] and [`SysUptime::now`] can be
+//! called from any context, but time only advances while SysTick exception is serviced.
+//! Delays need it too: in a critical section or in a handler with higher priority than SysTick,
+//! only one missed millisecond is counted, so longer delays take longer or never end.
+//!
+//! The feature can't be used together with other SysTick handlers (like `rtic-systick`).
+//!
+//! ```rust,ignore
+//! let mut uptime = cp.SYST.uptime(&clocks);
+//! let start = SysUptime::now();
+//! uptime.delay_us(50);
+//! let elapsed = SysUptime::now() - start;
+//! ```
+
+#[cfg(feature = "rtic-systick")]
+compile_error!("`systick-uptime` and `rtic-systick` features both define `SysTick` handler, enable only one of them");
+
+use super::Timer;
+use crate::rcc::Clocks;
+use core::cell::Cell;
+use core::ops::{Deref, DerefMut};
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::{SCB, SYST};
+use fugit::{MillisDurationU64, TimerInstantU64};
+
+/// Instant with millisecond resolution
+pub type SysInstant = TimerInstantU64<1_000>;
+
+static MILLIS: Mutex<Cell<u64>> = Mutex::new(Cell::new(0));
+
+#[no_mangle]
+#[allow(non_snake_case)]
+unsafe extern "C" fn SysTick() {
+    cortex_m::interrupt::free(|cs| {
+        let millis = MILLIS.borrow(cs);
+        millis.set(millis.get() + 1);
+    });
+}
+
+/// SysTick counting milliseconds since start
+pub struct SysUptime(Timer<SYST>);
+
+impl Deref for SysUptime {
+    type Target = Timer<SYST>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SysUptime {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Timer<SYST> {
+    /// Starts counting uptime from zero
+    pub fn uptime(mut self) -> SysUptime {
+        self.tim.disable_counter();
+        self.tim.set_reload(self.clk.raw() / 1_000 - 1);
+        self.tim.clear_current();
+        cortex_m::interrupt::free(|cs| MILLIS.borrow(cs).set(0));
+        self.tim.enable_interrupt();
+        self.tim.enable_counter();
+        SysUptime(self)
+    }
+}
+
+impl SysUptime {
+    /// Current time since start
+    pub fn now() -> SysInstant {
+        SysInstant::from_ticks(Self::millis_and_current().0)
+    }
+
+    /// Milliseconds since start and SysTick current value
+    fn millis_and_current() -> (u64, u32) {
+        cortex_m::interrupt::free(|cs| {
+            let millis = MILLIS.borrow(cs).get();
+            let current = SYST::get_current();
+            // Handler is blocked by critical section, count wrap it hasn't processed yet
+            if SCB::is_pendst_pending() {
+                (millis + 1, SYST::get_current())
+            } else {
+                (millis, current)
+            }
+        })
+    }
+
+    /// Clock cycles since start
+    fn cycles(&self) -> u64 {
+        let (millis, current) = Self::millis_and_current();
+        let reload = SYST::get_reload() as u64;
+        millis * (reload + 1) + (reload - current as u64)
+    }
+
+    /// Stops counting and releases the timer
+    pub fn release(mut self) -> Timer<SYST> {
+        self.0.tim.disable_interrupt();
+        self.0.tim.disable_counter();
+        self.0
+    }
+}
+
+/// Time since [`SysUptime`] was started
+pub fn uptime() -> MillisDurationU64 {
+    SysUptime::now().duration_since_epoch()
+}
+
+impl embedded_hal::delay::DelayNs for SysUptime {
+    fn delay_ns(&mut self, ns: u32) {
+        let cycles = (ns as u64 * self.clk.raw() as u64 + 999_999_999) / 1_000_000_000;
+        let start = self.cycles();
+        // Cycles go backwards if SysTick exception is blocked for more than 1 ms
+        while self.cycles().saturating_sub(start) < cycles {}
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        let cycles = (us as u64 * self.clk.raw() as u64 + 999_999) / 1_000_000;
+        let start = self.cycles();
+        while self.cycles().saturating_sub(start) < cycles {}
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        let start = SysUptime::now();
+        // Current millisecond is not complete
+        while SysUptime::now() - start <= MillisDurationU64::from_ticks(ms as u64) {}
+    }
+}
+
+/// Creates [`SysUptime`] from SysTick
+pub trait SysUptimeExt {
+    /// Starts counting uptime with SysTick clocked from HCLK
+    fn uptime(self, clocks: &Clocks) -> SysUptime;
+}
+
+impl SysUptimeExt for SYST {
+    fn uptime(self, clocks: &Clocks) -> SysUptime {
+        Timer::syst(self, clocks).uptime()
+    }
+}
@@ -0,0 +1,73 @@
+//! 64-bit timebase
+//!
+//! [`WideTimer`] chains two 32-bit timers (TIM2 and TIM5): the low one counts with fixed `FREQ`
+//! and its update event clocks the high one through internal trigger, so the pair never overflows
+//! in practice (more than 500 000 years at 1 MHz) and needs no interrupts.
+//!
+//! ```rust,ignore
+//! let wide = WideTimer::<_, _, 1_000_000>::new(dp.TIM2, dp.TIM5, &clocks);
+//! let start = wide.now();
+//! // ...
+//! let elapsed = wide.now() - start;
+//! ```
+
+use super::{FTimer, General, Instance, InternalTrigger, MasterTimer, SlaveMode, Timer};
+use crate::rcc::Clocks;
+use fugit::TimerInstantU64;
+
+/// Monotonic 64-bit counter built from two chained 32-bit timers
+pub struct WideTimer<LO, HI, const FREQ: u32> {
+    lo: FTimer<LO, FREQ>,
+    hi: HI,
+}
+
+impl<LO, HI, const FREQ: u32> WideTimer<LO, HI, FREQ>
+where
+    LO: Instance + MasterTimer + General<Width = u32>,
+    HI: Instance + InternalTrigger<LO> + General<Width = u32>,
+{
+    /// Starts `lo` counting with `FREQ` and `hi` counting its overflows
+    pub fn new(lo: LO, hi: HI, clocks: &Clocks) -> Self {
+        let mut lo = FTimer::<LO, FREQ>::new(lo, clocks);
+        let mut hi = Timer::new(hi, clocks).release();
+
+        // Load prescaler before `hi` is connected, update event would increment it
+        unsafe { lo.tim.set_auto_reload_unchecked(u32::MAX) };
+        lo.tim.trigger_update();
+
+        hi.set_prescaler(0);
+        unsafe { hi.set_auto_reload_unchecked(u32::MAX) };
+        hi.trigger_update();
+        hi.slave_mode(SlaveMode::ExternalClock, HI::ITR);
+        hi.reset_counter();
+
+        lo.tim.master_mode_update();
+        lo.tim.reset_counter();
+
+        hi.enable_counter(true);
+        lo.tim.enable_counter(true);
+
+        Self { lo, hi }
+    }
+
+    /// Current time
+    ///
+    /// Can be called from any context, counters are read without modification.
+    pub fn now(&self) -> TimerInstantU64<FREQ> {
+        loop {
+            let hi = self.hi.read_count();
+            let lo = self.lo.tim.read_count();
+            // `lo` belongs to `hi` if there was no overflow between reads
+            if self.hi.read_count() == hi {
+                return TimerInstantU64::from_ticks(((hi as u64) << 32) | lo as u64);
+            }
+        }
+    }
+
+    /// Stops both timers and returns them
+    pub fn release(mut self) -> (LO, HI) {
+        self.lo.tim.cr1_reset();
+        self.hi.cr1_reset();
+        (self.lo.release(), self.hi)
+    }
+}
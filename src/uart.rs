@@ -27,6 +27,12 @@ macro_rules! halUart {
         pub type $Tx<WORD = u8> = Tx<$UART, WORD>;
         pub type $Rx<WORD = u8> = Rx<$UART, WORD>;
 
+        impl crate::rcc::PeripheralClock for $UART {
+            fn kernel_clock(clocks: &crate::rcc::Clocks) -> crate::time::Hertz {
+                <$UART as crate::rcc::BusClock>::clock(clocks)
+            }
+        }
+
         impl Instance for $UART {
             type RegisterBlock = RegisterBlockUart;
 
@@ -42,7 +48,7 @@ macro_rules! halUart {
                     StopBits::STOP0P5 and StopBits::STOP1P5 aren't supported when using UART
                     STOP_A::STOP1 and STOP_A::STOP2 will be used, respectively
                 */
-                self.cr2().write(|w| {
+                self.cr2().modify(|_, w| {
                     w.stop().variant(match bits {
                         StopBits::STOP0P5 => STOP::Stop1,
                         StopBits::STOP1 => STOP::Stop1,
@@ -55,6 +61,18 @@ macro_rules! halUart {
             fn peri_address() -> u32 {
                 unsafe { (*Self::ptr()).peri_address() }
             }
+
+            fn error_stats() -> &'static crate::serial::stats::ErrorStats {
+                static STATS: crate::serial::stats::ErrorStats =
+                    crate::serial::stats::ErrorStats::new();
+                &STATS
+            }
+
+            #[cfg(feature = "async")]
+            fn wakers() -> &'static crate::serial::asynch::Wakers {
+                static WAKERS: crate::serial::asynch::Wakers = crate::serial::asynch::Wakers::new();
+                &WAKERS
+            }
         }
     };
 }
@@ -0,0 +1,38 @@
+//! Waker storage shared by interrupt driven async drivers
+
+use core::cell::RefCell;
+use core::task::Waker;
+
+use cortex_m::interrupt::{self, Mutex};
+
+/// Waker of the task waiting for an interrupt
+#[doc(hidden)]
+pub struct WakerRegistration(Mutex<RefCell<Option<Waker>>>);
+
+impl WakerRegistration {
+    pub const fn new() -> Self {
+        Self(Mutex::new(RefCell::new(None)))
+    }
+
+    pub(crate) fn register(&self, waker: &Waker) {
+        interrupt::free(|cs| {
+            let mut w = self.0.borrow(cs).borrow_mut();
+            match w.as_ref() {
+                Some(old) if old.will_wake(waker) => {}
+                _ => *w = Some(waker.clone()),
+            }
+        });
+    }
+
+    pub(crate) fn wake(&self) {
+        if let Some(waker) = interrupt::free(|cs| self.0.borrow(cs).borrow_mut().take()) {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for WakerRegistration {
+    fn default() -> Self {
+        Self::new()
+    }
+}